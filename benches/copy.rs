@@ -0,0 +1,48 @@
+//! Benchmarks for copying typed data into and out of buffers.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use data_buffer::VecCopy;
+
+fn copy(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Copy");
+
+    for &len in &[1_000usize, 1_000_000] {
+        let vec: Vec<f64> = (0..len).map(|i| i as f64).collect();
+        let buf = VecCopy::from_slice(&vec);
+
+        // Copying element by element through an iterator.
+        group.bench_function(BenchmarkId::new("iter_collect", len), |b| {
+            b.iter(|| black_box(buf.iter::<f64>().unwrap().copied().collect::<Vec<_>>()))
+        });
+
+        group.bench_function(BenchmarkId::new("copy_into_vec", len), |b| {
+            b.iter(|| black_box(buf.copy_into_vec::<f64>().unwrap()))
+        });
+
+        group.bench_function(BenchmarkId::new("extend", len), |b| {
+            b.iter(|| {
+                let mut out = VecCopy::with_type::<f64>();
+                out.extend(vec.iter().copied()).unwrap();
+                black_box(out)
+            })
+        });
+
+        group.bench_function(BenchmarkId::new("extend_from_slice", len), |b| {
+            b.iter(|| {
+                let mut out = VecCopy::with_type::<f64>();
+                out.extend_from_slice(&vec).unwrap();
+                black_box(out)
+            })
+        });
+
+        group.bench_function(BenchmarkId::new("from_slice", len), |b| {
+            b.iter(|| black_box(VecCopy::from_slice(&vec)))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, copy);
+criterion_main!(benches);
@@ -0,0 +1,40 @@
+//! Benchmarks for filling large buffers with a single value.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use data_buffer::VecCopy;
+
+const LEN: usize = 4_000_000;
+
+fn fill(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Fill");
+
+    for &value in &[0.0f32, 1.5] {
+        let mut vec = vec![0.5f32; LEN];
+        let mut buf = VecCopy::from_vec(vec.clone());
+
+        // The element-by-element loop previously used by `VecCopy::fill`.
+        group.bench_function(BenchmarkId::new("iter_mut", value), |b| {
+            b.iter(|| {
+                for v in buf.iter_mut::<f32>().unwrap() {
+                    *v = black_box(value);
+                }
+            })
+        });
+
+        group.bench_function(BenchmarkId::new("Vec", value), |b| {
+            b.iter(|| vec.fill(black_box(value)))
+        });
+
+        group.bench_function(BenchmarkId::new("VecCopy", value), |b| {
+            b.iter(|| {
+                buf.fill(black_box(value)).unwrap();
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, fill);
+criterion_main!(benches);
@@ -0,0 +1,30 @@
+//! Benchmarks for casting large numeric buffers serially and in parallel.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use data_buffer::VecCopy;
+
+fn par_cast(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Cast");
+    group.sample_size(20);
+
+    for &len in &[10_000usize, 1_000_000, 10_000_000] {
+        let buf = VecCopy::from_vec((0..len).map(|i| i as f32).collect::<Vec<_>>());
+
+        group.bench_function(BenchmarkId::new("cast_into_vec", len), |b| {
+            b.iter_with_setup(|| buf.clone(), |buf| black_box(buf.cast_into_vec::<f64>()))
+        });
+
+        group.bench_function(BenchmarkId::new("par_cast_into_vec", len), |b| {
+            b.iter_with_setup(
+                || buf.clone(),
+                |buf| black_box(buf.par_cast_into_vec::<f64>()),
+            )
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, par_cast);
+criterion_main!(benches);
@@ -0,0 +1,62 @@
+//! Benchmarks for adding elements to buffers one at a time.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use data_buffer::VecCopy;
+
+const LEN: usize = 100_000;
+
+fn push(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Push");
+
+    group.bench_function("Vec", |b| {
+        b.iter(|| {
+            let mut vec = Vec::with_capacity(LEN);
+            for i in 0..LEN {
+                vec.push(black_box(i as f32));
+            }
+            black_box(vec)
+        })
+    });
+
+    group.bench_function("push", |b| {
+        b.iter(|| {
+            let mut buf = VecCopy::with_capacity::<f32>(LEN);
+            for i in 0..LEN {
+                buf.push(black_box(i as f32)).unwrap();
+            }
+            black_box(buf)
+        })
+    });
+
+    group.bench_function("push_unchecked", |b| {
+        b.iter(|| {
+            let mut buf = VecCopy::with_capacity::<f32>(LEN);
+            for i in 0..LEN {
+                unsafe { buf.push_unchecked(black_box(i as f32)) };
+            }
+            black_box(buf)
+        })
+    });
+
+    group.bench_function("extend", |b| {
+        b.iter(|| {
+            let mut buf = VecCopy::with_type::<f32>();
+            buf.extend((0..LEN).map(|i| black_box(i as f32))).unwrap();
+            black_box(buf)
+        })
+    });
+
+    group.bench_function("extend_unchecked", |b| {
+        b.iter(|| {
+            let mut buf = VecCopy::with_type::<f32>();
+            unsafe { buf.extend_unchecked((0..LEN).map(|i| black_box(i as f32))) };
+            black_box(buf)
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, push);
+criterion_main!(benches);
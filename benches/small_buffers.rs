@@ -0,0 +1,55 @@
+//! Benchmarks for constructing many tiny buffers.
+//!
+//! Run with and without the `smallbuffer` feature to compare inline storage with heap
+//! allocation.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use data_buffer::VecCopy;
+
+#[inline]
+fn make_small_vecs(n: usize, len: u32) -> Vec<Vec<u32>> {
+    (0..n)
+        .map(|i| {
+            let mut v = Vec::new();
+            for j in 0..len {
+                v.push(i as u32 + j);
+            }
+            v
+        })
+        .collect()
+}
+
+#[inline]
+fn make_small_vec_copies(n: usize, len: u32) -> Vec<VecCopy> {
+    (0..n)
+        .map(|i| {
+            let mut v = VecCopy::with_type::<u32>();
+            for j in 0..len {
+                v.push(i as u32 + j).unwrap();
+            }
+            v
+        })
+        .collect()
+}
+
+fn small_buffers(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Small Buffers");
+
+    for &len in &[1u32, 4, 8] {
+        let num_bufs = 10_000;
+
+        group.bench_function(BenchmarkId::new("Vec", len), |b| {
+            b.iter(|| black_box(make_small_vecs(num_bufs, len)))
+        });
+
+        group.bench_function(BenchmarkId::new("VecCopy", len), |b| {
+            b.iter(|| black_box(make_small_vec_copies(num_bufs, len)))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, small_buffers);
+criterion_main!(benches);
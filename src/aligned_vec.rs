@@ -0,0 +1,1114 @@
+//! This module defines the byte storage used by `VecCopy`.
+//!
+//! A `Vec<u8>` allocates (and deallocates) its memory with an alignment of 1, which means that
+//! reinterpreting its contents as a slice of any type with a larger alignment is undefined
+//! behaviour. `AlignedVec` is a growable byte vector whose allocation is always made with a
+//! given alignment, which allows it to store elements of arbitrarily aligned types.
+//!
+//! With the `allocator_api` feature, the memory can also be obtained from a user provided
+//! allocator, which is stored in the vector in type-erased form.
+//!
+//! With the `smallbuffer` feature, up to `INLINE_CAPACITY` bytes with an alignment of at most 16
+//! are stored inline in the vector itself, and memory is only allocated once the bytes outgrow the
+//! inline storage. This avoids many tiny allocations when dealing with many small buffers.
+
+#[cfg(feature = "allocator_api")]
+use std::alloc::Allocator;
+use std::alloc::{self, Layout};
+use std::convert::TryFrom;
+use std::fmt;
+use std::mem::{align_of, size_of, ManuallyDrop, MaybeUninit};
+use std::ops::{Deref, DerefMut, Range};
+use std::ptr::{self, NonNull};
+#[cfg(feature = "allocator_api")]
+use std::sync::Arc;
+
+use crate::trace::{trace_copy, trace_event};
+use crate::TryReserveError;
+
+/// A shared handle to a type-erased allocator.
+///
+/// Allocators are required to be `Send` and `Sync` so that they don't affect the thread safety of
+/// the buffers using them.
+#[cfg(feature = "allocator_api")]
+pub(crate) type SharedAllocator = Arc<dyn Allocator + Send + Sync>;
+
+/// The number of bytes stored inline by vectors with the `smallbuffer` feature.
+#[cfg(feature = "smallbuffer")]
+pub(crate) const INLINE_CAPACITY: usize = 16;
+
+/// A heap allocation holding the bytes of a vector.
+#[derive(Copy, Clone)]
+struct Heap {
+    /// The start of the allocation, which is dangling but well aligned if `capacity` is zero.
+    ptr: NonNull<u8>,
+    /// The size of the allocation in bytes.
+    capacity: usize,
+}
+
+impl Heap {
+    /// An empty allocation with the given alignment.
+    #[inline]
+    fn dangling(align: usize) -> Heap {
+        Heap {
+            // A dangling, but non-null and well aligned pointer. Vectors with zero capacity never
+            // access or deallocate their pointer.
+            ptr: unsafe { NonNull::new_unchecked(align as *mut u8) },
+            capacity: 0,
+        }
+    }
+}
+
+/// The storage of the bytes of a vector, either in a heap allocation or inline.
+///
+/// The inline bytes occupy the same space as the heap allocation handle, and are sufficiently
+/// aligned for all primitive types.
+#[cfg(feature = "smallbuffer")]
+#[derive(Copy, Clone)]
+#[repr(C, align(16))]
+union Storage {
+    heap: Heap,
+    inline: [MaybeUninit<u8>; INLINE_CAPACITY],
+}
+
+#[cfg(not(feature = "smallbuffer"))]
+type Storage = Heap;
+
+/// Get the largest alignment that a type of the given size can possibly have.
+///
+/// Since the size of a type is always a multiple of its alignment, this is a conservative
+/// alignment for elements whose actual type is unknown.
+#[inline]
+pub(crate) fn max_align_of_size(size: usize) -> usize {
+    debug_assert_ne!(size, 0);
+    1 << size.trailing_zeros()
+}
+
+/// A growable contiguous array of bytes allocated with a specific alignment.
+pub(crate) struct AlignedVec {
+    /// The allocation or inline bytes.
+    storage: Storage,
+    /// The number of initialized bytes at the start of the storage.
+    len: usize,
+    /// The alignment of the allocation.
+    ///
+    /// This is stored in 32 bits, which fits any valid `Layout` alignment, so that the vector is
+    /// no larger with the `smallbuffer` feature.
+    align: u32,
+    /// Whether the bytes are stored inline rather than in `storage.heap`.
+    #[cfg(feature = "smallbuffer")]
+    inline: bool,
+    /// The allocator owning the allocation, or `None` for the global allocator.
+    #[cfg(feature = "allocator_api")]
+    alloc: Option<SharedAllocator>,
+}
+
+impl AlignedVec {
+    /// Construct an empty vector with the given alignment without allocating.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `align` is not a power of two.
+    #[inline]
+    pub(crate) fn new(align: usize) -> Self {
+        assert!(
+            align.is_power_of_two(),
+            "alignment {} is not a power of two",
+            align
+        );
+        let align32 = u32::try_from(align).expect("alignment is too large");
+        AlignedVec::from_heap(Heap::dangling(align), 0, align32)
+    }
+
+    /// Construct a vector owning the given allocation from the global allocator.
+    #[inline]
+    fn from_heap(heap: Heap, len: usize, align: u32) -> Self {
+        AlignedVec {
+            #[cfg(feature = "smallbuffer")]
+            storage: Storage { heap },
+            #[cfg(not(feature = "smallbuffer"))]
+            storage: heap,
+            len,
+            align,
+            #[cfg(feature = "smallbuffer")]
+            inline: false,
+            #[cfg(feature = "allocator_api")]
+            alloc: None,
+        }
+    }
+
+    /// Construct an empty vector with the given alignment, whose memory will be obtained from
+    /// the given allocator.
+    #[cfg(feature = "allocator_api")]
+    #[inline]
+    pub(crate) fn new_in(align: usize, alloc: SharedAllocator) -> Self {
+        let mut vec = AlignedVec::new(align);
+        vec.alloc = Some(alloc);
+        vec
+    }
+
+    /// Construct an empty vector with the same alignment and allocator as this one.
+    #[inline]
+    pub(crate) fn new_like(&self) -> Self {
+        self.new_aligned_like(self.align())
+    }
+
+    /// Construct an empty vector with the given alignment and the same allocator as this one.
+    #[inline]
+    pub(crate) fn new_aligned_like(&self, align: usize) -> Self {
+        #[allow(unused_mut)]
+        let mut vec = AlignedVec::new(align);
+        #[cfg(feature = "allocator_api")]
+        {
+            vec.alloc = self.alloc.clone();
+        }
+        vec
+    }
+
+    /// Construct an empty vector with the given alignment and byte capacity.
+    #[inline]
+    pub(crate) fn with_capacity(capacity: usize, align: usize) -> Self {
+        let mut vec = AlignedVec::new(align);
+        vec.reserve_exact(capacity);
+        vec
+    }
+
+    /// Construct a vector with the given alignment by copying the given bytes.
+    #[inline]
+    pub(crate) fn from_slice(bytes: &[u8], align: usize) -> Self {
+        let mut vec = AlignedVec::with_capacity(bytes.len(), align);
+        vec.extend_from_slice(bytes);
+        vec
+    }
+
+    /// Take ownership of the allocation of the given `Vec<T>`.
+    ///
+    /// The resulting vector has the alignment of `T`.
+    #[inline]
+    pub(crate) fn from_vec<T>(vec: Vec<T>) -> Self {
+        let element_size = size_of::<T>();
+        assert_ne!(element_size, 0, "AlignedVec doesn't support zero sized types.");
+        let mut md = ManuallyDrop::new(vec);
+        let heap = Heap {
+            // The allocation is deallocated in `Drop` using the same layout as the one used by the
+            // original vector. Its pointer is never null, even if nothing was allocated.
+            ptr: unsafe { NonNull::new_unchecked(md.as_mut_ptr() as *mut u8) },
+            capacity: md.capacity() * element_size,
+        };
+        AlignedVec::from_heap(heap, md.len() * element_size, align_of::<T>() as u32)
+    }
+
+    /// Convert this vector into a `Vec<T>`.
+    ///
+    /// The allocation is reused if its layout is compatible with `Vec<T>` and it was made by the
+    /// global allocator, otherwise the bytes are copied into a new vector.
+    ///
+    /// # Safety
+    ///
+    /// The bytes stored in this vector must be a valid representation of a `[T]`.
+    pub(crate) unsafe fn into_vec<T>(self) -> Vec<T> {
+        let element_size = size_of::<T>();
+        assert_ne!(element_size, 0, "AlignedVec doesn't support zero sized types.");
+        debug_assert_eq!(self.len() % element_size, 0);
+        let len = self.len() / element_size;
+        if self.capacity() == 0 {
+            return Vec::new();
+        }
+        // Only heap allocations made by the global allocator can be reused.
+        let Heap { ptr, capacity } = self.heap();
+        if capacity != 0
+            && self.align() == align_of::<T>()
+            && capacity % element_size == 0
+            && self.is_global()
+        {
+            std::mem::forget(self);
+            Vec::from_raw_parts(ptr.as_ptr() as *mut T, len, capacity / element_size)
+        } else {
+            let mut vec = Vec::<T>::with_capacity(len);
+            ptr::copy_nonoverlapping(self.as_ptr(), vec.as_mut_ptr() as *mut u8, self.len());
+            vec.set_len(len);
+            vec
+        }
+    }
+
+    /// Decompose this vector into a pointer to its allocation, its length and capacity in bytes,
+    /// and the alignment of the allocation.
+    ///
+    /// The allocation is always made by the global allocator, so the bytes are moved into a new
+    /// allocation if this vector uses a custom allocator.
+    pub(crate) fn into_raw_parts(self) -> (*mut u8, usize, usize, usize) {
+        if !self.is_global() {
+            return AlignedVec::from_slice(&self, self.align()).into_raw_parts();
+        }
+        #[allow(unused_mut)]
+        let mut md = ManuallyDrop::new(self);
+        #[cfg(feature = "smallbuffer")]
+        md.spill();
+        let heap = md.heap();
+        (heap.ptr.as_ptr(), md.len, heap.capacity, md.align())
+    }
+
+    /// Construct a vector from the parts produced by `into_raw_parts`.
+    ///
+    /// # Safety
+    ///
+    /// Unless `capacity` is zero, `ptr` must have been allocated by the global allocator with a
+    /// size of `capacity` bytes and alignment `align`, and the first `len` bytes must be
+    /// initialized. `align` must be a power of two.
+    pub(crate) unsafe fn from_raw_parts(
+        ptr: *mut u8,
+        len: usize,
+        capacity: usize,
+        align: usize,
+    ) -> Self {
+        if capacity == 0 {
+            return AlignedVec::new(align);
+        }
+        let heap = Heap {
+            ptr: NonNull::new_unchecked(ptr),
+            capacity,
+        };
+        AlignedVec::from_heap(heap, len, align as u32)
+    }
+
+    /// Returns `true` if the memory of this vector is managed by the global allocator.
+    #[inline]
+    fn is_global(&self) -> bool {
+        #[cfg(feature = "allocator_api")]
+        {
+            self.alloc.is_none()
+        }
+        #[cfg(not(feature = "allocator_api"))]
+        {
+            true
+        }
+    }
+
+    /// The alignment of the allocation.
+    #[inline]
+    pub(crate) fn align(&self) -> usize {
+        self.align as usize
+    }
+
+    /// Get the heap allocation of this vector, which is empty if the bytes are stored inline.
+    #[inline]
+    fn heap(&self) -> Heap {
+        #[cfg(feature = "smallbuffer")]
+        {
+            if self.inline {
+                return Heap::dangling(self.align());
+            }
+            // This is safe since the storage holds an allocation unless it is inline.
+            unsafe { self.storage.heap }
+        }
+        #[cfg(not(feature = "smallbuffer"))]
+        {
+            self.storage
+        }
+    }
+
+    /// Replace the storage of this vector with the given allocation.
+    ///
+    /// The previous allocation (if any) must have been released or moved into `heap`.
+    #[inline]
+    fn set_heap(&mut self, heap: Heap) {
+        #[cfg(feature = "smallbuffer")]
+        {
+            self.storage = Storage { heap };
+            self.inline = false;
+        }
+        #[cfg(not(feature = "smallbuffer"))]
+        {
+            self.storage = heap;
+        }
+    }
+
+    /// The number of bytes that can be stored without reallocating.
+    #[inline]
+    pub(crate) fn capacity(&self) -> usize {
+        #[cfg(feature = "smallbuffer")]
+        {
+            if self.inline {
+                return INLINE_CAPACITY;
+            }
+        }
+        self.heap().capacity
+    }
+
+    /// Get the number of bytes allocated on the heap by this vector.
+    ///
+    /// This is zero for vectors stored inline.
+    #[inline]
+    pub(crate) fn heap_size(&self) -> usize {
+        self.heap().capacity
+    }
+
+    /// Borrow the stored bytes as a slice.
+    #[inline]
+    pub(crate) fn as_slice(&self) -> &[u8] {
+        // This is safe since the first `len` bytes of the storage are initialized.
+        unsafe { std::slice::from_raw_parts(self.as_ptr_unchecked(), self.len) }
+    }
+
+    /// Mutably borrow the stored bytes as a slice.
+    #[inline]
+    pub(crate) fn as_mut_slice(&mut self) -> &mut [u8] {
+        // This is safe since the first `len` bytes of the storage are initialized.
+        unsafe { std::slice::from_raw_parts_mut(self.as_mut_ptr(), self.len) }
+    }
+
+    /// Get a pointer to the start of the storage, including any initialized bytes beyond the
+    /// length.
+    #[inline]
+    fn as_ptr_unchecked(&self) -> *const u8 {
+        #[cfg(feature = "smallbuffer")]
+        {
+            if self.inline {
+                // This is safe since the storage holds inline bytes.
+                return unsafe { self.storage.inline.as_ptr() as *const u8 };
+            }
+        }
+        self.heap().ptr.as_ptr()
+    }
+
+    /// Get a pointer to the start of the storage, which is valid for writes up to the capacity.
+    #[inline]
+    fn as_mut_ptr(&mut self) -> *mut u8 {
+        #[cfg(feature = "smallbuffer")]
+        {
+            if self.inline {
+                // This is safe since the storage holds inline bytes.
+                return unsafe { self.storage.inline.as_mut_ptr() as *mut u8 };
+            }
+        }
+        self.heap().ptr.as_ptr()
+    }
+
+    /// Borrow the unused capacity at the end of the storage.
+    #[inline]
+    pub(crate) fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<u8>] {
+        let len = self.len();
+        let spare = self.capacity() - len;
+        // This is safe since the storage is valid for writes up to the capacity, and
+        // `MaybeUninit` doesn't require the bytes to be initialized.
+        unsafe {
+            std::slice::from_raw_parts_mut(
+                self.as_mut_ptr().add(len) as *mut MaybeUninit<u8>,
+                spare,
+            )
+        }
+    }
+
+    /// Set the number of stored bytes.
+    ///
+    /// # Safety
+    ///
+    /// `len` must not exceed the capacity, and the first `len` bytes must be initialized.
+    #[inline]
+    pub(crate) unsafe fn set_len(&mut self, len: usize) {
+        debug_assert!(len <= self.capacity());
+        self.len = len;
+    }
+
+    /// Move inline bytes to the heap.
+    #[cfg(feature = "smallbuffer")]
+    fn spill(&mut self) {
+        if self.inline {
+            self.realloc(INLINE_CAPACITY);
+        }
+    }
+
+    /// Reserve capacity for at least `additional` more bytes.
+    ///
+    /// Like `Vec::reserve`, this may reserve more space to avoid frequent reallocations.
+    #[inline]
+    pub(crate) fn reserve(&mut self, additional: usize) {
+        let required = self
+            .len()
+            .checked_add(additional)
+            .expect("capacity overflow");
+        if required > self.capacity() {
+            self.realloc(required.max(self.capacity() * 2));
+        }
+    }
+
+    /// Reserve capacity for exactly `additional` more bytes.
+    #[inline]
+    pub(crate) fn reserve_exact(&mut self, additional: usize) {
+        let required = self
+            .len()
+            .checked_add(additional)
+            .expect("capacity overflow");
+        if required > self.capacity() {
+            self.realloc(required);
+        }
+    }
+
+    /// Shrink the capacity of this vector to hold at least `min_capacity` bytes, but no fewer
+    /// than its length.
+    #[inline]
+    pub(crate) fn shrink_to(&mut self, min_capacity: usize) {
+        let new_capacity = min_capacity.max(self.len());
+        if new_capacity >= self.capacity() {
+            return;
+        }
+        #[cfg(feature = "smallbuffer")]
+        {
+            // Inline storage cannot shrink.
+            if self.inline && new_capacity != 0 {
+                return;
+            }
+        }
+        if new_capacity == 0 {
+            // Release the allocation altogether.
+            *self = self.new_like();
+        } else {
+            self.realloc(new_capacity);
+        }
+    }
+
+    /// Try to reserve capacity for at least `additional` more bytes.
+    ///
+    /// Unlike `reserve`, this returns an error instead of aborting if the allocation fails.
+    #[inline]
+    pub(crate) fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let required = self
+            .len()
+            .checked_add(additional)
+            .ok_or(TryReserveError::CapacityOverflow)?;
+        if required > self.capacity() {
+            self.try_realloc(required.max(self.capacity() * 2))?;
+        }
+        Ok(())
+    }
+
+    /// Try to reserve capacity for exactly `additional` more bytes.
+    #[inline]
+    pub(crate) fn try_reserve_exact(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let required = self
+            .len()
+            .checked_add(additional)
+            .ok_or(TryReserveError::CapacityOverflow)?;
+        if required > self.capacity() {
+            self.try_realloc(required)?;
+        }
+        Ok(())
+    }
+
+    /// Reallocate the underlying buffer to hold exactly `new_capacity` bytes.
+    fn realloc(&mut self, new_capacity: usize) {
+        match self.try_realloc(new_capacity) {
+            Ok(()) => {}
+            Err(TryReserveError::CapacityOverflow) => panic!("capacity overflow"),
+            Err(TryReserveError::AllocError { layout }) => alloc::handle_alloc_error(layout),
+        }
+    }
+
+    /// Reallocate the underlying buffer to hold exactly `new_capacity` bytes, returning an error
+    /// if the allocation fails.
+    fn try_realloc(&mut self, new_capacity: usize) -> Result<(), TryReserveError> {
+        debug_assert!(new_capacity >= self.len());
+        debug_assert!(new_capacity > 0);
+        trace_event!(
+            len = self.len(),
+            old_capacity = self.capacity(),
+            new_capacity,
+            "reallocating buffer"
+        );
+        let new_layout = Layout::from_size_align(new_capacity, self.align())
+            .map_err(|_| TryReserveError::CapacityOverflow)?;
+        #[cfg(feature = "smallbuffer")]
+        {
+            if self.inline {
+                // Move the inline bytes into a new allocation.
+                let new_ptr = unsafe { self.allocate(new_layout) };
+                if new_ptr.is_null() {
+                    return Err(TryReserveError::AllocError { layout: new_layout });
+                }
+                // This is safe since the new allocation can hold all inline bytes.
+                unsafe {
+                    ptr::copy_nonoverlapping(self.as_ptr_unchecked(), new_ptr, self.len);
+                    self.set_heap(Heap {
+                        ptr: NonNull::new_unchecked(new_ptr),
+                        capacity: new_capacity,
+                    });
+                }
+                return Ok(());
+            }
+            if self.heap().capacity == 0
+                && new_capacity <= INLINE_CAPACITY
+                && self.align() <= align_of::<Storage>()
+                && self.is_global()
+            {
+                self.storage = Storage {
+                    inline: [MaybeUninit::uninit(); INLINE_CAPACITY],
+                };
+                self.inline = true;
+                return Ok(());
+            }
+        }
+        let capacity = self.heap().capacity;
+        // This is safe since the current allocation (if any) was made with the current capacity
+        // and alignment.
+        unsafe {
+            let new_ptr = if capacity == 0 {
+                self.allocate(new_layout)
+            } else {
+                let layout = Layout::from_size_align_unchecked(capacity, self.align());
+                self.reallocate(layout, new_layout)
+            };
+            if new_ptr.is_null() {
+                return Err(TryReserveError::AllocError { layout: new_layout });
+            }
+            // The old allocation is either dangling or has been reallocated, so it is simply
+            // replaced.
+            self.set_heap(Heap {
+                ptr: NonNull::new_unchecked(new_ptr),
+                capacity: new_capacity,
+            });
+        }
+        Ok(())
+    }
+
+    /// Allocate memory with the given layout, returning a null pointer on failure.
+    unsafe fn allocate(&self, layout: Layout) -> *mut u8 {
+        #[cfg(feature = "allocator_api")]
+        {
+            if let Some(alloc) = &self.alloc {
+                return alloc
+                    .allocate(layout)
+                    .map_or(ptr::null_mut(), |p| p.as_ptr() as *mut u8);
+            }
+        }
+        alloc::alloc(layout)
+    }
+
+    /// Move the current allocation with the given layout into a new allocation with the same
+    /// alignment, returning a null pointer on failure.
+    unsafe fn reallocate(&mut self, layout: Layout, new_layout: Layout) -> *mut u8 {
+        #[cfg(feature = "allocator_api")]
+        {
+            if let Some(alloc) = &self.alloc {
+                let result = if new_layout.size() >= layout.size() {
+                    alloc.grow(self.heap().ptr, layout, new_layout)
+                } else {
+                    alloc.shrink(self.heap().ptr, layout, new_layout)
+                };
+                return result.map_or(ptr::null_mut(), |p| p.as_ptr() as *mut u8);
+            }
+        }
+        alloc::realloc(self.heap().ptr.as_ptr(), layout, new_layout.size())
+    }
+
+    /// Append the given bytes to the end of this vector.
+    #[inline]
+    pub(crate) fn extend_from_slice(&mut self, bytes: &[u8]) {
+        trace_copy!(bytes.len());
+        self.reserve(bytes.len());
+        let len = self.len();
+        // This is safe since we reserved enough space for the bytes above.
+        unsafe {
+            ptr::copy_nonoverlapping(bytes.as_ptr(), self.as_mut_ptr().add(len), bytes.len());
+            self.set_len(len + bytes.len());
+        }
+    }
+
+    /// Copy the given values to the end of this vector with a single `memcpy`.
+    ///
+    /// Like `push_typed`, the bytes are copied without being read as integers, so values with
+    /// padding are supported.
+    #[inline]
+    pub(crate) fn extend_from_typed_slice<T: Copy>(&mut self, values: &[T]) {
+        let size = std::mem::size_of_val(values);
+        trace_copy!(size);
+        self.reserve(size);
+        let len = self.len();
+        // This is safe since we reserved enough space for the values above.
+        unsafe {
+            ptr::copy_nonoverlapping(
+                values.as_ptr() as *const u8,
+                self.as_mut_ptr().add(len),
+                size,
+            );
+            self.set_len(len + size);
+        }
+    }
+
+    /// Move the given value to the end of this vector.
+    ///
+    /// The value is written directly into the allocation, so its bytes (including any padding)
+    /// are never read.
+    #[inline]
+    pub(crate) fn push_typed<T>(&mut self, value: T) {
+        let size = size_of::<T>();
+        self.reserve(size);
+        let len = self.len();
+        // This is safe since we reserved enough space for the value above.
+        unsafe {
+            ptr::write_unaligned(self.as_mut_ptr().add(len) as *mut T, value);
+            self.set_len(len + size);
+        }
+    }
+
+    /// Move the given value into this vector at byte offset `offset`, shifting all bytes after
+    /// it to the right.
+    ///
+    /// Like `push_typed`, the bytes of the value are never read.
+    #[inline]
+    pub(crate) fn insert_typed<T>(&mut self, offset: usize, value: T) {
+        let len = self.len();
+        assert!(offset <= len);
+        let size = size_of::<T>();
+        self.reserve(size);
+        // This is safe since we reserved enough space for the value above, and the tail is
+        // shifted within the allocation.
+        unsafe {
+            let ptr = self.as_mut_ptr();
+            ptr::copy(ptr.add(offset), ptr.add(offset + size), len - offset);
+            ptr::write_unaligned(ptr.add(offset) as *mut T, value);
+            self.set_len(len + size);
+        }
+    }
+
+    /// Resize this vector in place to hold `new_len` bytes, filling new bytes with `value`.
+    #[inline]
+    pub(crate) fn resize(&mut self, new_len: usize, value: u8) {
+        let len = self.len();
+        if new_len > len {
+            self.reserve(new_len - len);
+            // This is safe since we reserved enough space for the new bytes above.
+            unsafe {
+                ptr::write_bytes(self.as_mut_ptr().add(len), value, new_len - len);
+                self.set_len(new_len);
+            }
+        } else {
+            self.truncate(new_len);
+        }
+    }
+
+    /// Shorten this vector to `len` bytes. This has no effect if `len` is greater than the
+    /// current length.
+    #[inline]
+    pub(crate) fn truncate(&mut self, len: usize) {
+        if len < self.len() {
+            // This is safe since the first `len` bytes are initialized.
+            unsafe { self.set_len(len) };
+        }
+    }
+
+    /// Remove all bytes from this vector, keeping its allocation.
+    #[inline]
+    pub(crate) fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    /// Insert the given bytes at byte offset `offset`, shifting all bytes after it to the right.
+    #[inline]
+    pub(crate) fn insert_slice(&mut self, offset: usize, bytes: &[u8]) {
+        assert!(offset <= self.len());
+        let len = self.len();
+        self.resize(len + bytes.len(), 0);
+        self.copy_within(offset..len, offset + bytes.len());
+        self[offset..offset + bytes.len()].copy_from_slice(bytes);
+    }
+
+    /// Remove the given byte range from this vector, returning its bytes in an iterator.
+    ///
+    /// The range is removed when the iterator is dropped, even if it is not fully consumed.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the start of the range is greater than its end, or if its end is
+    /// greater than the length of the vector.
+    #[inline]
+    pub(crate) fn drain(&mut self, range: Range<usize>) -> Drain<'_> {
+        let len = self.len();
+        assert!(
+            range.start <= range.end && range.end <= len,
+            "drain range {:?} is out of bounds for length {}",
+            range,
+            len
+        );
+        // Exclude the drained bytes and the tail until the iterator is dropped, so that they are
+        // not observable if the iterator is leaked.
+        unsafe { self.set_len(range.start) };
+        Drain {
+            start: range.start,
+            end: range.end,
+            tail_start: range.end,
+            tail_len: len - range.end,
+            vec: self,
+        }
+    }
+
+    /// Split this vector in two at the byte offset `at`, returning the bytes in `[at, len)` in a
+    /// newly allocated vector with the same alignment.
+    #[inline]
+    pub(crate) fn split_off(&mut self, at: usize) -> AlignedVec {
+        let mut tail = self.new_like();
+        tail.extend_from_slice(&self[at..]);
+        self.truncate(at);
+        tail
+    }
+
+    /// Move all bytes from `other` to the end of this vector, leaving `other` empty.
+    #[inline]
+    pub(crate) fn append(&mut self, other: &mut AlignedVec) {
+        self.extend_from_slice(other);
+        other.clear();
+    }
+}
+
+impl Drop for AlignedVec {
+    fn drop(&mut self) {
+        let Heap { ptr, capacity } = self.heap();
+        if capacity != 0 {
+            // This is safe since the allocation was made with this exact layout.
+            unsafe {
+                let layout = Layout::from_size_align_unchecked(capacity, self.align());
+                #[cfg(feature = "allocator_api")]
+                {
+                    if let Some(alloc) = &self.alloc {
+                        return alloc.deallocate(ptr, layout);
+                    }
+                }
+                alloc::dealloc(ptr.as_ptr(), layout);
+            }
+        }
+    }
+}
+
+// This is safe since `AlignedVec` uniquely owns its allocation of plain bytes, just like a
+// `Vec<u8>`.
+unsafe impl Send for AlignedVec {}
+unsafe impl Sync for AlignedVec {}
+
+impl Deref for AlignedVec {
+    type Target = [u8];
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl DerefMut for AlignedVec {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [u8] {
+        self.as_mut_slice()
+    }
+}
+
+impl Clone for AlignedVec {
+    #[inline]
+    fn clone(&self) -> Self {
+        let mut vec = self.new_like();
+        vec.extend_from_slice(self);
+        vec
+    }
+}
+
+/// Aligned vectors are compared by their contents only.
+impl PartialEq for AlignedVec {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl std::hash::Hash for AlignedVec {
+    #[inline]
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state)
+    }
+}
+
+impl fmt::Debug for AlignedVec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.as_slice().fmt(f)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for AlignedVec {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.as_slice())
+    }
+}
+
+impl IntoIterator for AlignedVec {
+    type Item = u8;
+    type IntoIter = IntoIter;
+    #[inline]
+    fn into_iter(self) -> IntoIter {
+        IntoIter {
+            start: 0,
+            end: self.len(),
+            buf: self,
+        }
+    }
+}
+
+/// An iterator that moves bytes out of an `AlignedVec`.
+pub(crate) struct IntoIter {
+    buf: AlignedVec,
+    start: usize,
+    end: usize,
+}
+
+impl IntoIter {
+    /// The remaining bytes of this iterator as a slice.
+    #[inline]
+    pub(crate) fn as_slice(&self) -> &[u8] {
+        &self.buf[self.start..self.end]
+    }
+}
+
+impl Iterator for IntoIter {
+    type Item = u8;
+
+    #[inline]
+    fn next(&mut self) -> Option<u8> {
+        if self.start < self.end {
+            self.start += 1;
+            Some(self.buf[self.start - 1])
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<u8> {
+        self.start = self.start.saturating_add(n).min(self.end);
+        self.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.end - self.start;
+        (n, Some(n))
+    }
+}
+
+impl DoubleEndedIterator for IntoIter {
+    #[inline]
+    fn next_back(&mut self) -> Option<u8> {
+        if self.start < self.end {
+            self.end -= 1;
+            Some(self.buf[self.end])
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn nth_back(&mut self, n: usize) -> Option<u8> {
+        self.end = self.end.saturating_sub(n).max(self.start);
+        self.next_back()
+    }
+}
+
+impl ExactSizeIterator for IntoIter {}
+
+/// A draining iterator over a range of bytes of an `AlignedVec`.
+///
+/// The drained bytes and the bytes after them remain in the allocation beyond the length of the
+/// vector until the iterator is dropped, at which point the tail is moved to close the gap.
+pub(crate) struct Drain<'a> {
+    vec: &'a mut AlignedVec,
+    /// The start of the bytes remaining to be yielded.
+    start: usize,
+    /// The end of the bytes remaining to be yielded.
+    end: usize,
+    /// The start of the bytes after the drained range.
+    tail_start: usize,
+    /// The number of bytes after the drained range.
+    tail_len: usize,
+}
+
+impl Drain<'_> {
+    /// The remaining bytes of this iterator as a slice.
+    #[inline]
+    pub(crate) fn as_slice(&self) -> &[u8] {
+        // This is safe since the bytes of the original vector beyond its current length are left
+        // initialized until the iterator is dropped.
+        unsafe {
+            let ptr = self.vec.as_ptr_unchecked();
+            std::slice::from_raw_parts(ptr.add(self.start), self.end - self.start)
+        }
+    }
+}
+
+impl Iterator for Drain<'_> {
+    type Item = u8;
+
+    #[inline]
+    fn next(&mut self) -> Option<u8> {
+        let byte = *self.as_slice().first()?;
+        self.start += 1;
+        Some(byte)
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<u8> {
+        self.start = self.start.saturating_add(n).min(self.end);
+        self.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.end - self.start;
+        (n, Some(n))
+    }
+}
+
+impl DoubleEndedIterator for Drain<'_> {
+    #[inline]
+    fn next_back(&mut self) -> Option<u8> {
+        let byte = *self.as_slice().last()?;
+        self.end -= 1;
+        Some(byte)
+    }
+
+    #[inline]
+    fn nth_back(&mut self, n: usize) -> Option<u8> {
+        self.end = self.end.saturating_sub(n).max(self.start);
+        self.next_back()
+    }
+}
+
+impl ExactSizeIterator for Drain<'_> {}
+
+impl Drop for Drain<'_> {
+    fn drop(&mut self) {
+        let len = self.vec.len();
+        // This is safe since the tail is initialized and moved within the allocation.
+        unsafe {
+            let ptr = self.vec.as_mut_ptr();
+            ptr::copy(ptr.add(self.tail_start), ptr.add(len), self.tail_len);
+            self.vec.set_len(len + self.tail_len);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[repr(align(64))]
+    #[derive(Copy, Clone)]
+    struct Aligned64(u8);
+
+    #[test]
+    fn growth_preserves_alignment() {
+        let mut vec = AlignedVec::new(64);
+        assert_eq!(vec.as_ptr() as usize % 64, 0);
+        for i in 0..100u8 {
+            vec.extend_from_slice(&[i; 3]);
+            assert_eq!(vec.as_ptr() as usize % 64, 0);
+        }
+        vec.insert_slice(1, &[7, 7]);
+        assert_eq!(&vec[..6], &[0, 7, 7, 0, 0, 1]);
+        let tail = vec.split_off(150);
+        assert_eq!(tail.align(), 64);
+        assert_eq!(tail.as_ptr() as usize % 64, 0);
+        assert_eq!(vec.len() + tail.len(), 302);
+    }
+
+    #[test]
+    fn vec_round_trip() {
+        let vec = vec![Aligned64(1), Aligned64(2)];
+        let bytes = AlignedVec::from_vec(vec);
+        assert_eq!(bytes.align(), 64);
+        assert_eq!(bytes.len(), 128);
+        let vec: Vec<Aligned64> = unsafe { bytes.clone().into_vec() };
+        assert_eq!(vec.len(), 2);
+        assert_eq!(vec[1].0, 2);
+        // Reinterpreting with a different alignment copies the data.
+        let vec: Vec<u8> = unsafe { bytes.into_vec() };
+        assert_eq!(vec.len(), 128);
+        assert_eq!((vec[0], vec[64]), (1, 2));
+    }
+
+    #[test]
+    fn into_iter() {
+        let bytes = AlignedVec::from_slice(&[1, 2, 3, 4, 5], 8);
+        let mut iter = bytes.into_iter();
+        assert_eq!(iter.nth(1), Some(2));
+        assert_eq!(iter.nth_back(1), Some(4));
+        assert_eq!(iter.as_slice(), &[3]);
+        assert_eq!(iter.len(), 1);
+        assert_eq!(iter.next(), Some(3));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn drain() {
+        let mut vec = AlignedVec::from_slice(&[1, 2, 3, 4, 5, 6], 4);
+        let mut drain = vec.drain(1..5);
+        assert_eq!(drain.len(), 4);
+        assert_eq!(drain.next(), Some(2));
+        assert_eq!(drain.next_back(), Some(5));
+        assert_eq!(drain.as_slice(), &[3, 4]);
+        drop(drain);
+        assert_eq!(&vec[..], &[1, 6]);
+
+        let drained: Vec<u8> = vec.drain(0..2).collect();
+        assert_eq!(drained, vec![1, 6]);
+        assert!(vec.is_empty());
+
+        // Leaking the iterator leaves the bytes before the range.
+        let mut vec = AlignedVec::from_slice(&[1, 2, 3], 1);
+        std::mem::forget(vec.drain(1..2));
+        assert_eq!(&vec[..], &[1]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn drain_out_of_bounds() {
+        AlignedVec::from_slice(&[1, 2, 3], 1).drain(2..4);
+    }
+
+    #[cfg(feature = "smallbuffer")]
+    #[test]
+    fn inline_storage() {
+        let mut vec = AlignedVec::new(4);
+        assert_eq!(vec.capacity(), 0);
+        vec.extend_from_slice(&[1, 2, 3]);
+        vec.push_typed(0x0404_0404u32);
+        assert!(vec.inline);
+        assert_eq!(vec.capacity(), INLINE_CAPACITY);
+        assert_eq!(vec.as_ptr() as usize % 4, 0);
+        vec.insert_slice(0, &[0]);
+        assert_eq!(&vec[..], &[0, 1, 2, 3, 4, 4, 4, 4]);
+
+        // Draining keeps the bytes inline.
+        let drained: Vec<u8> = vec.drain(1..4).collect();
+        assert_eq!(drained, vec![1, 2, 3]);
+        assert!(vec.inline);
+        assert_eq!(&vec[..], &[0, 4, 4, 4, 4]);
+        vec.insert_slice(1, &[1, 2, 3]);
+
+        // Bytes are moved to the heap once they exceed the inline capacity.
+        vec.resize(INLINE_CAPACITY + 1, 5);
+        assert!(!vec.inline);
+        assert_eq!(&vec[..8], &[0, 1, 2, 3, 4, 4, 4, 4]);
+        assert_eq!(vec.len(), INLINE_CAPACITY + 1);
+        assert_eq!(vec.as_ptr() as usize % 4, 0);
+
+        // Large alignments are never stored inline.
+        let vec = AlignedVec::from_slice(&[1], 64);
+        assert!(!vec.inline);
+        assert_eq!(vec.as_ptr() as usize % 64, 0);
+
+        let vec: Vec<u32> = unsafe { AlignedVec::from_slice(&[1, 0, 0, 0], 4).into_vec() };
+        assert_eq!(vec, vec![1]);
+    }
+
+    #[cfg(all(feature = "smallbuffer", target_pointer_width = "64"))]
+    #[cfg(not(feature = "allocator_api"))]
+    #[test]
+    fn inline_storage_size() {
+        // Inline bytes share space with the heap allocation.
+        assert_eq!(size_of::<AlignedVec>(), 32);
+    }
+}
@@ -0,0 +1,112 @@
+//! This module implements buffers whose memory is obtained from custom allocators.
+//!
+//! This relies on the unstable `allocator_api` and therefore requires a nightly compiler. The
+//! allocator is stored in the buffer in type-erased form, so buffers using different allocators
+//! have the same type. Buffers derived from a buffer with a custom allocator (e.g. clones or the
+//! result of `split_off`) use the same allocator.
+
+use std::alloc::Allocator;
+use std::mem::{align_of, size_of};
+use std::sync::Arc;
+
+use crate::aligned_vec::AlignedVec;
+use crate::{Elem, VecCopy};
+
+impl VecCopy {
+    /// Construct an empty `VecCopy` with a specific type, whose memory will be obtained from the
+    /// given allocator.
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use data_buffer::VecCopy;
+    /// use std::alloc::System;
+    /// let mut buf = VecCopy::with_type_in::<u32, _>(System);
+    /// buf.push(1u32).unwrap();
+    /// assert_eq!(buf.as_slice::<u32>().unwrap(), &[1]);
+    /// ```
+    #[inline]
+    pub fn with_type_in<T, A>(alloc: A) -> Self
+    where
+        T: Elem,
+        A: Allocator + Send + Sync + 'static,
+    {
+        let mut buf = VecCopy::with_type::<T>();
+        buf.data = AlignedVec::new_in(align_of::<T>(), Arc::new(alloc));
+        buf
+    }
+
+    /// Construct an empty `VecCopy` with a capacity for a given number of typed elements, whose
+    /// memory is obtained from the given allocator.
+    #[inline]
+    pub fn with_capacity_in<T, A>(n: usize, alloc: A) -> Self
+    where
+        T: Elem,
+        A: Allocator + Send + Sync + 'static,
+    {
+        let mut buf = VecCopy::with_type_in::<T, A>(alloc);
+        buf.data.reserve_exact(n * size_of::<T>());
+        buf
+    }
+
+    /// Construct a `VecCopy` from a given slice by copying the data into memory obtained from
+    /// the given allocator.
+    #[inline]
+    pub fn from_slice_in<T, A>(slice: &[T], alloc: A) -> Self
+    where
+        T: Elem,
+        A: Allocator + Send + Sync + 'static,
+    {
+        let mut buf = VecCopy::with_capacity_in::<T, A>(slice.len(), alloc);
+        buf.copy_from_slice(slice);
+        buf
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::alloc::{AllocError, Global, Layout};
+    use std::ptr::NonNull;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// An allocator that counts the number of live allocations.
+    struct Counting<'a>(&'a AtomicUsize);
+
+    unsafe impl Allocator for Counting<'static> {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Global.allocate(layout)
+        }
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            self.0.fetch_sub(1, Ordering::SeqCst);
+            Global.deallocate(ptr, layout)
+        }
+    }
+
+    #[test]
+    fn allocator_test() {
+        static LIVE: AtomicUsize = AtomicUsize::new(0);
+        let mut buf = VecCopy::with_type_in::<u64, _>(Counting(&LIVE));
+        assert_eq!(LIVE.load(Ordering::SeqCst), 0);
+        buf.extend(0..100u64).unwrap();
+        assert_eq!(LIVE.load(Ordering::SeqCst), 1);
+
+        let tail = buf.split_off(50);
+        let copy = buf.clone();
+        let empty = VecCopy::with_type_from(&buf);
+        assert_eq!(LIVE.load(Ordering::SeqCst), 3);
+        assert_eq!(empty.byte_capacity(), 0);
+
+        let vec: Vec<u64> = tail.into_vec().unwrap();
+        assert_eq!(vec, (50..100).collect::<Vec<_>>());
+        assert_eq!(LIVE.load(Ordering::SeqCst), 2);
+        drop(copy);
+        drop(buf);
+        assert_eq!(LIVE.load(Ordering::SeqCst), 0);
+
+        let buf = VecCopy::from_slice_in(&[1u8, 2, 3], Counting(&LIVE));
+        assert_eq!(buf.as_slice::<u8>().unwrap(), &[1, 2, 3]);
+        assert_eq!(LIVE.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,121 @@
+//! This module defines an immutable buffer whose data is shared between its clones.
+//!
+//! Cloning a `VecCopy` copies all of its data, which is wasteful for large buffers that are only
+//! read after construction. `ArcBuffer` instead stores the buffer behind an `Arc`, so clones are
+//! cheap and all readers share a single allocation. The buffer is kept whole (rather than as an
+//! `Arc<[u8]>`) to preserve the alignment of its data and the functions captured for its element
+//! type.
+
+use std::ops::Deref;
+use std::sync::Arc;
+
+use crate::VecCopy;
+
+/// An immutable, reference counted `VecCopy`.
+///
+/// This dereferences to the underlying buffer, so all read-only accessors of `VecCopy` are
+/// available.
+///
+/// # Examples
+/// ```
+/// use data_buffer::{ArcBuffer, VecCopy};
+/// let buf = ArcBuffer::from(VecCopy::from_vec(vec![1u32, 2, 3]));
+/// let shared = buf.clone();
+/// assert!(ArcBuffer::ptr_eq(&buf, &shared));
+/// assert_eq!(shared.as_slice::<u32>().unwrap(), &[1, 2, 3]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd)]
+pub struct ArcBuffer(pub(crate) Arc<VecCopy>);
+
+impl ArcBuffer {
+    /// Construct a shared buffer by moving the given buffer behind a reference count.
+    #[inline]
+    pub fn new(buf: VecCopy) -> Self {
+        ArcBuffer(Arc::new(buf))
+    }
+
+    /// Returns `true` if both buffers share the same data.
+    #[inline]
+    pub fn ptr_eq(a: &ArcBuffer, b: &ArcBuffer) -> bool {
+        Arc::ptr_eq(&a.0, &b.0)
+    }
+
+    /// Get the number of buffers sharing this data.
+    #[inline]
+    pub fn strong_count(&self) -> usize {
+        Arc::strong_count(&self.0)
+    }
+
+    /// Unwrap the underlying buffer if this is the only reference to it, otherwise return the
+    /// shared buffer back.
+    #[inline]
+    pub fn try_unwrap(self) -> Result<VecCopy, ArcBuffer> {
+        Arc::try_unwrap(self.0).map_err(ArcBuffer)
+    }
+
+    /// Convert into an owned buffer, which is copied only if the data is shared.
+    #[inline]
+    pub fn into_vec_copy(self) -> VecCopy {
+        self.try_unwrap()
+            .unwrap_or_else(|shared| VecCopy::clone(&shared))
+    }
+}
+
+impl Deref for ArcBuffer {
+    type Target = VecCopy;
+    #[inline]
+    fn deref(&self) -> &VecCopy {
+        &self.0
+    }
+}
+
+impl AsRef<VecCopy> for ArcBuffer {
+    #[inline]
+    fn as_ref(&self) -> &VecCopy {
+        &self.0
+    }
+}
+
+impl From<VecCopy> for ArcBuffer {
+    #[inline]
+    fn from(buf: VecCopy) -> Self {
+        ArcBuffer::new(buf)
+    }
+}
+
+impl From<ArcBuffer> for VecCopy {
+    #[inline]
+    fn from(buf: ArcBuffer) -> Self {
+        buf.into_vec_copy()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arc_buffer_test() {
+        let buf = ArcBuffer::new(VecCopy::from_vec(vec![1.0f32, 2.0, 3.0]));
+        let shared = buf.clone();
+        assert_eq!(buf.strong_count(), 2);
+        assert_eq!(buf.as_bytes().as_ptr(), shared.as_bytes().as_ptr());
+        assert_eq!(buf, shared);
+
+        // Readers on other threads share the same allocation.
+        let handle = {
+            let buf = buf.clone();
+            std::thread::spawn(move || buf.iter::<f32>().unwrap().sum::<f32>())
+        };
+        assert_eq!(handle.join().unwrap(), 6.0);
+
+        // Shared data is copied when converted into an owned buffer.
+        let buf = buf.try_unwrap().unwrap_err();
+        let owned = buf.into_vec_copy();
+        assert_ne!(owned.as_bytes().as_ptr(), shared.as_bytes().as_ptr());
+        let ptr = shared.as_bytes().as_ptr();
+        let owned_shared = VecCopy::from(shared);
+        assert_eq!(owned_shared.as_bytes().as_ptr(), ptr);
+        assert_eq!(owned, owned_shared);
+    }
+}
@@ -0,0 +1,265 @@
+//! This module defines an arena for storing many small buffers in a single allocation.
+//!
+//! Every `VecCopy` owns its own allocation, so workloads creating thousands of tiny buffers, for
+//! instance one per element per frame, pay for a heap allocation each. A `BufferArena` instead
+//! packs the data of all its buffers into one allocation, and frees them all at once with
+//! `reset`, which keeps the allocation for the next batch.
+//!
+//! Since the allocation may move as the arena grows, buffers are identified by an `ArenaId` handle
+//! and borrowed as untyped slices on demand.
+
+use std::any::TypeId;
+use std::mem::align_of;
+
+use crate::aligned_vec::AlignedVec;
+use crate::vtable::CopyVTable;
+use crate::{Elem, SliceCopy, SliceCopyMut, TypeName, VecCopy};
+
+/// A handle to a buffer stored in a `BufferArena`.
+///
+/// Handles are invalidated when the arena is reset. A handle only refers to buffers in the arena
+/// that returned it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ArenaId {
+    index: usize,
+    generation: u64,
+}
+
+/// The location and element type of a buffer stored in an arena.
+#[derive(Copy, Clone, Debug)]
+struct ArenaEntry {
+    /// The byte offset of the first element.
+    offset: usize,
+    /// The number of bytes occupied by the buffer.
+    num_bytes: usize,
+    element_size: usize,
+    element_type_id: TypeId,
+    element_type_name: TypeName,
+    vtable: CopyVTable,
+}
+
+/// A collection of buffers sharing a single allocation.
+///
+/// # Examples
+/// ```
+/// use data_buffer::BufferArena;
+/// let mut arena = BufferArena::new();
+/// let a = arena.alloc_from_slice(&[1u8, 2, 3]);
+/// let b = arena.alloc_from_slice(&[4.0f64, 5.0]);
+/// assert_eq!(arena.get(a).unwrap().as_slice::<u8>().unwrap(), &[1, 2, 3]);
+/// arena.get_mut(b).unwrap().as_mut_slice::<f64>().unwrap()[0] = 0.0;
+/// assert_eq!(arena.get(b).unwrap().as_slice::<f64>().unwrap(), &[0.0, 5.0]);
+///
+/// // Free all buffers at once, keeping the allocation.
+/// arena.reset();
+/// assert!(arena.get(a).is_none());
+/// ```
+#[derive(Clone, Debug)]
+pub struct BufferArena {
+    data: AlignedVec,
+    entries: Vec<ArenaEntry>,
+    /// The number of times this arena has been reset, used to invalidate old handles.
+    generation: u64,
+}
+
+impl Default for BufferArena {
+    #[inline]
+    fn default() -> Self {
+        BufferArena::new()
+    }
+}
+
+impl BufferArena {
+    /// Construct an empty arena without allocating.
+    #[inline]
+    pub fn new() -> Self {
+        BufferArena {
+            data: AlignedVec::new(1),
+            entries: Vec::new(),
+            generation: 0,
+        }
+    }
+
+    /// Construct an empty arena with room for `num_bytes` bytes of data.
+    #[inline]
+    pub fn with_byte_capacity(num_bytes: usize) -> Self {
+        let mut arena = BufferArena::new();
+        arena.data.reserve_exact(num_bytes);
+        arena
+    }
+
+    /// Get the number of buffers stored in this arena.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if there are any buffers stored in this arena.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Get the number of bytes used by the buffers in this arena, including any padding
+    /// between them.
+    #[inline]
+    pub fn num_bytes(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Get the number of bytes this arena can hold without reallocating.
+    #[inline]
+    pub fn byte_capacity(&self) -> usize {
+        self.data.capacity()
+    }
+
+    /// Remove all buffers from this arena, keeping its allocation.
+    ///
+    /// All previously returned handles are invalidated.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.data.clear();
+        self.entries.clear();
+        self.generation += 1;
+    }
+
+    /// Pad the data to an offset aligned to `align`, at which the next buffer starts.
+    fn start_buffer(&mut self, align: usize) -> usize {
+        if align > self.data.align() {
+            // Offsets aligned to the old alignment remain valid in the more strictly aligned
+            // allocation.
+            let mut data = self.data.new_aligned_like(align);
+            data.reserve_exact(self.data.capacity());
+            data.extend_from_slice(&self.data);
+            self.data = data;
+        }
+        let offset = self.data.len().div_ceil(align) * align;
+        self.data.resize(offset, 0);
+        offset
+    }
+
+    /// Record the buffer whose data was appended after `offset`, returning a handle to it.
+    fn finish_buffer(&mut self, offset: usize, buf: &VecCopy) -> ArenaId {
+        self.entries.push(ArenaEntry {
+            offset,
+            num_bytes: self.data.len() - offset,
+            element_size: buf.element_size,
+            element_type_id: buf.element_type_id,
+            element_type_name: buf.element_type_name,
+            vtable: buf.vtable,
+        });
+        ArenaId {
+            index: self.entries.len() - 1,
+            generation: self.generation,
+        }
+    }
+
+    /// Copy the given slice into this arena, returning a handle to the new buffer.
+    pub fn alloc_from_slice<T: Elem>(&mut self, slice: &[T]) -> ArenaId {
+        let offset = self.start_buffer(align_of::<T>());
+        self.data.extend_from_typed_slice(slice);
+        self.finish_buffer(offset, &VecCopy::with_type::<T>())
+    }
+
+    /// Copy the elements of the given buffer into this arena, returning a handle to the new
+    /// buffer.
+    ///
+    /// The new buffer keeps the functions captured by `buf`.
+    pub fn alloc_from_buffer(&mut self, buf: &VecCopy) -> ArenaId {
+        let offset = self.start_buffer(buf.data.align());
+        self.data.extend_from_slice(buf.as_bytes());
+        self.finish_buffer(offset, buf)
+    }
+
+    /// Get the entry for the given handle if it is still valid.
+    #[inline]
+    fn entry(&self, id: ArenaId) -> Option<ArenaEntry> {
+        if id.generation != self.generation {
+            return None;
+        }
+        self.entries.get(id.index).copied()
+    }
+
+    /// Borrow the buffer with the given handle as an untyped immutable slice.
+    ///
+    /// Returns `None` if the handle was invalidated by a `reset`.
+    #[inline]
+    pub fn get(&self, id: ArenaId) -> Option<SliceCopy<'_>> {
+        let entry = self.entry(id)?;
+        Some(SliceCopy {
+            data: &self.data[entry.offset..entry.offset + entry.num_bytes],
+            element_size: entry.element_size,
+            element_type_id: entry.element_type_id,
+            element_type_name: entry.element_type_name,
+            vtable: entry.vtable,
+        })
+    }
+
+    /// Borrow the buffer with the given handle as an untyped mutable slice.
+    ///
+    /// Returns `None` if the handle was invalidated by a `reset`.
+    #[inline]
+    pub fn get_mut(&mut self, id: ArenaId) -> Option<SliceCopyMut<'_>> {
+        let entry = self.entry(id)?;
+        Some(SliceCopyMut {
+            data: &mut self.data[entry.offset..entry.offset + entry.num_bytes],
+            element_size: entry.element_size,
+            element_type_id: entry.element_type_id,
+            element_type_name: entry.element_type_name,
+            vtable: entry.vtable,
+        })
+    }
+
+    /// Iterate over all buffers in this arena in the order they were allocated.
+    #[inline]
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = SliceCopy<'_>> + '_ {
+        let generation = self.generation;
+        (0..self.entries.len()).map(move |index| self.get(ArenaId { index, generation }).unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arena_test() {
+        let mut arena = BufferArena::with_byte_capacity(64);
+        assert!(arena.is_empty());
+        let a = arena.alloc_from_slice(&[1u8, 2, 3]);
+        let empty = arena.alloc_from_slice::<u16>(&[]);
+        let b = arena.alloc_from_buffer(&VecCopy::from_vec(vec![10u64, 20]));
+        assert_eq!(arena.len(), 3);
+        // Data is padded to the alignment of each element type.
+        assert_eq!(arena.num_bytes(), 8 + 16);
+        assert_eq!(arena.get(b).unwrap().as_bytes().as_ptr() as usize % 8, 0);
+
+        assert!(arena.get(empty).unwrap().is_empty());
+        assert_eq!(
+            arena.get(empty).unwrap().element_type_id(),
+            TypeId::of::<u16>()
+        );
+        for x in arena.get_mut(a).unwrap().iter_mut::<u8>().unwrap() {
+            *x *= 2;
+        }
+        assert_eq!(arena.get(a).unwrap().as_slice::<u8>().unwrap(), &[2, 4, 6]);
+        assert_eq!(arena.get(b).unwrap().as_slice::<u64>().unwrap(), &[10, 20]);
+        assert_eq!(
+            arena.iter().map(|buf| buf.len()).collect::<Vec<_>>(),
+            vec![3, 0, 2]
+        );
+
+        let capacity = arena.byte_capacity();
+        arena.reset();
+        assert!(arena.is_empty());
+        assert!(arena.get(a).is_none());
+        assert!(arena.get_mut(b).is_none());
+        assert_eq!(arena.byte_capacity(), capacity);
+
+        // Handles from before the reset don't refer to new buffers.
+        let c = arena.alloc_from_slice(&[1u8]);
+        assert_ne!(a, c);
+        assert!(arena.get(a).is_none());
+        assert_eq!(arena.get(c).unwrap().len(), 1);
+    }
+}
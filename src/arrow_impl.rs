@@ -0,0 +1,116 @@
+//! This module implements conversions between buffers and Apache Arrow primitive arrays.
+//!
+//! Both buffers and Arrow arrays store their elements contiguously, so conversions reuse the
+//! existing allocation whenever the memory layouts match. Otherwise, the elements are copied in
+//! bulk.
+
+use std::any::TypeId;
+use std::sync::Arc;
+
+use arrow_array::types::{
+    ArrowPrimitiveType, Float32Type, Float64Type, Int16Type, Int32Type, Int64Type, Int8Type,
+    UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+};
+use arrow_array::{Array, ArrayRef, PrimitiveArray};
+use arrow_buffer::ScalarBuffer;
+
+use crate::VecCopy;
+
+impl VecCopy {
+    /// Convert this buffer into an Arrow primitive array with elements of type `T::Native`.
+    ///
+    /// The allocation of the buffer is reused if its alignment matches the element type.
+    /// Returns `None` if `T::Native` doesn't match the element type of the buffer.
+    ///
+    /// # Examples
+    /// ```
+    /// use arrow_array::{types::Float32Type, Array};
+    /// use data_buffer::VecCopy;
+    /// let buf = VecCopy::from_vec(vec![1.0f32, 2.0, 3.0]);
+    /// let array = buf.into_arrow::<Float32Type>().unwrap();
+    /// assert_eq!(array.len(), 3);
+    /// assert_eq!(array.value(1), 2.0);
+    /// let buf = VecCopy::from_arrow(array).unwrap();
+    /// assert_eq!(buf.as_slice::<f32>().unwrap(), &[1.0, 2.0, 3.0]);
+    /// ```
+    pub fn into_arrow<T: ArrowPrimitiveType>(self) -> Option<PrimitiveArray<T>> {
+        let vec = self.into_vec::<T::Native>()?;
+        Some(PrimitiveArray::new(ScalarBuffer::from(vec), None))
+    }
+
+    /// Convert this buffer into a type-erased Arrow array.
+    ///
+    /// This supports buffers of fixed size integer and floating point types, and returns `None`
+    /// for all other element types.
+    pub fn into_arrow_array(self) -> Option<ArrayRef> {
+        macro_rules! dispatch {
+            ($($t:ty),*) => {
+                $(
+                    if self.element_type_id() == TypeId::of::<<$t as ArrowPrimitiveType>::Native>() {
+                        return Some(Arc::new(self.into_arrow::<$t>()?));
+                    }
+                )*
+            };
+        }
+        dispatch!(
+            Int8Type,
+            Int16Type,
+            Int32Type,
+            Int64Type,
+            UInt8Type,
+            UInt16Type,
+            UInt32Type,
+            UInt64Type,
+            Float32Type,
+            Float64Type
+        );
+        None
+    }
+
+    /// Construct a buffer from an Arrow primitive array.
+    ///
+    /// The allocation of the array is reused if it is not shared and was allocated with the
+    /// alignment of the element type. Returns `None` if the array contains nulls, which cannot be
+    /// represented in a buffer.
+    pub fn from_arrow<T: ArrowPrimitiveType>(array: PrimitiveArray<T>) -> Option<Self> {
+        if array.null_count() > 0 {
+            return None;
+        }
+        let (_, values, _) = array.into_parts();
+        let vec = values
+            .into_inner()
+            .into_vec::<T::Native>()
+            .unwrap_or_else(|buffer| buffer.typed_data::<T::Native>().to_vec());
+        Some(VecCopy::from_vec(vec))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::Int64Array;
+
+    #[test]
+    fn arrow_test() {
+        let buf = VecCopy::from_vec(vec![1i64, 2, 3]);
+        let ptr = buf.as_bytes().as_ptr();
+        assert!(buf.clone().into_arrow::<Int32Type>().is_none());
+        let array: Int64Array = buf.into_arrow().unwrap();
+        assert_eq!(array.values().as_ptr() as *const u8, ptr);
+        let buf = VecCopy::from_arrow(array).unwrap();
+        assert_eq!(buf.as_bytes().as_ptr(), ptr);
+        assert_eq!(buf.as_slice::<i64>().unwrap(), &[1, 2, 3]);
+
+        // Arrays allocated by Arrow and slices of arrays are copied.
+        let array = Int64Array::from_iter_values(0..10).slice(2, 3);
+        let buf = VecCopy::from_arrow(array).unwrap();
+        assert_eq!(buf.as_slice::<i64>().unwrap(), &[2, 3, 4]);
+
+        let array = Int64Array::from(vec![Some(1), None]);
+        assert!(VecCopy::from_arrow(array).is_none());
+
+        let array = VecCopy::from_vec(vec![1.5f64]).into_arrow_array().unwrap();
+        assert_eq!(array.data_type(), &Float64Type::DATA_TYPE);
+        assert!(VecCopy::from_vec(vec!['a']).into_arrow_array().is_none());
+    }
+}
@@ -0,0 +1,108 @@
+//! This module implements safe reinterpretation of buffers of plain-old-data with `bytemuck`.
+//!
+//! Any bit pattern is a valid value of a `Pod` type, so reading the bytes of a buffer as a `Pod`
+//! type can only fail if the size or alignment of the data doesn't fit, which is checked at run
+//! time. This makes the unsafe `reinterpret_*` methods unnecessary for plain-old-data.
+
+use std::mem::size_of;
+
+use bytemuck::Pod;
+
+use crate::VecCopy;
+
+impl VecCopy {
+    /// Borrow the buffer data as a slice of the plain-old-data type `T`.
+    ///
+    /// Unlike `as_slice`, `T` doesn't need to match the element type of the buffer. Returns
+    /// `None` if the number of bytes stored in the buffer is not a multiple of the size of `T`, or
+    /// if the data is not sufficiently aligned for `T`.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let buf = VecCopy::from_vec(vec![[1u16, 2], [3, 4]]);
+    /// assert_eq!(buf.cast_slice::<u16>(), Some(&[1u16, 2, 3, 4][..]));
+    /// assert_eq!(buf.cast_slice::<[u8; 3]>(), None);
+    /// ```
+    #[inline]
+    pub fn cast_slice<T: Pod>(&self) -> Option<&[T]> {
+        bytemuck::try_cast_slice(self.as_bytes()).ok()
+    }
+
+    /// Move the buffer data into a `Vec` of the plain-old-data type `T`.
+    ///
+    /// The allocation is reused if it is compatible with `Vec<T>`, otherwise the data is copied.
+    /// Returns `None` if the number of bytes stored in the buffer is not a multiple of the size
+    /// of `T`.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let buf = VecCopy::from_vec(vec![1u32, 2]);
+    /// let bytes: Vec<u8> = buf.cast_into_vec_pod().unwrap();
+    /// assert_eq!(bytes.len(), 8);
+    /// ```
+    #[inline]
+    pub fn cast_into_vec_pod<T: Pod>(self) -> Option<Vec<T>> {
+        if size_of::<T>() == 0 || self.data.len() % size_of::<T>() != 0 {
+            return None;
+        }
+        // This is safe since any sequence of bytes of the right length is a valid `Vec<T>`.
+        Some(unsafe { self.reinterpret_into_vec() })
+    }
+
+    /// Construct a buffer with elements of the plain-old-data type `T` by copying the bytes of
+    /// the given slice of plain-old-data.
+    ///
+    /// Returns `None` if the number of bytes in `slice` is not a multiple of the size of `T`.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let bytes = 42.0f32.to_ne_bytes();
+    /// let buf = VecCopy::from_pod_slice::<f32, _>(&bytes).unwrap();
+    /// assert_eq!(buf.as_slice::<f32>().unwrap(), &[42.0]);
+    /// ```
+    pub fn from_pod_slice<T: Pod + PartialEq, S: Pod>(slice: &[S]) -> Option<Self> {
+        let bytes: &[u8] = bytemuck::cast_slice(slice);
+        if size_of::<T>() == 0 || bytes.len() % size_of::<T>() != 0 {
+            return None;
+        }
+        let mut buf = VecCopy::with_capacity::<T>(bytes.len() / size_of::<T>());
+        buf.data.extend_from_slice(bytes);
+        Some(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pod_cast_test() {
+        let buf = VecCopy::from_vec(vec![0x0102_0304u32, 0x0506_0708]);
+        let halves = buf.cast_slice::<u16>().unwrap();
+        assert_eq!(halves.len(), 4);
+        assert_eq!(buf.cast_slice::<[u8; 3]>(), None);
+        assert_eq!(
+            buf.cast_slice::<[u32; 2]>(),
+            Some(&[[0x0102_0304, 0x0506_0708]][..])
+        );
+
+        let bytes = buf.as_bytes().to_vec();
+        let new_buf = VecCopy::from_pod_slice::<u32, _>(&bytes).unwrap();
+        assert_eq!(new_buf, buf);
+        assert!(VecCopy::from_pod_slice::<u32, _>(&bytes[1..]).is_none());
+        assert_eq!(
+            VecCopy::from_pod_slice::<u32, u32>(&[1, 2]).unwrap(),
+            VecCopy::from_vec(vec![1u32, 2])
+        );
+
+        assert_eq!(new_buf.clone().cast_into_vec_pod::<u8>(), Some(bytes));
+        assert_eq!(new_buf.clone().cast_into_vec_pod::<[u8; 3]>(), None);
+        assert_eq!(
+            new_buf.cast_into_vec_pod::<u32>(),
+            Some(vec![0x0102_0304, 0x0506_0708])
+        );
+    }
+}
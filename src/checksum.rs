@@ -0,0 +1,63 @@
+//! This module implements the CRC-32 checksum used to detect corrupted buffer data.
+//!
+//! This is the common CRC-32 (ISO-HDLC) variant used by zlib, PNG and many other formats, so
+//! checksums can be verified with standard tools.
+
+use crate::DecodeError;
+
+/// The lookup table of the reflected CRC-32 polynomial `0xEDB88320`.
+const TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// Compute the CRC-32 checksum of the given bytes.
+pub(crate) fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0u32, |crc, &byte| {
+        TABLE[((crc ^ byte as u32) & 0xFF) as usize] ^ (crc >> 8)
+    })
+}
+
+/// Check that the given bytes have the expected checksum.
+pub(crate) fn verify(bytes: &[u8], expected: u32) -> Result<(), DecodeError> {
+    let found = crc32(bytes);
+    if found == expected {
+        Ok(())
+    } else {
+        Err(DecodeError::CorruptData { expected, found })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crc32_test() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert!(verify(b"123456789", 0xCBF4_3926).is_ok());
+        assert_eq!(
+            verify(b"123456780", 0xCBF4_3926),
+            Err(DecodeError::CorruptData {
+                expected: 0xCBF4_3926,
+                found: crc32(b"123456780")
+            })
+        );
+    }
+}
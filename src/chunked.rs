@@ -0,0 +1,293 @@
+//! This module defines a buffer stored in fixed-size segments.
+//!
+//! Growing a `VecCopy` past its capacity reallocates and copies all of its data, which is slow and
+//! temporarily doubles the memory footprint of very large buffers. `ChunkedVecCopy` instead stores
+//! its elements in a list of separately allocated segments of equal capacity, so growing it only
+//! ever allocates a new segment. The segments can be consolidated into a single contiguous
+//! `VecCopy` when needed.
+
+use std::any::{Any, TypeId};
+
+use crate::{Elem, VecCopy};
+
+/// A buffer of elements of a single type stored in fixed-size segments.
+///
+/// Every segment except the last holds exactly `chunk_len` elements.
+///
+/// # Examples
+/// ```
+/// use data_buffer::{ChunkedVecCopy, VecCopy};
+/// let mut buf = ChunkedVecCopy::with_type::<u32>(2);
+/// for i in 0..5u32 {
+///     buf.push(i).unwrap();
+/// }
+/// assert_eq!(buf.num_chunks(), 3);
+/// assert_eq!(buf.get::<u32>(3), Some(3));
+/// assert_eq!(buf.into_vec_copy(), VecCopy::from_vec(vec![0u32, 1, 2, 3, 4]));
+/// ```
+#[derive(Clone, Debug)]
+pub struct ChunkedVecCopy {
+    /// The segments storing the elements of this buffer.
+    chunks: Vec<VecCopy>,
+    /// An empty buffer used to construct new segments.
+    empty: VecCopy,
+    /// The number of elements in each full segment.
+    chunk_len: usize,
+}
+
+impl ChunkedVecCopy {
+    /// Construct an empty buffer of elements of type `T` stored in segments of `chunk_len`
+    /// elements.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `chunk_len` is zero.
+    #[inline]
+    pub fn with_type<T: Elem>(chunk_len: usize) -> Self {
+        ChunkedVecCopy::with_type_from(&VecCopy::with_type::<T>(), chunk_len)
+    }
+
+    /// Construct an empty buffer with the same element type and captured functions as the given
+    /// buffer, stored in segments of `chunk_len` elements.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `chunk_len` is zero.
+    #[inline]
+    pub fn with_type_from(other: &VecCopy, chunk_len: usize) -> Self {
+        assert_ne!(chunk_len, 0, "chunk length must be non-zero");
+        ChunkedVecCopy {
+            chunks: Vec::new(),
+            empty: VecCopy::with_type_from(other),
+            chunk_len,
+        }
+    }
+
+    /// Get the number of elements stored in this buffer.
+    #[inline]
+    pub fn len(&self) -> usize {
+        match self.chunks.last() {
+            Some(last) => (self.chunks.len() - 1) * self.chunk_len + last.len(),
+            None => 0,
+        }
+    }
+
+    /// Check if there are any elements stored in this buffer.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.chunks.is_empty()
+    }
+
+    /// Get the number of elements in each full segment.
+    #[inline]
+    pub fn chunk_len(&self) -> usize {
+        self.chunk_len
+    }
+
+    /// Get the number of allocated segments.
+    #[inline]
+    pub fn num_chunks(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Get the `TypeId` of the elements stored in this buffer.
+    #[inline]
+    pub fn element_type_id(&self) -> TypeId {
+        self.empty.element_type_id()
+    }
+
+    /// Get the size of the element type in bytes.
+    #[inline]
+    pub fn element_size(&self) -> usize {
+        self.empty.element_size()
+    }
+
+    /// Remove all elements and segments from this buffer.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+    }
+
+    /// Get a segment with room for at least one more element.
+    fn last_chunk_with_room(&mut self) -> &mut VecCopy {
+        match self.chunks.last() {
+            Some(last) if last.len() < self.chunk_len => {}
+            _ => {
+                let mut chunk = VecCopy::with_type_from(&self.empty);
+                chunk.reserve_exact(self.chunk_len);
+                self.chunks.push(chunk);
+            }
+        }
+        self.chunks.last_mut().unwrap()
+    }
+
+    /// Add an element to this buffer.
+    ///
+    /// Returns `None` if `T` doesn't match the type stored by this buffer.
+    #[inline]
+    pub fn push<T: Elem>(&mut self, element: T) -> Option<&mut Self> {
+        self.empty.check_ref::<T>()?;
+        self.last_chunk_with_room().push(element)?;
+        Some(self)
+    }
+
+    /// Add all elements of the given slice to this buffer.
+    ///
+    /// Returns `None` if `T` doesn't match the type stored by this buffer.
+    pub fn extend_from_slice<T: Elem>(&mut self, mut slice: &[T]) -> Option<&mut Self> {
+        self.empty.check_ref::<T>()?;
+        while !slice.is_empty() {
+            let chunk_len = self.chunk_len;
+            let chunk = self.last_chunk_with_room();
+            let (head, tail) = slice.split_at((chunk_len - chunk.len()).min(slice.len()));
+            chunk.extend_from_slice(head)?;
+            slice = tail;
+        }
+        Some(self)
+    }
+
+    /// Get a reference to the `i`'th element of this buffer.
+    ///
+    /// Returns `None` if `T` doesn't match the type stored by this buffer or if `i` is out of
+    /// bounds.
+    #[inline]
+    pub fn get_ref<T: Any>(&self, i: usize) -> Option<&T> {
+        self.chunks
+            .get(i / self.chunk_len)?
+            .get_ref(i % self.chunk_len)
+    }
+
+    /// Get a mutable reference to the `i`'th element of this buffer.
+    ///
+    /// Returns `None` if `T` doesn't match the type stored by this buffer or if `i` is out of
+    /// bounds.
+    #[inline]
+    pub fn get_mut<T: Any>(&mut self, i: usize) -> Option<&mut T> {
+        let chunk_len = self.chunk_len;
+        self.chunks.get_mut(i / chunk_len)?.get_mut(i % chunk_len)
+    }
+
+    /// Get a copy of the `i`'th element of this buffer.
+    ///
+    /// Returns `None` if `T` doesn't match the type stored by this buffer or if `i` is out of
+    /// bounds.
+    #[inline]
+    pub fn get<T: Elem>(&self, i: usize) -> Option<T> {
+        self.get_ref(i).copied()
+    }
+
+    /// Get the bytes of the `i`'th element of this buffer.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `i` is out of bounds.
+    #[inline]
+    pub fn get_bytes(&self, i: usize) -> &[u8] {
+        let len = self.len();
+        assert!(i < len, "index {} is out of bounds for length {}", i, len);
+        self.chunks[i / self.chunk_len].get_bytes(i % self.chunk_len)
+    }
+
+    /// Iterate over the elements of this buffer in order.
+    ///
+    /// Returns `None` if `T` doesn't match the type stored by this buffer.
+    #[inline]
+    pub fn iter<T: Any>(&self) -> Option<impl Iterator<Item = &T> + '_> {
+        self.empty.check_ref::<T>()?;
+        Some(
+            self.chunks
+                .iter()
+                .flat_map(|chunk| chunk.as_slice::<T>().unwrap()),
+        )
+    }
+
+    /// Iterate over the bytes of each element of this buffer in order.
+    #[inline]
+    pub fn byte_chunks(&self) -> impl Iterator<Item = &[u8]> + '_ {
+        self.chunks.iter().flat_map(VecCopy::byte_chunks)
+    }
+
+    /// Iterate over the segments of this buffer.
+    #[inline]
+    pub fn chunks(&self) -> impl ExactSizeIterator<Item = &VecCopy> + '_ {
+        self.chunks.iter()
+    }
+
+    /// Copy the elements of this buffer into a single contiguous buffer.
+    pub fn to_vec_copy(&self) -> VecCopy {
+        let mut buf = VecCopy::with_type_from(&self.empty);
+        buf.reserve_exact(self.len());
+        for chunk in &self.chunks {
+            buf.data.extend_from_slice(chunk.as_bytes());
+        }
+        buf
+    }
+
+    /// Consolidate the elements of this buffer into a single contiguous buffer.
+    ///
+    /// A buffer with a single segment is returned without copying.
+    pub fn into_vec_copy(mut self) -> VecCopy {
+        if self.chunks.len() == 1 {
+            self.chunks.pop().unwrap()
+        } else {
+            self.to_vec_copy()
+        }
+    }
+}
+
+impl From<ChunkedVecCopy> for VecCopy {
+    #[inline]
+    fn from(buf: ChunkedVecCopy) -> Self {
+        buf.into_vec_copy()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunked_test() {
+        let mut buf = ChunkedVecCopy::with_type::<u16>(3);
+        assert!(buf.is_empty());
+        assert_eq!(buf.len(), 0);
+        assert!(buf.push(1u32).is_none());
+        assert!(buf.extend_from_slice(&[1u8]).is_none());
+        assert!(buf.iter::<u32>().is_none());
+        assert_eq!(buf.to_vec_copy(), VecCopy::with_type::<u16>());
+
+        buf.push(0u16).unwrap();
+        buf.extend_from_slice(&[1u16, 2, 3, 4, 5, 6]).unwrap();
+        buf.push(7u16).unwrap();
+        assert_eq!(buf.len(), 8);
+        assert_eq!(buf.num_chunks(), 3);
+        assert_eq!(
+            buf.chunks().map(VecCopy::len).collect::<Vec<_>>(),
+            vec![3, 3, 2]
+        );
+        assert_eq!(buf.get::<u16>(4), Some(4));
+        assert_eq!(buf.get::<u16>(8), None);
+        assert_eq!(buf.get_ref::<u32>(0), None);
+        *buf.get_mut::<u16>(5).unwrap() = 50;
+        assert_eq!(buf.get_bytes(5), &50u16.to_ne_bytes());
+        assert_eq!(buf.byte_chunks().count(), 8);
+        assert_eq!(
+            buf.iter::<u16>().unwrap().copied().collect::<Vec<_>>(),
+            vec![0, 1, 2, 3, 4, 50, 6, 7]
+        );
+
+        let contiguous = VecCopy::from(buf.clone());
+        assert_eq!(
+            contiguous.as_slice::<u16>().unwrap(),
+            &[0, 1, 2, 3, 4, 50, 6, 7]
+        );
+        buf.clear();
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn chunked_zero_len_test() {
+        ChunkedVecCopy::with_type::<u8>(0);
+    }
+}
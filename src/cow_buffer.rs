@@ -0,0 +1,143 @@
+//! This module defines a copy-on-write buffer.
+//!
+//! Buffers are often cloned defensively but rarely modified afterwards. Clones of a `CowBuffer`
+//! share their data until one of them is mutably accessed, at which point that clone makes its
+//! own copy of the data.
+
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+
+use crate::{ArcBuffer, VecCopy};
+
+/// A `VecCopy` whose data is shared between clones until it is modified.
+///
+/// This dereferences to the underlying buffer. Mutable dereferencing copies the data first if it
+/// is shared with other clones.
+///
+/// # Examples
+/// ```
+/// use data_buffer::{CowBuffer, VecCopy};
+/// let a = CowBuffer::from(VecCopy::from_vec(vec![1u32, 2, 3]));
+/// let mut b = a.clone();
+/// assert!(b.is_shared());
+/// b.push(4u32); // Copies the data before modifying it.
+/// assert!(!b.is_shared());
+/// assert_eq!(a.as_slice::<u32>().unwrap(), &[1, 2, 3]);
+/// assert_eq!(b.as_slice::<u32>().unwrap(), &[1, 2, 3, 4]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd)]
+pub struct CowBuffer(Arc<VecCopy>);
+
+impl CowBuffer {
+    /// Construct a copy-on-write buffer from the given buffer.
+    #[inline]
+    pub fn new(buf: VecCopy) -> Self {
+        CowBuffer(Arc::new(buf))
+    }
+
+    /// Returns `true` if the data of this buffer is shared with other buffers.
+    #[inline]
+    pub fn is_shared(&self) -> bool {
+        Arc::strong_count(&self.0) > 1
+    }
+
+    /// Mutably borrow the underlying buffer, copying its data first if it is shared.
+    #[inline]
+    pub fn to_mut(&mut self) -> &mut VecCopy {
+        Arc::make_mut(&mut self.0)
+    }
+
+    /// Convert into an owned buffer, which is copied only if the data is shared.
+    #[inline]
+    pub fn into_vec_copy(self) -> VecCopy {
+        Arc::try_unwrap(self.0).unwrap_or_else(|shared| VecCopy::clone(&shared))
+    }
+}
+
+impl Deref for CowBuffer {
+    type Target = VecCopy;
+    #[inline]
+    fn deref(&self) -> &VecCopy {
+        &self.0
+    }
+}
+
+impl DerefMut for CowBuffer {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut VecCopy {
+        self.to_mut()
+    }
+}
+
+impl AsRef<VecCopy> for CowBuffer {
+    #[inline]
+    fn as_ref(&self) -> &VecCopy {
+        &self.0
+    }
+}
+
+impl From<VecCopy> for CowBuffer {
+    #[inline]
+    fn from(buf: VecCopy) -> Self {
+        CowBuffer::new(buf)
+    }
+}
+
+impl From<CowBuffer> for VecCopy {
+    #[inline]
+    fn from(buf: CowBuffer) -> Self {
+        buf.into_vec_copy()
+    }
+}
+
+/// The data remains shared with existing `ArcBuffer`s until it is modified.
+impl From<ArcBuffer> for CowBuffer {
+    #[inline]
+    fn from(buf: ArcBuffer) -> Self {
+        CowBuffer(buf.0)
+    }
+}
+
+/// The data remains shared with existing `CowBuffer`s, which will copy it when modified.
+impl From<CowBuffer> for ArcBuffer {
+    #[inline]
+    fn from(buf: CowBuffer) -> Self {
+        ArcBuffer(buf.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cow_buffer_test() {
+        let a = CowBuffer::new(VecCopy::from_vec(vec![1u64, 2, 3]));
+        let mut b = a.clone();
+        let ptr = a.as_bytes().as_ptr();
+        assert_eq!(b.as_bytes().as_ptr(), ptr);
+
+        // Reading doesn't copy.
+        assert_eq!(b.get::<u64>(1), Some(2));
+        assert!(a.is_shared() && b.is_shared());
+
+        b.as_mut_slice::<u64>().unwrap()[0] = 10;
+        assert_ne!(b.as_bytes().as_ptr(), ptr);
+        assert!(!a.is_shared() && !b.is_shared());
+        assert_eq!(a.as_slice::<u64>().unwrap(), &[1, 2, 3]);
+        assert_eq!(b.as_slice::<u64>().unwrap(), &[10, 2, 3]);
+
+        // Unshared buffers are modified in place.
+        let ptr = b.as_bytes().as_ptr();
+        b.to_mut().as_mut_slice::<u64>().unwrap()[1] = 20;
+        assert_eq!(b.as_bytes().as_ptr(), ptr);
+        assert_eq!(VecCopy::from(b).as_bytes().as_ptr(), ptr);
+
+        let arc = ArcBuffer::from(a.clone());
+        assert!(a.is_shared());
+        let mut c = CowBuffer::from(arc);
+        c.push(4u64).unwrap();
+        assert_eq!(a.len(), 3);
+        assert_eq!(c.len(), 4);
+    }
+}
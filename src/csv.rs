@@ -0,0 +1,300 @@
+//! This module implements reading and writing a single column of CSV text.
+//!
+//! Text data carries no element type, so the type of the buffer a column is read into is
+//! selected at runtime with a `ScalarType`. Each line holds one row of delimited fields, and the
+//! value in the selected column is optionally surrounded by whitespace and double quotes.
+
+use std::fmt::Display;
+use std::io::{self, BufRead, Write};
+use std::str::FromStr;
+
+use crate::trace::trace_span;
+use crate::{Elem, ParseError, ScalarType, VecCopy};
+
+/// Options controlling how `VecCopy::from_csv_column_with` reads a column of CSV text.
+///
+/// By default the first column of comma separated values is read.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CsvOptions {
+    column: usize,
+    delimiter: char,
+}
+
+impl Default for CsvOptions {
+    #[inline]
+    fn default() -> Self {
+        CsvOptions {
+            column: 0,
+            delimiter: ',',
+        }
+    }
+}
+
+impl CsvOptions {
+    /// Construct the default options.
+    #[inline]
+    pub fn new() -> Self {
+        CsvOptions::default()
+    }
+
+    /// Select the index of the column to read, counted from zero.
+    #[inline]
+    pub fn column(mut self, column: usize) -> Self {
+        self.column = column;
+        self
+    }
+
+    /// Select the character separating the fields of each row.
+    #[inline]
+    pub fn delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+}
+
+/// Get the field at the given column of a row, ignoring delimiters inside double quotes.
+///
+/// Returns `None` if the row has too few fields.
+fn csv_field(line: &str, column: usize, delimiter: char) -> Option<&str> {
+    let mut in_quotes = false;
+    let mut index = 0;
+    let mut start = 0;
+    for (i, c) in line.char_indices() {
+        if c == '"' {
+            in_quotes = !in_quotes;
+        } else if c == delimiter && !in_quotes {
+            if index == column {
+                return Some(&line[start..i]);
+            }
+            index += 1;
+            start = i + c.len_utf8();
+        }
+    }
+    if index == column {
+        Some(&line[start..])
+    } else {
+        None
+    }
+}
+
+/// Parse the selected column of the lines of the given reader into a buffer of elements of type
+/// `T`.
+fn parse_column<T: Elem + FromStr>(
+    reader: impl BufRead,
+    ty: ScalarType,
+    options: &CsvOptions,
+) -> io::Result<VecCopy> {
+    let mut vec = Vec::<T>::new();
+    for (row, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let token = csv_field(&line, options.column, options.delimiter)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("row {} has no column {}", row, options.column),
+                )
+            })?
+            .trim();
+        let unquoted = token
+            .strip_prefix('"')
+            .and_then(|token| token.strip_suffix('"'))
+            .unwrap_or(token)
+            .trim();
+        let value = unquoted.parse().map_err(|_| {
+            let err = ParseError {
+                row,
+                token: token.to_string(),
+                ty,
+            };
+            io::Error::new(io::ErrorKind::InvalidData, err)
+        })?;
+        vec.push(value);
+    }
+    Ok(VecCopy::from_vec(vec))
+}
+
+/// Write the elements of type `T` of the given buffer on separate lines.
+fn write_column<T: Elem + Display>(buf: &VecCopy, mut writer: impl Write) -> io::Result<()> {
+    for x in buf.iter::<T>().unwrap() {
+        writeln!(writer, "{}", x)?;
+    }
+    Ok(())
+}
+
+impl VecCopy {
+    /// Read the first column of comma separated values into a buffer with elements of the given
+    /// type.
+    ///
+    /// See `from_csv_column_with` for details.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::io::BufRead;
+    /// use data_buffer::{ParseError, ScalarType, VecCopy};
+    /// let mut text = "mass\n0.5\n\"1.5\"\n2\n".as_bytes();
+    /// text.read_line(&mut String::new()).unwrap();
+    /// let buf = VecCopy::from_csv_column(text, ScalarType::F32).unwrap();
+    /// assert_eq!(buf.as_slice::<f32>().unwrap(), &[0.5, 1.5, 2.0]);
+    ///
+    /// let err = VecCopy::from_csv_column("1\nx\n".as_bytes(), ScalarType::I32).unwrap_err();
+    /// let err = err.get_ref().unwrap().downcast_ref::<ParseError>().unwrap();
+    /// assert_eq!(err.row, 1);
+    /// ```
+    #[inline]
+    pub fn from_csv_column(reader: impl BufRead, ty: ScalarType) -> io::Result<VecCopy> {
+        VecCopy::from_csv_column_with(reader, ty, &CsvOptions::default())
+    }
+
+    /// Read a column of CSV text into a buffer with elements of the given type, with the given
+    /// options.
+    ///
+    /// Each line holds one row, whose fields are separated by the selected delimiter. The value
+    /// in the selected column may be surrounded by whitespace and double quotes, and delimiters
+    /// inside double quotes are ignored. Blank lines are skipped. A header line must be consumed
+    /// from the reader before calling this function.
+    ///
+    /// A value that cannot be parsed as the given type produces an error of kind `InvalidData`
+    /// wrapping a `ParseError`, which identifies the offending row. A row without the selected
+    /// column also produces an error of kind `InvalidData`.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::{CsvOptions, ScalarType, VecCopy};
+    /// let text = "1;\"a;b\";0.5\n2;c;1.5\n";
+    /// let options = CsvOptions::new().column(2).delimiter(';');
+    /// let buf = VecCopy::from_csv_column_with(text.as_bytes(), ScalarType::F64, &options).unwrap();
+    /// assert_eq!(buf.as_slice::<f64>().unwrap(), &[0.5, 1.5]);
+    /// ```
+    pub fn from_csv_column_with(
+        reader: impl BufRead,
+        ty: ScalarType,
+        options: &CsvOptions,
+    ) -> io::Result<VecCopy> {
+        trace_span!("decode_csv");
+        match ty {
+            ScalarType::U8 => parse_column::<u8>(reader, ty, options),
+            ScalarType::I8 => parse_column::<i8>(reader, ty, options),
+            ScalarType::U16 => parse_column::<u16>(reader, ty, options),
+            ScalarType::I16 => parse_column::<i16>(reader, ty, options),
+            ScalarType::U32 => parse_column::<u32>(reader, ty, options),
+            ScalarType::I32 => parse_column::<i32>(reader, ty, options),
+            ScalarType::U64 => parse_column::<u64>(reader, ty, options),
+            ScalarType::I64 => parse_column::<i64>(reader, ty, options),
+            ScalarType::F32 => parse_column::<f32>(reader, ty, options),
+            ScalarType::F64 => parse_column::<f64>(reader, ty, options),
+            ScalarType::Bool => parse_column::<bool>(reader, ty, options),
+        }
+    }
+
+    /// Write the elements of this buffer as a column of CSV text, one value per line.
+    ///
+    /// Only buffers of primitive scalar types can be written, otherwise an error of kind
+    /// `InvalidInput` is returned. Floating point values are written with the shortest
+    /// representation that reads back to the same value.
+    pub fn write_csv_column(&self, writer: impl Write) -> io::Result<()> {
+        trace_span!("encode_csv", num_bytes = self.data.len());
+        let ty = self.scalar_type().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "element type `{}` is not a scalar type",
+                    self.element_type_name()
+                ),
+            )
+        })?;
+        match ty {
+            ScalarType::U8 => write_column::<u8>(self, writer),
+            ScalarType::I8 => write_column::<i8>(self, writer),
+            ScalarType::U16 => write_column::<u16>(self, writer),
+            ScalarType::I16 => write_column::<i16>(self, writer),
+            ScalarType::U32 => write_column::<u32>(self, writer),
+            ScalarType::I32 => write_column::<i32>(self, writer),
+            ScalarType::U64 => write_column::<u64>(self, writer),
+            ScalarType::I64 => write_column::<i64>(self, writer),
+            ScalarType::F32 => write_column::<f32>(self, writer),
+            ScalarType::F64 => write_column::<f64>(self, writer),
+            ScalarType::Bool => write_column::<bool>(self, writer),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_column_test() {
+        let buf = VecCopy::from_vec(vec![0.1f32, -2.5, f32::INFINITY]);
+        let mut text = Vec::new();
+        buf.write_csv_column(&mut text).unwrap();
+        assert_eq!(String::from_utf8(text.clone()).unwrap(), "0.1\n-2.5\ninf\n");
+        let read = VecCopy::from_csv_column(text.as_slice(), ScalarType::F32).unwrap();
+        assert_eq!(read, buf);
+
+        let text = " 1\r\n\n\"2\"\n  \n3";
+        let read = VecCopy::from_csv_column(text.as_bytes(), ScalarType::U64).unwrap();
+        assert_eq!(read.as_slice::<u64>().unwrap(), &[1, 2, 3]);
+        let read = VecCopy::from_csv_column("true\nfalse".as_bytes(), ScalarType::Bool).unwrap();
+        assert_eq!(read.as_slice::<bool>().unwrap(), &[true, false]);
+        assert!(VecCopy::from_csv_column("".as_bytes(), ScalarType::I8)
+            .unwrap()
+            .is_empty());
+
+        // Rows are counted from zero, including blank lines.
+        let err = VecCopy::from_csv_column("1\n\n300\n".as_bytes(), ScalarType::U8).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            err.get_ref().unwrap().downcast_ref::<ParseError>(),
+            Some(&ParseError {
+                row: 2,
+                token: String::from("300"),
+                ty: ScalarType::U8,
+            })
+        );
+        assert_eq!(
+            err.to_string(),
+            "cannot parse `300` in row 2 as a value of type `u8`"
+        );
+
+        let err = VecCopy::from_vec(vec!['a'])
+            .write_csv_column(&mut Vec::new())
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn csv_column_options_test() {
+        let text = "a,1, \"x,y\" \n\nb,2,\"3\"\n";
+        let options = CsvOptions::new().column(1);
+        let read = VecCopy::from_csv_column_with(text.as_bytes(), ScalarType::I32, &options);
+        assert_eq!(read.unwrap().as_slice::<i32>().unwrap(), &[1, 2]);
+        let options = CsvOptions::new().column(2);
+        let err = VecCopy::from_csv_column_with(text.as_bytes(), ScalarType::I32, &options);
+        assert_eq!(
+            err.unwrap_err()
+                .get_ref()
+                .unwrap()
+                .downcast_ref::<ParseError>(),
+            Some(&ParseError {
+                row: 0,
+                token: String::from("\"x,y\""),
+                ty: ScalarType::I32,
+            })
+        );
+
+        let options = CsvOptions::new().column(1).delimiter('\t');
+        let read =
+            VecCopy::from_csv_column_with("1\t2.5\n3\t-1".as_bytes(), ScalarType::F32, &options);
+        assert_eq!(read.unwrap().as_slice::<f32>().unwrap(), &[2.5, -1.0]);
+
+        // Rows with too few columns are rejected.
+        let options = CsvOptions::new().column(3);
+        let err = VecCopy::from_csv_column_with(text.as_bytes(), ScalarType::I32, &options);
+        let err = err.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "row 0 has no column 3");
+    }
+}
@@ -0,0 +1,485 @@
+//! This module implements configurable formatting of buffers.
+//!
+//! Large buffers are impractical to print in full, for instance in logs. `DisplayOptions`
+//! controls how many elements are shown and how each element is formatted, and
+//! `VecCopy::display_with` applies the options to a numeric buffer. For quick inspection of
+//! runtime typed data, `display_table` prints several buffers side by side as an aligned table.
+
+use std::any::TypeId;
+use std::fmt;
+use std::ops::Range;
+
+use crate::traits::FmtFn;
+use crate::{DataTable, Elem, ScalarType, VecCopy};
+
+/// Options controlling how the elements of a numeric buffer are displayed.
+///
+/// By default all elements are shown, separated by a single space, using the `Display`
+/// formatting of the element type.
+///
+/// # Examples
+/// ```
+/// use data_buffer::{DisplayOptions, VecCopy};
+/// let buf = VecCopy::from_vec((0..100).map(|i| i as f32 / 3.0).collect::<Vec<_>>());
+/// let options = DisplayOptions::new().max_elements(4).precision(2).separator(", ");
+/// assert_eq!(
+///     buf.display_with(&options).unwrap().to_string(),
+///     "0.00, 0.33, ..., 32.67, 33.00"
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DisplayOptions {
+    max_elements: Option<usize>,
+    precision: Option<usize>,
+    separator: String,
+    scientific: bool,
+}
+
+impl Default for DisplayOptions {
+    #[inline]
+    fn default() -> Self {
+        DisplayOptions {
+            max_elements: None,
+            precision: None,
+            separator: String::from(" "),
+            scientific: false,
+        }
+    }
+}
+
+impl DisplayOptions {
+    /// Construct the default options.
+    #[inline]
+    pub fn new() -> Self {
+        DisplayOptions::default()
+    }
+
+    /// Show at most `n` elements.
+    ///
+    /// Longer buffers are shown as the first and last elements separated by an ellipsis (`...`).
+    #[inline]
+    pub fn max_elements(mut self, n: usize) -> Self {
+        self.max_elements = Some(n);
+        self
+    }
+
+    /// Show the given number of digits after the decimal point.
+    ///
+    /// This has no effect on integers unless they are shown in scientific notation.
+    #[inline]
+    pub fn precision(mut self, precision: usize) -> Self {
+        self.precision = Some(precision);
+        self
+    }
+
+    /// Separate elements with the given string.
+    #[inline]
+    pub fn separator(mut self, separator: impl Into<String>) -> Self {
+        self.separator = separator.into();
+        self
+    }
+
+    /// Show elements in scientific notation, like `1.5e3`.
+    #[inline]
+    pub fn scientific(mut self, scientific: bool) -> Self {
+        self.scientific = scientific;
+        self
+    }
+}
+
+/// A function formatting a single element of a specific type from its bytes.
+type FmtElementFn = unsafe fn(&[u8], &DisplayOptions, &mut fmt::Formatter) -> fmt::Result;
+
+/// Write the element of type `T` stored in `bytes` with the given options.
+///
+/// # Safety
+///
+/// `bytes` must be a valid representation of `T`.
+unsafe fn fmt_element<T: Elem + fmt::Display + fmt::LowerExp>(
+    bytes: &[u8],
+    options: &DisplayOptions,
+    f: &mut fmt::Formatter,
+) -> fmt::Result {
+    let x = std::ptr::read_unaligned(bytes.as_ptr() as *const T);
+    match (options.scientific, options.precision) {
+        (true, Some(precision)) => write!(f, "{:.*e}", precision, x),
+        (true, None) => write!(f, "{:e}", x),
+        (false, Some(precision)) => write!(f, "{:.*}", precision, x),
+        (false, None) => write!(f, "{}", x),
+    }
+}
+
+/// Get the function formatting elements of the numeric type with the given `TypeId`.
+fn numeric_fmt_fn(type_id: TypeId) -> Option<FmtElementFn> {
+    Some(match ScalarType::from_type_id(type_id)? {
+        ScalarType::U8 => fmt_element::<u8>,
+        ScalarType::I8 => fmt_element::<i8>,
+        ScalarType::U16 => fmt_element::<u16>,
+        ScalarType::I16 => fmt_element::<i16>,
+        ScalarType::U32 => fmt_element::<u32>,
+        ScalarType::I32 => fmt_element::<i32>,
+        ScalarType::U64 => fmt_element::<u64>,
+        ScalarType::I64 => fmt_element::<i64>,
+        ScalarType::F32 => fmt_element::<f32>,
+        ScalarType::F64 => fmt_element::<f64>,
+        ScalarType::Bool => return None,
+    })
+}
+
+/// Split the indices of `len` elements into the leading and trailing indices shown with the
+/// given options.
+///
+/// The trailing indices are `None` if all elements are shown.
+fn shown_indices(len: usize, options: &DisplayOptions) -> (Range<usize>, Option<Range<usize>>) {
+    match options.max_elements {
+        Some(n) if n < len => (0..n - n / 2, Some(len - n / 2..len)),
+        _ => (0..len, None),
+    }
+}
+
+/// A helper for displaying a numeric buffer with the given options.
+///
+/// This `struct` is created by the `display_with` method on `VecCopy`.
+pub struct DisplayWith<'a> {
+    buf: &'a VecCopy,
+    options: &'a DisplayOptions,
+    fmt_element: FmtElementFn,
+}
+
+impl fmt::Display for DisplayWith<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (head, tail) = shown_indices(self.buf.len(), self.options);
+        let mut first = true;
+        let mut separate = |f: &mut fmt::Formatter| {
+            if first {
+                first = false;
+                Ok(())
+            } else {
+                f.write_str(&self.options.separator)
+            }
+        };
+        for i in head {
+            separate(f)?;
+            // This is safe since the function was chosen for the element type of the buffer.
+            unsafe { (self.fmt_element)(self.buf.get_bytes(i), self.options, f)? };
+        }
+        if let Some(tail) = tail {
+            separate(f)?;
+            f.write_str("...")?;
+            for i in tail {
+                separate(f)?;
+                unsafe { (self.fmt_element)(self.buf.get_bytes(i), self.options, f)? };
+            }
+        }
+        Ok(())
+    }
+}
+
+impl VecCopy {
+    /// Display the elements of this buffer with the given options.
+    ///
+    /// Returns `None` if the element type is not a fixed size integer or floating point type.
+    #[inline]
+    pub fn display_with<'a>(&'a self, options: &'a DisplayOptions) -> Option<DisplayWith<'a>> {
+        Some(DisplayWith {
+            buf: self,
+            options,
+            fmt_element: numeric_fmt_fn(self.element_type_id())?,
+        })
+    }
+
+    /// Format the `i`'th element of this buffer into a string for display in a table.
+    fn element_to_string(&self, i: usize, options: &DisplayOptions) -> String {
+        Element {
+            buf: self,
+            i,
+            options,
+        }
+        .to_string()
+    }
+}
+
+/// A single element of a buffer displayed with the given options.
+///
+/// Numeric elements are formatted with the given options. Other elements are formatted with the
+/// captured `Display` implementation if any, then the captured `Debug` implementation if any, and
+/// as hexadecimal bytes otherwise.
+struct Element<'a> {
+    buf: &'a VecCopy,
+    i: usize,
+    options: &'a DisplayOptions,
+}
+
+impl fmt::Display for Element<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let bytes = self.buf.get_bytes(self.i);
+        // This is safe since the functions were chosen for the element type of the buffer.
+        if let Some(fmt_element) = numeric_fmt_fn(self.buf.element_type_id()) {
+            unsafe { fmt_element(bytes, self.options, f) }
+        } else if let Some(FmtFn(display)) = self.buf.vtable.display {
+            unsafe { display(bytes, f) }
+        } else if let Some(FmtFn(fmt)) = self.buf.vtable.fmt {
+            unsafe { fmt(bytes, f) }
+        } else {
+            f.write_str("0x")?;
+            for byte in bytes {
+                write!(f, "{:02x}", byte)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Prints the elements of the buffer separated by spaces.
+///
+/// Non-numeric elements are printed with the function captured by `with_display` or
+/// `with_debug`, and as hexadecimal bytes if neither was captured.
+impl fmt::Display for VecCopy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let options = DisplayOptions::default();
+        for i in 0..self.len() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            Element {
+                buf: self,
+                i,
+                options: &options,
+            }
+            .fmt(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// The default number of rows shown when displaying a `DataTable`.
+const TABLE_MAX_ROWS: usize = 10;
+
+/// Display the given named buffers as the columns of a table.
+///
+/// Each row shows the elements at one index, preceded by the index itself. The `max_elements`
+/// option limits the number of rows shown, eliding the rows in the middle. Buffers shorter than
+/// others leave their trailing cells empty.
+///
+/// # Examples
+/// ```
+/// use data_buffer::{display_table, DisplayOptions, VecCopy};
+/// let id = VecCopy::from_vec(vec![1u32, 2, 3, 4]);
+/// let mass = VecCopy::from_vec(vec![0.5f32, 1.0, 1.5, 2.0]);
+/// let options = DisplayOptions::new().max_elements(2).precision(1);
+/// let table = display_table(vec![("id", &id), ("mass", &mass)], &options);
+/// assert_eq!(
+///     table.to_string().lines().collect::<Vec<_>>(),
+///     vec![
+///         "      id  mass",
+///         "  0    1   0.5",
+///         "...  ...   ...",
+///         "  3    4   2.0",
+///     ]
+/// );
+/// ```
+pub fn display_table<'a, I>(columns: I, options: &'a DisplayOptions) -> TableDisplay<'a>
+where
+    I: IntoIterator<Item = (&'a str, &'a VecCopy)>,
+{
+    TableDisplay {
+        columns: columns.into_iter().collect(),
+        options,
+    }
+}
+
+/// A helper for displaying buffers as the columns of a table.
+///
+/// This `struct` is created by the `display_table` function and the `display_with` method on
+/// `DataTable`.
+pub struct TableDisplay<'a> {
+    columns: Vec<(&'a str, &'a VecCopy)>,
+    options: &'a DisplayOptions,
+}
+
+impl fmt::Display for TableDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let num_rows = self
+            .columns
+            .iter()
+            .map(|(_, buf)| buf.len())
+            .max()
+            .unwrap_or(0);
+        let (head, tail) = shown_indices(num_rows, self.options);
+        // Rows shown in the table, where `None` marks the elided rows.
+        let rows: Vec<Option<usize>> = match tail {
+            Some(tail) => head
+                .map(Some)
+                .chain(std::iter::once(None))
+                .chain(tail.map(Some))
+                .collect(),
+            None => head.map(Some).collect(),
+        };
+
+        // Build the cells of each column including the header, starting with the index column.
+        let mut cells: Vec<Vec<String>> = Vec::with_capacity(self.columns.len() + 1);
+        cells.push(
+            std::iter::once(String::new())
+                .chain(rows.iter().map(|row| match row {
+                    Some(i) => i.to_string(),
+                    None => String::from("..."),
+                }))
+                .collect(),
+        );
+        for &(name, buf) in &self.columns {
+            cells.push(
+                std::iter::once(name.to_string())
+                    .chain(rows.iter().map(|row| match row {
+                        Some(i) if *i < buf.len() => buf.element_to_string(*i, self.options),
+                        Some(_) => String::new(),
+                        None => String::from("..."),
+                    }))
+                    .collect(),
+            );
+        }
+
+        let widths: Vec<usize> = cells
+            .iter()
+            .map(|column| {
+                column
+                    .iter()
+                    .map(|cell| cell.chars().count())
+                    .max()
+                    .unwrap()
+            })
+            .collect();
+        for line in 0..=rows.len() {
+            if line > 0 {
+                writeln!(f)?;
+            }
+            for (j, column) in cells.iter().enumerate() {
+                if j > 0 {
+                    f.write_str("  ")?;
+                }
+                write!(f, "{:>width$}", column[line], width = widths[j])?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl DataTable {
+    /// Display the columns of this table with the given options.
+    ///
+    /// See `display_table` for details.
+    #[inline]
+    pub fn display_with<'a>(&'a self, options: &'a DisplayOptions) -> TableDisplay<'a> {
+        display_table(self.iter(), options)
+    }
+}
+
+/// Prints the columns of the table with at most 10 rows.
+impl fmt::Display for DataTable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let options = DisplayOptions::new().max_elements(TABLE_MAX_ROWS);
+        self.display_with(&options).fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_with_test() {
+        let buf = VecCopy::from_vec(vec![1u32, 20, 300, 4000, 50000]);
+        let display = |options: &DisplayOptions| buf.display_with(options).unwrap().to_string();
+        assert_eq!(display(&DisplayOptions::new()), "1 20 300 4000 50000");
+        assert_eq!(
+            display(&DisplayOptions::new().max_elements(3).separator(",")),
+            "1,20,...,50000"
+        );
+        assert_eq!(display(&DisplayOptions::new().max_elements(0)), "...");
+        assert_eq!(
+            display(&DisplayOptions::new().max_elements(5)),
+            "1 20 300 4000 50000"
+        );
+        assert_eq!(
+            display(&DisplayOptions::new().scientific(true).precision(1)),
+            "1.0e0 2.0e1 3.0e2 4.0e3 5.0e4"
+        );
+
+        let floats = VecCopy::from_vec(vec![1.5f64, -0.25]);
+        let options = DisplayOptions::new().precision(3);
+        assert_eq!(
+            floats.display_with(&options).unwrap().to_string(),
+            "1.500 -0.250"
+        );
+        let options = DisplayOptions::new().scientific(true);
+        assert_eq!(
+            floats.display_with(&options).unwrap().to_string(),
+            "1.5e0 -2.5e-1"
+        );
+
+        assert_eq!(
+            VecCopy::with_type::<u8>()
+                .display_with(&options)
+                .unwrap()
+                .to_string(),
+            ""
+        );
+        assert!(VecCopy::from_vec(vec![true])
+            .display_with(&options)
+            .is_none());
+    }
+
+    #[test]
+    fn display_test() {
+        assert_eq!(VecCopy::from_vec(vec![1u8, 2, 3]).to_string(), "1 2 3");
+        assert_eq!(VecCopy::from_vec(vec![0.5f32, -1.0]).to_string(), "0.5 -1");
+        assert_eq!(VecCopy::with_type::<f64>().to_string(), "");
+
+        // Non-numeric elements are displayed as hexadecimal bytes unless a formatting function
+        // was captured, with `Display` taking precedence over `Debug`.
+        let buf = VecCopy::from_vec(vec![[0xabu8, 0x01], [0, 0xff]]);
+        assert_eq!(buf.to_string(), "0xab01 0x00ff");
+        assert_eq!(
+            buf.with_debug::<[u8; 2]>().unwrap().to_string(),
+            "[171, 1] [0, 255]"
+        );
+        let buf = VecCopy::from_vec(vec!['x', 'y'])
+            .with_debug::<char>()
+            .unwrap();
+        assert_eq!(buf.to_string(), "'x' 'y'");
+        assert_eq!(buf.with_display::<char>().unwrap().to_string(), "x y");
+    }
+
+    #[test]
+    fn display_table_test() {
+        let mut table = DataTable::new();
+        assert_eq!(table.to_string(), "");
+        table
+            .insert("x", VecCopy::from_vec((0..12).collect::<Vec<i64>>()))
+            .unwrap();
+        table
+            .insert(
+                "flag",
+                VecCopy::from_vec(vec![true; 12])
+                    .with_debug::<bool>()
+                    .unwrap(),
+            )
+            .unwrap();
+        let lines: Vec<String> = table.to_string().lines().map(String::from).collect();
+        assert_eq!(lines.len(), 12);
+        assert_eq!(lines[0], "     flag    x");
+        assert_eq!(lines[1], "  0  true    0");
+        assert_eq!(lines[6], "...   ...  ...");
+        assert_eq!(lines[11], " 11  true   11");
+
+        // Uncaptured non-numeric elements are shown as hexadecimal bytes, and missing elements are
+        // blank.
+        let short = VecCopy::from_vec(vec![[1u8, 2]]);
+        let long = VecCopy::from_vec(vec![1.5f32, 2.5]);
+        let options = DisplayOptions::new().scientific(true);
+        let table = display_table(vec![("a", &short), ("b", &long)], &options);
+        assert_eq!(
+            table.to_string().lines().collect::<Vec<_>>(),
+            vec!["        a      b", "0  0x0102  1.5e0", "1          2.5e0"]
+        );
+    }
+}
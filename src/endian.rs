@@ -0,0 +1,29 @@
+//! This module defines the byte order of serialized buffers.
+
+/// The order of bytes within the scalars of serialized data.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Endianness {
+    /// Least significant byte first.
+    Little,
+    /// Most significant byte first.
+    Big,
+}
+
+impl Endianness {
+    /// The byte order of the target platform.
+    pub const NATIVE: Endianness = if cfg!(target_endian = "big") {
+        Endianness::Big
+    } else {
+        Endianness::Little
+    };
+}
+
+/// Reverse the bytes of each consecutive `scalar_size` byte scalar in the given slice.
+pub(crate) fn swap_bytes(data: &mut [u8], scalar_size: usize) {
+    if scalar_size > 1 {
+        for scalar in data.chunks_exact_mut(scalar_size) {
+            scalar.reverse();
+        }
+    }
+}
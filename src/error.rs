@@ -0,0 +1,364 @@
+//! This module defines the error types returned by the `Result` based (`try_*`) API, by the
+//! decoders of portable buffer data, by conversions from text formats and by `DataTable`.
+
+use std::alloc::Layout;
+use std::any::{Any, TypeId};
+use std::fmt;
+
+use crate::{Endianness, ScalarType, StableTypeId};
+
+/// An error indicating that the requested element type doesn't match the type stored in a buffer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TypeMismatchError {
+    expected: TypeId,
+    expected_name: &'static str,
+    found: TypeId,
+    found_name: Option<&'static str>,
+}
+
+impl TypeMismatchError {
+    /// Construct a new error for the requested type `T`, given the `TypeId` and, if known, the
+    /// type name of the elements actually stored.
+    #[inline]
+    pub(crate) fn new<T: Any>(found: TypeId, found_name: Option<&'static str>) -> Self {
+        TypeMismatchError {
+            expected: TypeId::of::<T>(),
+            expected_name: std::any::type_name::<T>(),
+            found,
+            found_name,
+        }
+    }
+
+    /// The `TypeId` of the requested type.
+    #[inline]
+    pub fn expected(&self) -> TypeId {
+        self.expected
+    }
+
+    /// The name of the requested type.
+    #[inline]
+    pub fn expected_name(&self) -> &'static str {
+        self.expected_name
+    }
+
+    /// The `TypeId` of the type stored in the buffer.
+    #[inline]
+    pub fn found(&self) -> TypeId {
+        self.found
+    }
+
+    /// The name of the type stored in the buffer if it is known.
+    #[inline]
+    pub fn found_name(&self) -> Option<&'static str> {
+        self.found_name
+    }
+}
+
+impl fmt::Display for TypeMismatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "type mismatch: expected `{}`, ", self.expected_name)?;
+        match self.found_name {
+            Some(name) => write!(f, "found `{}`", name),
+            None => write!(f, "found type with {:?}", self.found),
+        }
+    }
+}
+
+impl std::error::Error for TypeMismatchError {}
+
+/// An error produced by the `try_*` accessors of a buffer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AccessError {
+    /// The requested element type doesn't match the type stored in the buffer.
+    TypeMismatch(TypeMismatchError),
+    /// The requested element index is out of bounds.
+    OutOfBounds {
+        /// The requested index.
+        index: usize,
+        /// The number of elements in the buffer.
+        len: usize,
+    },
+}
+
+impl fmt::Display for AccessError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AccessError::TypeMismatch(err) => err.fmt(f),
+            AccessError::OutOfBounds { index, len } => write!(
+                f,
+                "index {} is out of bounds for buffer of length {}",
+                index, len
+            ),
+        }
+    }
+}
+
+impl std::error::Error for AccessError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AccessError::TypeMismatch(err) => Some(err),
+            AccessError::OutOfBounds { .. } => None,
+        }
+    }
+}
+
+impl From<TypeMismatchError> for AccessError {
+    #[inline]
+    fn from(err: TypeMismatchError) -> Self {
+        AccessError::TypeMismatch(err)
+    }
+}
+
+/// An error indicating that a buffer doesn't have the required number of elements, for instance
+/// when it is inserted as a column of a `DataTable`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LengthMismatchError {
+    /// The required number of elements.
+    pub expected: usize,
+    /// The number of elements in the buffer.
+    pub found: usize,
+}
+
+impl fmt::Display for LengthMismatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "expected a buffer with {} elements, found {}",
+            self.expected, self.found
+        )
+    }
+}
+
+impl std::error::Error for LengthMismatchError {}
+
+/// An error indicating that memory for a buffer could not be reserved.
+///
+/// This mirrors `std::collections::TryReserveError`, which cannot be constructed outside of the
+/// standard library.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TryReserveError {
+    /// The requested capacity exceeds the maximum size of an allocation.
+    CapacityOverflow,
+    /// The allocator failed to allocate memory with the given layout.
+    AllocError {
+        /// The layout of the failed allocation.
+        layout: Layout,
+    },
+}
+
+impl fmt::Display for TryReserveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("memory allocation failed")?;
+        match self {
+            TryReserveError::CapacityOverflow => {
+                f.write_str(" because the computed capacity exceeded the maximum")
+            }
+            TryReserveError::AllocError { layout } => write!(
+                f,
+                " because the allocator returned an error for {} bytes aligned to {}",
+                layout.size(),
+                layout.align()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TryReserveError {}
+
+/// An error indicating that portable buffer data, either deserialized or decoded from the raw
+/// binary format, cannot be converted into a buffer in the running program.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DecodeError {
+    /// The data doesn't start with the magic bytes of the raw binary format.
+    InvalidMagic,
+    /// The raw binary format version is not supported by this version of the library.
+    UnsupportedVersion(u16),
+    /// The endianness tag of the raw binary format is invalid.
+    InvalidEndianness(u8),
+    /// The compression method of the raw binary format is not supported, or requires a feature
+    /// that is not enabled.
+    UnsupportedCompression(u8),
+    /// The header flags of the raw binary format have unknown bits set.
+    UnsupportedFlags(u8),
+    /// No type with the given identifier was registered with `register_type`.
+    UnregisteredType(StableTypeId),
+    /// The element size doesn't match the size of the registered type.
+    ElementSizeMismatch {
+        /// The name of the registered type.
+        type_name: &'static str,
+        /// The size of the registered type.
+        expected: usize,
+        /// The element size of the data.
+        found: usize,
+    },
+    /// The element size is zero.
+    ZeroElementSize,
+    /// The element size doesn't fit in the address space of the running program.
+    ElementSizeOverflow(u64),
+    /// The number of data bytes is not a multiple of the element size or is too large.
+    InvalidLength {
+        /// The number of data bytes or elements.
+        len: u64,
+        /// The size of each element.
+        element_size: usize,
+    },
+    /// The data has a foreign byte order, and the registered type has no scalar size to convert
+    /// it with (see `StableType::SCALAR_SIZE`).
+    UnknownScalarLayout {
+        /// The name of the registered type.
+        type_name: &'static str,
+        /// The byte order of the data.
+        endianness: Endianness,
+    },
+    /// The data has a different element type than requested.
+    TypeMismatch {
+        /// The name of the requested type.
+        expected: &'static str,
+        /// The identifier of the element type of the data.
+        found: StableTypeId,
+    },
+    /// The data has a foreign byte order, so it cannot be borrowed without converting it.
+    ForeignEndianness(Endianness),
+    /// The checksum of the data doesn't match the checksum stored with it.
+    CorruptData {
+        /// The stored checksum.
+        expected: u32,
+        /// The checksum of the data.
+        found: u32,
+    },
+    /// The data is not sufficiently aligned to be borrowed as elements with the given alignment.
+    Misaligned {
+        /// The alignment of the elements.
+        align: usize,
+    },
+    /// An element of the data is not a valid value of its type.
+    InvalidValue {
+        /// The name of the element type.
+        type_name: &'static str,
+        /// The index of the invalid element.
+        index: u64,
+    },
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::InvalidMagic => write!(f, "invalid magic bytes"),
+            DecodeError::UnsupportedVersion(version) => {
+                write!(f, "unsupported format version {}", version)
+            }
+            DecodeError::InvalidEndianness(tag) => write!(f, "invalid endianness tag {}", tag),
+            DecodeError::UnsupportedCompression(tag) => {
+                write!(f, "unsupported compression method {}", tag)
+            }
+            DecodeError::UnsupportedFlags(flags) => {
+                write!(f, "unsupported header flags {:#04x}", flags)
+            }
+            DecodeError::UnregisteredType(id) => {
+                write!(f, "unregistered element type with {:?}", id)
+            }
+            DecodeError::ElementSizeMismatch {
+                type_name,
+                expected,
+                found,
+            } => write!(
+                f,
+                "element size {} doesn't match the size {} of the registered type `{}`",
+                found, expected, type_name
+            ),
+            DecodeError::ZeroElementSize => write!(f, "element size is zero"),
+            DecodeError::ElementSizeOverflow(element_size) => {
+                write!(f, "element size {} is too large", element_size)
+            }
+            DecodeError::InvalidLength { len, element_size } => write!(
+                f,
+                "invalid data length {} for elements of size {}",
+                len, element_size
+            ),
+            DecodeError::UnknownScalarLayout {
+                type_name,
+                endianness,
+            } => write!(
+                f,
+                "cannot convert elements of type `{}` from {:?} endian data",
+                type_name, endianness
+            ),
+            DecodeError::TypeMismatch { expected, found } => write!(
+                f,
+                "expected elements of type `{}`, found type with {:?}",
+                expected, found
+            ),
+            DecodeError::ForeignEndianness(endianness) => {
+                write!(f, "cannot borrow {:?} endian data", endianness)
+            }
+            DecodeError::CorruptData { expected, found } => write!(
+                f,
+                "corrupt data: checksum {:#010x} doesn't match the stored checksum {:#010x}",
+                found, expected
+            ),
+            DecodeError::Misaligned { align } => {
+                write!(f, "data is not aligned to {} bytes", align)
+            }
+            DecodeError::InvalidValue { type_name, index } => write!(
+                f,
+                "element {} is not a valid value of type `{}`",
+                index, type_name
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// An error indicating that a text token cannot be parsed as a value of a scalar type.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ParseError {
+    /// The index of the row containing the token.
+    pub row: usize,
+    /// The token that failed to parse.
+    pub token: String,
+    /// The type the token was parsed as.
+    pub ty: ScalarType,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "cannot parse `{}` in row {} as a value of type `{}`",
+            self.token, self.row, self.ty
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// An error indicating that a JSON value cannot be converted into a buffer.
+#[cfg(feature = "json")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum JsonError {
+    /// The value is not a JSON array.
+    NotAnArray,
+    /// The array element at the given index is not representable by the element type.
+    InvalidElement {
+        /// The index of the element.
+        index: usize,
+    },
+}
+
+#[cfg(feature = "json")]
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            JsonError::NotAnArray => write!(f, "expected a JSON array"),
+            JsonError::InvalidElement { index } => write!(
+                f,
+                "array element {} is not representable by the element type",
+                index
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl std::error::Error for JsonError {}
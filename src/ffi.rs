@@ -0,0 +1,236 @@
+//! This module provides a C interface for creating and accessing buffers.
+//!
+//! Buffers are passed across the boundary as opaque `VecCopy` pointers owned by the caller.
+//! Handles created with [`databuffer_new`] must be released with [`databuffer_free`]. A buffer
+//! created in Rust can be handed to C with `Box::into_raw(Box::new(buf))`.
+//!
+//! Element types are identified by the [`ElementType`] enum, whose discriminants are part of the
+//! interface and never change.
+
+use std::any::TypeId;
+use std::os::raw::c_void;
+use std::ptr;
+
+use crate::VecCopy;
+
+/// The element types of buffers that can be created through the C interface.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ElementType {
+    /// `uint8_t` (`u8`).
+    U8 = 0,
+    /// `int8_t` (`i8`).
+    I8 = 1,
+    /// `uint16_t` (`u16`).
+    U16 = 2,
+    /// `int16_t` (`i16`).
+    I16 = 3,
+    /// `uint32_t` (`u32`).
+    U32 = 4,
+    /// `int32_t` (`i32`).
+    I32 = 5,
+    /// `uint64_t` (`u64`).
+    U64 = 6,
+    /// `int64_t` (`i64`).
+    I64 = 7,
+    /// `float` (`f32`).
+    F32 = 8,
+    /// `double` (`f64`).
+    F64 = 9,
+    /// `bool` from `stdbool.h` (`bool`).
+    Bool = 10,
+    /// Any other type, for buffers created in Rust.
+    Unknown = 255,
+}
+
+impl ElementType {
+    /// Get the element type with the given discriminant.
+    ///
+    /// Returns `None` if `tag` is not the discriminant of any element type.
+    pub fn from_tag(tag: u32) -> Option<ElementType> {
+        Some(match tag {
+            0 => ElementType::U8,
+            1 => ElementType::I8,
+            2 => ElementType::U16,
+            3 => ElementType::I16,
+            4 => ElementType::U32,
+            5 => ElementType::I32,
+            6 => ElementType::U64,
+            7 => ElementType::I64,
+            8 => ElementType::F32,
+            9 => ElementType::F64,
+            10 => ElementType::Bool,
+            255 => ElementType::Unknown,
+            _ => return None,
+        })
+    }
+
+    /// Get the element type corresponding to the given `TypeId`.
+    pub fn from_type_id(type_id: TypeId) -> ElementType {
+        macro_rules! types {
+            ($($t:ty => $variant:ident),*) => {
+                $(
+                    if type_id == TypeId::of::<$t>() {
+                        return ElementType::$variant;
+                    }
+                )*
+            };
+        }
+        types!(
+            u8 => U8, i8 => I8, u16 => U16, i16 => I16, u32 => U32, i32 => I32,
+            u64 => U64, i64 => I64, f32 => F32, f64 => F64, bool => Bool
+        );
+        ElementType::Unknown
+    }
+
+    /// Construct an empty buffer with elements of this type.
+    ///
+    /// Returns `None` for `ElementType::Unknown`.
+    pub fn new_buffer(self) -> Option<VecCopy> {
+        Some(match self {
+            ElementType::U8 => VecCopy::with_type::<u8>(),
+            ElementType::I8 => VecCopy::with_type::<i8>(),
+            ElementType::U16 => VecCopy::with_type::<u16>(),
+            ElementType::I16 => VecCopy::with_type::<i16>(),
+            ElementType::U32 => VecCopy::with_type::<u32>(),
+            ElementType::I32 => VecCopy::with_type::<i32>(),
+            ElementType::U64 => VecCopy::with_type::<u64>(),
+            ElementType::I64 => VecCopy::with_type::<i64>(),
+            ElementType::F32 => VecCopy::with_type::<f32>(),
+            ElementType::F64 => VecCopy::with_type::<f64>(),
+            ElementType::Bool => VecCopy::with_type::<bool>(),
+            ElementType::Unknown => return None,
+        })
+    }
+}
+
+/// Create a new empty buffer with the element type given by the discriminant of an
+/// [`ElementType`].
+///
+/// Returns null if `element_type` is `Unknown` or not a valid discriminant. The type is passed
+/// as an integer, since C callers can pass any value for an enum.
+#[no_mangle]
+pub extern "C" fn databuffer_new(element_type: u32) -> *mut VecCopy {
+    match ElementType::from_tag(element_type).and_then(ElementType::new_buffer) {
+        Some(buf) => Box::into_raw(Box::new(buf)),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Free a buffer. Passing null does nothing.
+///
+/// # Safety
+///
+/// `buf` must be null or a buffer handle that has not been freed.
+#[no_mangle]
+pub unsafe extern "C" fn databuffer_free(buf: *mut VecCopy) {
+    if !buf.is_null() {
+        drop(Box::from_raw(buf));
+    }
+}
+
+/// Get the element type of a buffer, or `Unknown` if `buf` is null.
+///
+/// # Safety
+///
+/// `buf` must be null or a valid buffer handle.
+#[no_mangle]
+pub unsafe extern "C" fn databuffer_element_type(buf: *const VecCopy) -> ElementType {
+    match buf.as_ref() {
+        Some(buf) => ElementType::from_type_id(buf.element_type_id()),
+        None => ElementType::Unknown,
+    }
+}
+
+/// Get the number of elements in a buffer, or zero if `buf` is null.
+///
+/// # Safety
+///
+/// `buf` must be null or a valid buffer handle.
+#[no_mangle]
+pub unsafe extern "C" fn databuffer_len(buf: *const VecCopy) -> usize {
+    buf.as_ref().map_or(0, VecCopy::len)
+}
+
+/// Get a pointer to the first element of a buffer, or null if `buf` is null.
+///
+/// The pointer is invalidated when elements are pushed to the buffer or the buffer is freed.
+///
+/// # Safety
+///
+/// `buf` must be null or a valid buffer handle.
+#[no_mangle]
+pub unsafe extern "C" fn databuffer_as_ptr(buf: *const VecCopy) -> *const c_void {
+    match buf.as_ref() {
+        Some(buf) => buf.as_bytes().as_ptr() as *const c_void,
+        None => ptr::null(),
+    }
+}
+
+macro_rules! impl_push {
+    ($($name:ident: $t:ty),*) => {
+        $(
+            #[doc = concat!("Push a value to a buffer of `", stringify!($t), "` elements.")]
+            ///
+            /// Returns `false` if `buf` is null or has a different element type.
+            ///
+            /// # Safety
+            ///
+            /// `buf` must be null or a valid buffer handle.
+            #[no_mangle]
+            pub unsafe extern "C" fn $name(buf: *mut VecCopy, value: $t) -> bool {
+                match buf.as_mut() {
+                    Some(buf) => buf.push(value).is_some(),
+                    None => false,
+                }
+            }
+        )*
+    };
+}
+
+impl_push!(
+    databuffer_push_u8: u8,
+    databuffer_push_i8: i8,
+    databuffer_push_u16: u16,
+    databuffer_push_i16: i16,
+    databuffer_push_u32: u32,
+    databuffer_push_i32: i32,
+    databuffer_push_u64: u64,
+    databuffer_push_i64: i64,
+    databuffer_push_f32: f32,
+    databuffer_push_f64: f64,
+    databuffer_push_bool: bool
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ffi_test() {
+        unsafe {
+            let buf = databuffer_new(ElementType::F64 as u32);
+            assert_eq!(databuffer_element_type(buf), ElementType::F64);
+            assert!(databuffer_push_f64(buf, 1.0));
+            assert!(databuffer_push_f64(buf, 2.5));
+            assert!(!databuffer_push_f32(buf, 3.0));
+            assert_eq!(databuffer_len(buf), 2);
+            let data = std::slice::from_raw_parts(databuffer_as_ptr(buf) as *const f64, 2);
+            assert_eq!(data, &[1.0, 2.5]);
+            databuffer_free(buf);
+
+            assert!(databuffer_new(ElementType::Unknown as u32).is_null());
+            assert!(databuffer_new(11).is_null());
+            assert!(databuffer_new(u32::MAX).is_null());
+            assert!(!databuffer_push_f64(ptr::null_mut(), 1.0));
+            assert_eq!(databuffer_len(ptr::null()), 0);
+            assert!(databuffer_as_ptr(ptr::null()).is_null());
+            databuffer_free(ptr::null_mut());
+
+            let buf = Box::into_raw(Box::new(VecCopy::from_vec(vec!['a'])));
+            assert_eq!(databuffer_element_type(buf), ElementType::Unknown);
+            assert_eq!(databuffer_len(buf), 1);
+            databuffer_free(buf);
+        }
+    }
+}
@@ -0,0 +1,124 @@
+//! This module implements conversions between separate buffers and a single interleaved buffer.
+//!
+//! An interleaved buffer stores one record per row, consisting of the elements of each source
+//! buffer at that row packed back to back without padding. This is the layout expected for
+//! instance by GPU vertex buffers, where each vertex stores all of its attributes together.
+
+use crate::VecCopy;
+
+impl VecCopy {
+    /// Pack the elements of the given equal length buffers into a single interleaved buffer of
+    /// bytes.
+    ///
+    /// Row `i` of the result is the concatenation of the bytes of element `i` of each source, so
+    /// rows are `sources.iter().map(|s| s.element_size()).sum()` bytes long. The result stores
+    /// `u8` elements. Returns `None` if the sources have different lengths.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let positions = VecCopy::from_vec(vec![[0.0f32; 3], [1.0; 3]]);
+    /// let colors = VecCopy::from_vec(vec![[255u8, 0, 0, 255], [0, 255, 0, 255]]);
+    /// let vertices = VecCopy::interleave(&[&positions, &colors]).unwrap();
+    /// assert_eq!(vertices.len(), 2 * (12 + 4));
+    /// assert_eq!(&vertices.as_bytes()[12..16], &[255, 0, 0, 255]);
+    /// ```
+    pub fn interleave(sources: &[&VecCopy]) -> Option<VecCopy> {
+        let len = sources.first().map_or(0, |source| source.len());
+        if sources.iter().any(|source| source.len() != len) {
+            return None;
+        }
+        let stride: usize = sources.iter().map(|source| source.element_size).sum();
+        let mut buf = VecCopy::with_type::<u8>();
+        buf.data.reserve_exact(len * stride);
+        for i in 0..len {
+            for source in sources {
+                buf.data.extend_from_slice(source.get_bytes(i));
+            }
+        }
+        Some(buf)
+    }
+
+    /// Split the bytes of this interleaved buffer into separate buffers with the types of the
+    /// buffers in `layout`.
+    ///
+    /// This is the inverse of `interleave`, where `layout` lists buffers with the element types
+    /// of the fields of each row in order. Only the types of the buffers in `layout` are used,
+    /// along with any captured functions. Returns `None` if the number of bytes in this buffer is
+    /// not a multiple of the row size.
+    ///
+    /// # Safety
+    ///
+    /// The bytes of each field must be a valid representation of the corresponding element type
+    /// in `layout`, as is the case for buffers produced by `interleave`. Otherwise this function
+    /// will cause undefined behavior.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let ids = VecCopy::from_vec(vec![1u32, 2]);
+    /// let flags = VecCopy::from_vec(vec![true, false]);
+    /// let records = VecCopy::interleave(&[&ids, &flags]).unwrap();
+    /// let fields = unsafe { records.deinterleave(&[&ids, &flags]) }.unwrap();
+    /// assert_eq!(fields, vec![ids, flags]);
+    /// ```
+    pub unsafe fn deinterleave(&self, layout: &[&VecCopy]) -> Option<Vec<VecCopy>> {
+        let bytes = self.as_bytes();
+        let stride: usize = layout.iter().map(|field| field.element_size).sum();
+        if layout.is_empty() {
+            return if bytes.is_empty() {
+                Some(Vec::new())
+            } else {
+                None
+            };
+        }
+        let len = bytes.len() / stride;
+        if len * stride != bytes.len() {
+            return None;
+        }
+        let mut fields: Vec<VecCopy> = layout
+            .iter()
+            .map(|field| {
+                let mut buf = VecCopy::with_type_from(field);
+                buf.data.reserve_exact(len * field.element_size);
+                buf
+            })
+            .collect();
+        for row in bytes.chunks_exact(stride) {
+            let mut offset = 0;
+            for field in &mut fields {
+                let size = field.element_size;
+                field.data.extend_from_slice(&row[offset..offset + size]);
+                offset += size;
+            }
+        }
+        Some(fields)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interleave_test() {
+        let a = VecCopy::from_vec(vec![1u16, 2, 3]);
+        let b = VecCopy::from_vec(vec![[4u8; 3], [5; 3], [6; 3]]);
+        assert!(VecCopy::interleave(&[&a, &VecCopy::from_vec(vec![1u8])]).is_none());
+
+        let records = VecCopy::interleave(&[&b, &a]).unwrap();
+        assert_eq!(records.len(), 15);
+        assert_eq!(&records.as_bytes()[5..8], &[5; 3]);
+        assert_eq!(&records.as_bytes()[8..10], &2u16.to_ne_bytes());
+
+        let fields = unsafe { records.deinterleave(&[&b, &a]) }.unwrap();
+        assert_eq!(fields, vec![b.clone(), a.clone()]);
+        assert!(unsafe { records.deinterleave(&[&a, &a]) }.is_none());
+
+        // Degenerate layouts.
+        let empty = VecCopy::interleave(&[]).unwrap();
+        assert!(empty.is_empty());
+        assert_eq!(unsafe { empty.deinterleave(&[]) }, Some(vec![]));
+        assert!(unsafe { records.deinterleave(&[]) }.is_none());
+    }
+}
@@ -0,0 +1,80 @@
+//! This module defines typed iterators that move elements out of a `VecCopy`.
+
+use std::iter::FusedIterator;
+use std::marker::PhantomData;
+use std::mem::size_of;
+
+use crate::Elem;
+
+/// A draining iterator over typed elements of a `VecCopy`.
+///
+/// This `struct` is created by the `drain` method on `VecCopy`.
+pub struct Drain<'a, T> {
+    pub(crate) iter: crate::aligned_vec::Drain<'a>,
+    pub(crate) phantom: PhantomData<T>,
+}
+
+/// An iterator that moves typed elements out of a `VecCopy`.
+///
+/// This `struct` is created by the `into_typed_iter` method on `VecCopy`.
+pub struct IntoIter<T> {
+    pub(crate) iter: crate::aligned_vec::IntoIter,
+    pub(crate) phantom: PhantomData<T>,
+}
+
+// Implement typed iteration over an iterator of bytes, which exposes the remaining bytes via
+// `as_slice`.
+macro_rules! impl_typed_byte_iter {
+    ($iter:ident $(<$lt:lifetime>)?) => {
+        impl<$($lt,)? T: Elem> Iterator for $iter<$($lt,)? T> {
+            type Item = T;
+
+            #[inline]
+            fn next(&mut self) -> Option<T> {
+                let bytes = self.iter.as_slice();
+                if bytes.len() < size_of::<T>() {
+                    return None;
+                }
+                // This is safe since the bytes were stored as a `T`. They are not guaranteed to
+                // be aligned in the original buffer so we read them unaligned.
+                let element = unsafe { std::ptr::read_unaligned(bytes.as_ptr() as *const T) };
+                self.iter.nth(size_of::<T>() - 1);
+                Some(element)
+            }
+
+            #[inline]
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                let n = self.len();
+                (n, Some(n))
+            }
+        }
+
+        impl<$($lt,)? T: Elem> DoubleEndedIterator for $iter<$($lt,)? T> {
+            #[inline]
+            fn next_back(&mut self) -> Option<T> {
+                let bytes = self.iter.as_slice();
+                if bytes.len() < size_of::<T>() {
+                    return None;
+                }
+                let offset = bytes.len() - size_of::<T>();
+                // This is safe for the same reasons as in `next`.
+                let element =
+                    unsafe { std::ptr::read_unaligned(bytes[offset..].as_ptr() as *const T) };
+                self.iter.nth_back(size_of::<T>() - 1);
+                Some(element)
+            }
+        }
+
+        impl<$($lt,)? T: Elem> ExactSizeIterator for $iter<$($lt,)? T> {
+            #[inline]
+            fn len(&self) -> usize {
+                self.iter.len() / size_of::<T>()
+            }
+        }
+
+        impl<$($lt,)? T: Elem> FusedIterator for $iter<$($lt,)? T> {}
+    };
+}
+
+impl_typed_byte_iter!(Drain<'a>);
+impl_typed_byte_iter!(IntoIter);
@@ -0,0 +1,166 @@
+//! This module implements conversions between buffers and JSON arrays.
+//!
+//! Unlike serialization with `serde`, which stores the raw bytes of a buffer, these conversions
+//! produce and consume arrays of JSON numbers and booleans, which can be consumed directly by web
+//! frontends or written in configuration files.
+
+use num_traits::{cast, NumCast};
+use serde_json::{Number, Value};
+
+use crate::{Elem, JsonError, ScalarType, VecCopy};
+
+/// Convert a floating point number into a JSON number, or `null` if it is not finite.
+fn float_to_json(x: f64) -> Value {
+    Number::from_f64(x).map_or(Value::Null, Value::Number)
+}
+
+/// Convert a JSON number into an integer of type `T` if it is representable.
+fn int_from_json<T: NumCast>(value: &Value) -> Option<T> {
+    match value.as_u64() {
+        Some(x) => cast(x),
+        None => cast(value.as_i64()?),
+    }
+}
+
+/// Convert a JSON number into a floating point number, where `null` is read as NaN.
+fn float_from_json(value: &Value) -> Option<f64> {
+    match value {
+        Value::Null => Some(f64::NAN),
+        _ => value.as_f64(),
+    }
+}
+
+/// Collect the given JSON values into a buffer of elements of type `T`, converting each with `f`.
+fn collect_json<T: Elem>(
+    values: &[Value],
+    f: impl Fn(&Value) -> Option<T>,
+) -> Result<VecCopy, JsonError> {
+    let vec = values
+        .iter()
+        .enumerate()
+        .map(|(index, value)| f(value).ok_or(JsonError::InvalidElement { index }))
+        .collect::<Result<Vec<T>, _>>()?;
+    Ok(VecCopy::from_vec(vec))
+}
+
+impl VecCopy {
+    /// Convert the elements of this buffer into a JSON array of numbers or booleans.
+    ///
+    /// Floating point numbers that are not finite are converted into `null`, since they cannot be
+    /// represented in JSON. Returns `None` if the elements are not of a primitive scalar type.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let buf = VecCopy::from_vec(vec![0.5f32, 1.0, f32::NAN]);
+    /// assert_eq!(buf.to_json_array().unwrap().to_string(), "[0.5,1.0,null]");
+    /// ```
+    pub fn to_json_array(&self) -> Option<Value> {
+        fn elements<T: Elem>(buf: &VecCopy, f: impl Fn(T) -> Value) -> Value {
+            Value::Array(buf.iter::<T>().unwrap().map(|&x| f(x)).collect())
+        }
+        Some(match self.scalar_type()? {
+            ScalarType::U8 => elements::<u8>(self, Value::from),
+            ScalarType::I8 => elements::<i8>(self, Value::from),
+            ScalarType::U16 => elements::<u16>(self, Value::from),
+            ScalarType::I16 => elements::<i16>(self, Value::from),
+            ScalarType::U32 => elements::<u32>(self, Value::from),
+            ScalarType::I32 => elements::<i32>(self, Value::from),
+            ScalarType::U64 => elements::<u64>(self, Value::from),
+            ScalarType::I64 => elements::<i64>(self, Value::from),
+            // Convert through the shortest decimal representation so that, for instance, `0.1f32`
+            // is written as `0.1` rather than `0.10000000149011612`.
+            ScalarType::F32 => elements::<f32>(self, |x| {
+                float_to_json(x.to_string().parse().unwrap_or(f64::NAN))
+            }),
+            ScalarType::F64 => elements::<f64>(self, float_to_json),
+            ScalarType::Bool => elements::<bool>(self, Value::Bool),
+        })
+    }
+
+    /// Construct a buffer with elements of the given type from a JSON array.
+    ///
+    /// Integer elements must be JSON integers within the range of the integer type. Floating
+    /// point elements may be any JSON numbers, or `null`, which is read as NaN. Boolean elements
+    /// must be JSON booleans.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::{ScalarType, VecCopy};
+    /// let value = serde_json::json!([1, 2, 3]);
+    /// let buf = VecCopy::from_json_array(ScalarType::U16, &value).unwrap();
+    /// assert_eq!(buf.as_slice::<u16>().unwrap(), &[1, 2, 3]);
+    ///
+    /// // Values must be representable by the element type.
+    /// assert!(VecCopy::from_json_array(ScalarType::U8, &serde_json::json!([256])).is_err());
+    /// ```
+    pub fn from_json_array(ty: ScalarType, value: &Value) -> Result<VecCopy, JsonError> {
+        let values = value.as_array().ok_or(JsonError::NotAnArray)?;
+        match ty {
+            ScalarType::U8 => collect_json(values, int_from_json::<u8>),
+            ScalarType::I8 => collect_json(values, int_from_json::<i8>),
+            ScalarType::U16 => collect_json(values, int_from_json::<u16>),
+            ScalarType::I16 => collect_json(values, int_from_json::<i16>),
+            ScalarType::U32 => collect_json(values, int_from_json::<u32>),
+            ScalarType::I32 => collect_json(values, int_from_json::<i32>),
+            ScalarType::U64 => collect_json(values, int_from_json::<u64>),
+            ScalarType::I64 => collect_json(values, int_from_json::<i64>),
+            ScalarType::F32 => collect_json(values, |v| float_from_json(v).map(|x| x as f32)),
+            ScalarType::F64 => collect_json(values, float_from_json),
+            ScalarType::Bool => collect_json(values, Value::as_bool),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn json_array_test() {
+        let buf = VecCopy::from_vec(vec![-1i64, 0, i64::MAX]);
+        let value = buf.to_json_array().unwrap();
+        assert_eq!(value, json!([-1, 0, i64::MAX]));
+        let read = VecCopy::from_json_array(ScalarType::I64, &value).unwrap();
+        assert_eq!(
+            read.as_slice::<i64>().unwrap(),
+            buf.as_slice::<i64>().unwrap()
+        );
+
+        let buf = VecCopy::from_vec(vec![0.1f32, f32::INFINITY]);
+        let value = buf.to_json_array().unwrap();
+        assert_eq!(value.to_string(), "[0.1,null]");
+        let read = VecCopy::from_json_array(ScalarType::F32, &value).unwrap();
+        assert_eq!(read.get::<f32>(0), Some(0.1));
+        assert!(read.get::<f32>(1).unwrap().is_nan());
+
+        let value = VecCopy::from_vec(vec![true, false])
+            .to_json_array()
+            .unwrap();
+        assert_eq!(value, json!([true, false]));
+        let read = VecCopy::from_json_array(ScalarType::Bool, &value).unwrap();
+        assert_eq!(read.as_slice::<bool>().unwrap(), &[true, false]);
+
+        // Integers are accepted as floating point numbers, but not vice versa.
+        let read = VecCopy::from_json_array(ScalarType::F64, &json!([1, 2.5])).unwrap();
+        assert_eq!(read.as_slice::<f64>().unwrap(), &[1.0, 2.5]);
+        assert_eq!(
+            VecCopy::from_json_array(ScalarType::I32, &json!([1, 2.5])).unwrap_err(),
+            JsonError::InvalidElement { index: 1 }
+        );
+        assert_eq!(
+            VecCopy::from_json_array(ScalarType::I8, &json!([-128, -129])).unwrap_err(),
+            JsonError::InvalidElement { index: 1 }
+        );
+        assert_eq!(
+            VecCopy::from_json_array(ScalarType::U8, &json!({ "a": 1 })).unwrap_err(),
+            JsonError::NotAnArray
+        );
+        assert!(VecCopy::from_json_array(ScalarType::U32, &json!([]))
+            .unwrap()
+            .is_empty());
+
+        assert!(VecCopy::from_vec(vec![[0u8; 2]]).to_json_array().is_none());
+    }
+}
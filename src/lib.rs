@@ -11,24 +11,90 @@
 //!
 //! [`VecCopy`]: struct.VecCopy
 
+#![cfg_attr(feature = "allocator_api", feature(allocator_api))]
+
 use std::{
     any::{Any, TypeId},
-    mem::size_of,
+    fmt,
+    hash::Hash,
+    io,
+    marker::PhantomData,
+    mem::{align_of, size_of, MaybeUninit},
+    ops::{Bound, Range, RangeBounds},
     slice,
 };
 
-#[cfg(feature = "numeric")]
-use std::fmt;
-
 #[cfg(feature = "numeric")]
 use num_traits::{cast, NumCast, Zero};
 
-pub mod macros;
+mod aligned_vec;
+#[cfg(feature = "allocator_api")]
+mod allocator;
+mod arc_buffer;
+mod arena;
 mod bytes;
+mod checksum;
+mod chunked;
+mod cow_buffer;
+#[cfg(feature = "csv")]
+mod csv;
+mod display;
+mod endian;
+mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod interleave;
+mod iter;
+pub mod macros;
+mod memory;
+#[cfg(feature = "mmap")]
+mod mmap;
+#[cfg(feature = "npy")]
+mod npy;
+mod parse;
+mod pool;
+mod raw;
+mod scalar;
+#[cfg(feature = "shared-memory")]
+mod shared_memory;
+mod slice_copy;
+mod sort;
+mod stable_id;
+mod strided;
+mod table;
+mod thread_safe;
+mod trace;
 mod traits;
 #[macro_use]
 mod value;
 mod vec_dyn;
+mod vtable;
+
+#[cfg(feature = "arrow")]
+mod arrow_impl;
+#[cfg(feature = "bytemuck")]
+mod bytemuck_impl;
+#[cfg(feature = "json")]
+mod json_impl;
+#[cfg(feature = "ndarray")]
+mod ndarray_impl;
+#[cfg(feature = "pyo3")]
+mod pyo3_impl;
+#[cfg(feature = "rayon")]
+mod rayon_impl;
+#[cfg(feature = "rkyv")]
+mod rkyv_impl;
+#[cfg(feature = "tokio")]
+mod tokio_impl;
+#[cfg(feature = "wasm")]
+mod wasm_impl;
+#[cfg(feature = "zerocopy")]
+mod zerocopy_impl;
+#[cfg(feature = "zstd")]
+mod zstd_impl;
+
+#[cfg(feature = "typed_serde")]
+pub mod typed_serde;
 
 #[cfg(feature = "testing")]
 pub mod clone_value;
@@ -37,32 +103,145 @@ pub mod vec_clone;
 
 #[cfg(feature = "serde")]
 pub(crate) mod serde_helpers {
-    use std::any::TypeId;
-    fn transmute_type_id_to_u64(id: &TypeId) -> u64 {
-        unsafe { std::mem::transmute::<TypeId, u64>(*id) }
+    use serde::{de, ser, Deserialize, Serialize};
+    use std::fmt;
+
+    use super::{Endianness, StableTypeId, VecCopy};
+    use crate::trace::{trace_event, trace_span};
+
+    /// The serialized representation of a `VecCopy`.
+    ///
+    /// The element type is identified by its `StableTypeId`, which must be registered in the
+    /// running program to be deserialized. The data is stored in the byte order of the
+    /// serializing platform, and converted on deserialization if necessary.
+    ///
+    /// The data is serialized as a single byte blob, which binary formats like `bincode` and
+    /// `postcard` store contiguously without per-element overhead.
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename = "VecCopy")]
+    struct VecCopyRepr<Data> {
+        data: Data,
+        element_size: usize,
+        element_type: StableTypeId,
+        endianness: Endianness,
+    }
+
+    impl Serialize for VecCopy {
+        fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            trace_span!("serialize", num_bytes = self.data.len());
+            let element_type = self.stable_type_id().ok_or_else(|| {
+                ser::Error::custom(format_args!(
+                    "element type `{}` is not registered",
+                    self.element_type_name()
+                ))
+            })?;
+            VecCopyRepr {
+                data: &self.data,
+                element_size: self.element_size,
+                element_type,
+                endianness: Endianness::NATIVE,
+            }
+            .serialize(serializer)
+        }
     }
 
-    #[derive(serde::Serialize, serde::Deserialize)]
-    #[serde(remote = "TypeId")]
-    pub struct TypeIdDef {
-        #[serde(getter = "transmute_type_id_to_u64")]
-        t: u64,
+    /// A byte vector deserialized either from bytes or from a sequence, since formats like JSON
+    /// represent bytes as sequences of integers.
+    struct Data(Vec<u8>);
+
+    impl<'de> Deserialize<'de> for Data {
+        fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            struct DataVisitor;
+
+            impl<'de> de::Visitor<'de> for DataVisitor {
+                type Value = Data;
+
+                fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    f.write_str("a byte array")
+                }
+
+                fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<Data, E> {
+                    Ok(Data(bytes.to_vec()))
+                }
+
+                fn visit_byte_buf<E: de::Error>(self, bytes: Vec<u8>) -> Result<Data, E> {
+                    Ok(Data(bytes))
+                }
+
+                fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Data, A::Error> {
+                    // Don't trust the size hint with the size of the allocation.
+                    let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+                    while let Some(byte) = seq.next_element()? {
+                        bytes.push(byte);
+                    }
+                    Ok(Data(bytes))
+                }
+            }
+
+            deserializer.deserialize_byte_buf(DataVisitor)
+        }
     }
 
-    impl From<TypeIdDef> for TypeId {
-        fn from(def: TypeIdDef) -> TypeId {
-            unsafe { std::mem::transmute::<u64, TypeId>(def.t) }
+    impl<'de> Deserialize<'de> for VecCopy {
+        fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            trace_span!("deserialize");
+            let repr = VecCopyRepr::<Data>::deserialize(deserializer)?;
+            trace_event!(num_bytes = repr.data.0.len(), "deserialized bytes");
+            VecCopy::from_portable_bytes(
+                repr.data.0,
+                repr.element_size,
+                repr.element_type,
+                repr.endianness,
+            )
+            .map_err(de::Error::custom)
         }
     }
 }
 
-use bytes::Bytes;
+use aligned_vec::AlignedVec;
+pub use arc_buffer::*;
+pub use arena::*;
+pub use chunked::*;
+pub use cow_buffer::*;
+#[cfg(feature = "csv")]
+pub use csv::CsvOptions;
+pub use display::*;
+pub use endian::*;
+pub use error::*;
+pub use iter::*;
+pub use memory::*;
+#[cfg(feature = "mmap")]
+pub use mmap::*;
+pub use parse::*;
+pub use pool::*;
+#[cfg(feature = "pyo3")]
+pub use pyo3_impl::PyVecCopy;
+#[cfg(feature = "rkyv")]
+pub use rkyv_impl::*;
+pub use scalar::*;
+#[cfg(feature = "shared-memory")]
+pub use shared_memory::*;
+pub use slice_copy::*;
+pub use sort::*;
+pub use stable_id::*;
+pub use strided::*;
+pub use table::*;
+pub use thread_safe::*;
 pub use value::*;
 pub use value::{CopyValueMut, CopyValueRef};
 pub use vec_dyn::*;
+use traits::{
+    CmpFn, DebugBytes, DefaultBytes, DefaultFn, DisplayBytes, EqFn, FmtFn, HashBytes, HashFn,
+    OrdBytes, PartialCmpFn, PartialOrdBytes,
+};
+use vtable::CopyVTable;
 
-pub trait Elem: Any + Copy {}
-impl<T> Elem for T where T: Any + Copy {}
+/// Types that can be stored in a `VecCopy`.
+///
+/// The `PartialEq` implementation of the element type is captured at construction, so that
+/// buffers compare their elements rather than their bytes.
+pub trait Elem: Any + Copy + PartialEq {}
+impl<T> Elem for T where T: Any + Copy + PartialEq {}
 
 /// Buffer of plain old data. The data is stored as an array of bytes (`Vec<u8>`).
 ///
@@ -77,31 +256,76 @@ impl<T> Elem for T where T: Any + Copy {}
 /// representative bytes cannot be modified or inspected by the safe API exposed by this library,
 /// they can only be copied.
 ///
+/// The bytes are stored in an allocation aligned to at least the alignment of the element type
+/// (see [`VecCopy::alignment`]), so over-aligned types like `#[repr(align(32))]` structs can be
+/// stored and borrowed as typed slices.
+///
 /// Further, the bytes representing a type are never interpreted as
 /// anything other than a type with an identical `TypeId`, which are assumed to have an identical
 /// memory layout throughout the execution of the program.
 ///
-/// Although serializing is enabled via the `serde` feature, deserializing this type in a program
-/// compiled into a different binary may cause undefined behavior. Deserializing this type should be
-/// done using the same binary because `TypeId` values could change between Rust compiler versions,
-/// architectures or other variables.
+/// Serialization is enabled via the `serde` feature. Since `TypeId` values could change between
+/// Rust compiler versions, architectures or other variables, the element type is serialized as a
+/// [`StableTypeId`] instead. Only buffers of types registered with [`register_type`] can be
+/// serialized and deserialized.
 ///
 /// [`bytemuck`]: https://crates.io/crates/bytemuck
 /// [`zerocopy`]: https://crates.io/crates/zerocopy
-#[derive(Clone, Debug, PartialEq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone)]
 pub struct VecCopy {
     /// Raw data stored as bytes.
-    #[cfg_attr(feature = "serde_bytes", serde(with = "serde_bytes"))]
-    pub(crate) data: Vec<u8>,
+    ///
+    /// The allocation is aligned to at least the alignment of the element type.
+    pub(crate) data: AlignedVec,
     /// Number of bytes occupied by an element of this buffer.
     ///
     /// Note: We store this instead of length because it gives us the ability to get the type size
     /// when the buffer is empty.
     pub(crate) element_size: usize,
     /// Type encoding for hiding the type of data from the compiler.
-    #[cfg_attr(feature = "serde", serde(with = "serde_helpers::TypeIdDef"))]
     pub(crate) element_type_id: TypeId,
+    /// Name of the element type used for diagnostics.
+    ///
+    /// This is not serialized. Deserialized buffers are given the name of the registered type.
+    pub(crate) element_type_name: TypeName,
+    /// Optional trait functions captured for the element type.
+    pub(crate) vtable: CopyVTable,
+}
+
+/// The name of a type, which is only used for diagnostics.
+///
+/// Since the type is already identified by its `TypeId`, all type names compare equal and don't
+/// contribute to the hash. This way buffers whose type name is unknown (e.g. after
+/// deserialization) still compare equal to their original.
+#[derive(Copy, Clone, Default)]
+pub(crate) struct TypeName(pub(crate) Option<&'static str>);
+
+impl TypeName {
+    #[inline]
+    pub(crate) fn of<T: ?Sized>() -> Self {
+        TypeName(Some(std::any::type_name::<T>()))
+    }
+}
+
+impl PartialEq for TypeName {
+    #[inline]
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl std::hash::Hash for TypeName {
+    #[inline]
+    fn hash<H: std::hash::Hasher>(&self, _: &mut H) {}
+}
+
+impl std::fmt::Debug for TypeName {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.0 {
+            Some(name) => name.fmt(f),
+            None => f.write_str("<unknown>"),
+        }
+    }
 }
 
 impl VecCopy {
@@ -109,7 +333,9 @@ impl VecCopy {
     #[inline]
     pub fn with_type<T: Elem>() -> Self {
         // This is safe because `T` is `Elem`.
-        unsafe { VecCopy::with_type_non_copy::<T>() }
+        let mut buf = unsafe { VecCopy::with_type_non_copy::<T>() };
+        buf.vtable = CopyVTable::of_elem::<T>();
+        buf
     }
 
     /// It is unsafe to construct a `VecCopy` if `T` is not `Elem`.
@@ -118,19 +344,50 @@ impl VecCopy {
         let element_size = size_of::<T>();
         assert_ne!(element_size, 0, "VecCopy doesn't support zero sized types.");
         VecCopy {
-            data: Vec::new(),
+            data: AlignedVec::new(align_of::<T>()),
             element_size,
             element_type_id: TypeId::of::<T>(),
+            element_type_name: TypeName::of::<T>(),
+            vtable: CopyVTable::of::<T>(),
         }
     }
 
+    /// Construct an empty `VecCopy` with a specific type, whose data is allocated with at least
+    /// the given alignment.
+    ///
+    /// The alignment of a buffer is never smaller than the alignment of its element type, so this
+    /// is only useful to request a stronger alignment, for instance to facilitate SIMD loads.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `align` is not a power of two.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let mut buf = VecCopy::with_type_aligned::<f32>(64);
+    /// buf.extend((0..100).map(|i| i as f32));
+    /// assert_eq!(buf.alignment(), 64);
+    /// assert_eq!(buf.as_bytes().as_ptr() as usize % 64, 0);
+    /// ```
+    #[inline]
+    pub fn with_type_aligned<T: Elem>(align: usize) -> Self {
+        let mut buf = VecCopy::with_type::<T>();
+        buf.data = AlignedVec::new(align.max(align_of::<T>()));
+        buf
+    }
+
     /// Construct a `VecCopy` with the same type as the given buffer without copying its data.
+    ///
+    /// The new buffer uses the same alignment and allocator as `other`.
     #[inline]
     pub fn with_type_from(other: &VecCopy) -> Self {
         VecCopy {
-            data: Vec::new(),
+            data: other.data.new_like(),
             element_size: other.element_size,
             element_type_id: other.element_type_id,
+            element_type_name: other.element_type_name,
+            vtable: other.vtable,
         }
     }
 
@@ -139,7 +396,9 @@ impl VecCopy {
     #[inline]
     pub fn with_capacity<T: Elem>(n: usize) -> Self {
         // This is safe because `T` is `Elem`.
-        unsafe { VecCopy::with_capacity_non_copy::<T>(n) }
+        let mut buf = unsafe { VecCopy::with_capacity_non_copy::<T>(n) };
+        buf.vtable = CopyVTable::of_elem::<T>();
+        buf
     }
 
     /// It is unsafe to construct a `VecCopy` if `T` is not `Elem`.
@@ -148,12 +407,40 @@ impl VecCopy {
         let element_size = size_of::<T>();
         assert_ne!(element_size, 0, "VecCopy doesn't support zero sized types.");
         VecCopy {
-            data: Vec::with_capacity(n * element_size),
+            data: AlignedVec::with_capacity(n * element_size, align_of::<T>()),
             element_size,
             element_type_id: TypeId::of::<T>(),
+            element_type_name: TypeName::of::<T>(),
+            vtable: CopyVTable::of::<T>(),
         }
     }
 
+    /// Construct an empty `VecCopy` with a capacity for a given number of typed elements,
+    /// returning an error if the memory cannot be allocated.
+    ///
+    /// This is useful when the number of elements comes from untrusted input, since `with_capacity`
+    /// aborts the program if the allocation fails.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::{TryReserveError, VecCopy};
+    /// let buf = VecCopy::try_with_capacity::<f32>(4).unwrap();
+    /// assert!(buf.byte_capacity() >= 16);
+    /// assert_eq!(
+    ///     VecCopy::try_with_capacity::<f32>(usize::MAX).unwrap_err(),
+    ///     TryReserveError::CapacityOverflow
+    /// );
+    /// ```
+    #[inline]
+    pub fn try_with_capacity<T: Elem>(n: usize) -> Result<Self, TryReserveError> {
+        let bytes = n
+            .checked_mul(size_of::<T>())
+            .ok_or(TryReserveError::CapacityOverflow)?;
+        let mut buf = VecCopy::with_type::<T>();
+        buf.data.try_reserve_exact(bytes)?;
+        Ok(buf)
+    }
+
     /// Construct a typed `VecCopy` with a given size and filled with the specified default
     /// value.
     ///
@@ -182,7 +469,9 @@ impl VecCopy {
     /// ```
     pub fn from_vec<T: Elem>(vec: Vec<T>) -> Self {
         // This is safe because `T` is `Elem`.
-        unsafe { Self::from_vec_non_copy(vec) }
+        let mut buf = unsafe { Self::from_vec_non_copy(vec) };
+        buf.vtable = CopyVTable::of_elem::<T>();
+        buf
     }
 
     /// It is unsafe to call this for `T` that is not `Elem`.
@@ -190,20 +479,12 @@ impl VecCopy {
         let element_size = size_of::<T>();
         assert_ne!(element_size, 0, "VecCopy doesn't support zero sized types.");
 
-        let data = {
-            // Replace with into_raw_parts when that stabilizes.
-            let mut md = std::mem::ManuallyDrop::new(vec);
-            let len_in_bytes = md.len() * element_size;
-            let capacity_in_bytes = md.capacity() * element_size;
-            let vec_ptr = md.as_mut_ptr() as *mut u8;
-
-            Vec::from_raw_parts(vec_ptr, len_in_bytes, capacity_in_bytes)
-        };
-
         VecCopy {
-            data,
+            data: AlignedVec::from_vec(vec),
             element_size,
             element_type_id: TypeId::of::<T>(),
+            element_type_name: TypeName::of::<T>(),
+            vtable: CopyVTable::of::<T>(),
         }
     }
 
@@ -241,8 +522,40 @@ impl VecCopy {
                 self.push(value.clone());
             }
         } else {
-            // Truncate
-            self.data.resize(new_len * size_t, 0);
+            self.truncate(new_len);
+        }
+        Some(self)
+    }
+
+    /// Resizes the buffer in-place to store `new_len` elements, filling any new elements with the
+    /// default value of the element type, and returns an optional mutable reference to `Self`.
+    ///
+    /// Unlike `resize`, this function doesn't require knowledge of the element type, however the
+    /// `Default` implementation of the element type must have been captured with
+    /// `with_default`. Otherwise `None` is returned and the buffer is left unchanged.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let mut buf = VecCopy::with_type::<u32>().with_default::<u32>().unwrap();
+    /// buf.resize_with_default(3).unwrap();
+    /// assert_eq!(buf.as_slice::<u32>().unwrap(), &[0, 0, 0]);
+    /// buf.resize_with_default(1).unwrap();
+    /// assert_eq!(buf.as_slice::<u32>().unwrap(), &[0]);
+    /// ```
+    #[inline]
+    pub fn resize_with_default(&mut self, new_len: usize) -> Option<&mut Self> {
+        let DefaultFn(default) = self.vtable.default?;
+        let len = self.len();
+        let element_size = self.element_size;
+        if new_len > len {
+            self.data.resize(new_len * element_size, 0);
+            for elem_bytes in self.data[len * element_size..].chunks_exact_mut(element_size) {
+                // This is safe since the function was captured for the stored element type.
+                unsafe { default(elem_bytes) };
+            }
+        } else {
+            self.truncate(new_len);
         }
         Some(self)
     }
@@ -257,10 +570,16 @@ impl VecCopy {
         assert_ne!(element_size, 0, "VecCopy doesn't support zero sized types.");
         let bins = slice.len() * element_size;
         let byte_slice = unsafe { slice::from_raw_parts(slice.as_ptr() as *const u8, bins) };
+        if self.data.align() < align_of::<T>() {
+            // The current allocation is not sufficiently aligned for the new type.
+            self.data = self.data.new_aligned_like(align_of::<T>());
+        }
         self.data.resize(bins, 0);
         self.data.copy_from_slice(byte_slice);
         self.element_size = element_size;
         self.element_type_id = TypeId::of::<T>();
+        self.element_type_name = TypeName::of::<T>();
+        self.vtable = CopyVTable::of_elem::<T>();
         self
     }
 
@@ -270,6 +589,78 @@ impl VecCopy {
         self.data.clear();
     }
 
+    /// Shorten the buffer, keeping the first `new_len` elements and dropping the rest.
+    ///
+    /// If `new_len` is greater than or equal to the current length, this has no effect.
+    /// Unlike `resize`, this function doesn't require knowledge of the element type.
+    ///
+    /// #  Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let mut buf = VecCopy::from_vec(vec![1u8, 3, 4, 1, 2]);
+    /// buf.truncate(2);
+    /// assert_eq!(buf.into_vec::<u8>().unwrap(), vec![1u8, 3]);
+    /// ```
+    #[inline]
+    pub fn truncate(&mut self, new_len: usize) {
+        // Lengths beyond the capacity of the buffer have no effect, so the product can saturate.
+        self.data
+            .truncate(new_len.saturating_mul(self.element_size));
+    }
+
+    /// Reserve capacity for at least `additional` more elements.
+    ///
+    /// Like `Vec::reserve`, this may reserve more space to avoid frequent reallocations.
+    ///
+    /// #  Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let mut buf = VecCopy::from_vec(vec![1u32, 2]);
+    /// buf.reserve(10);
+    /// assert!(buf.capacity() >= 12);
+    /// ```
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        let additional_bytes = additional
+            .checked_mul(self.element_size)
+            .expect("capacity overflow");
+        self.data.reserve(additional_bytes);
+    }
+
+    /// Reserve capacity for exactly `additional` more elements.
+    #[inline]
+    pub fn reserve_exact(&mut self, additional: usize) {
+        let additional_bytes = additional
+            .checked_mul(self.element_size)
+            .expect("capacity overflow");
+        self.data.reserve_exact(additional_bytes);
+    }
+
+    /// Shrink the capacity of this buffer as much as possible.
+    ///
+    /// #  Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let mut buf = VecCopy::with_capacity::<u32>(10);
+    /// buf.push(1u32);
+    /// buf.shrink_to_fit();
+    /// assert_eq!(buf.capacity(), 1);
+    /// ```
+    #[inline]
+    pub fn shrink_to_fit(&mut self) {
+        self.data.shrink_to(0);
+    }
+
+    /// Shrink the capacity of this buffer to hold at least `min_capacity` elements.
+    ///
+    /// The capacity remains at least as large as the length of the buffer. If the current
+    /// capacity is less than `min_capacity`, this has no effect.
+    #[inline]
+    pub fn shrink_to(&mut self, min_capacity: usize) {
+        self.data
+            .shrink_to(min_capacity.saturating_mul(self.element_size));
+    }
+
     /// Fill the current buffer with copies of the given value. The size of the buffer is left
     /// unchanged. If the given type doesn't patch the internal type, `None` is returned, otherwise
     /// a mut reference to the modified buffer is returned.
@@ -284,9 +675,19 @@ impl VecCopy {
     /// ```
     #[inline]
     pub fn fill<T: Elem>(&mut self, def: T) -> Option<&mut Self> {
-        for v in self.iter_mut::<T>()? {
-            *v = def;
+        self.check_ref::<T>()?;
+        if vtable::is_primitive::<T>() {
+            // Primitive types have no padding, so their bytes can be inspected. Values made of a
+            // single repeated byte, such as zero, are written with a single `memset`.
+            let bytes =
+                unsafe { slice::from_raw_parts(&def as *const T as *const u8, size_of::<T>()) };
+            if bytes.iter().all(|&b| b == bytes[0]) {
+                self.data.fill(bytes[0]);
+                return Some(self);
+            }
         }
+        // `slice::fill` is vectorized to write whole registers at a time for `Copy` types.
+        self.as_mut_slice::<T>()?.fill(def);
         Some(self)
     }
 
@@ -295,222 +696,1844 @@ impl VecCopy {
     /// If the type of the given element coincides with the type
     /// stored by this buffer, then the modified buffer is returned via a mutable reference.
     /// Otherwise, `None` is returned.
+    ///
+    /// The element is moved directly into the buffer, so its padding bytes are never read.
     #[inline]
-    pub fn push<T: Any>(&mut self, element: T) -> Option<&mut Self> {
-        self.check_ref::<T>()?;
-        let bytes = element.as_bytes();
-        let result = unsafe { self.push_bytes(bytes) };
-        std::mem::forget(element);
-        result
-    }
-
-    /// Check if the current buffer contains elements of the specified type. Returns `Some(self)`
-    /// if the type matches and `None` otherwise.
-    #[inline]
-    pub fn check<T: Any>(self) -> Option<Self> {
-        if TypeId::of::<T>() != self.element_type_id() {
-            None
-        } else {
-            Some(self)
-        }
+    pub fn push<T: Elem>(&mut self, element: T) -> Option<&mut Self> {
+        // This is safe because `T` is `Elem`.
+        unsafe { self.push_non_copy(element) }
     }
 
-    /// Check if the current buffer contains elements of the specified type. Returns `None` if the
-    /// check fails, otherwise a reference to self is returned.
+    /// Add the default element to this buffer.
+    ///
+    /// This is the untyped version of `push(T::default())`, which requires the `Default`
+    /// implementation of the element type to have been captured with `with_default`. Otherwise
+    /// `None` is returned and the buffer is left unchanged.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let mut buf = VecCopy::from_vec(vec![1.0f32]).with_default::<f32>().unwrap();
+    /// buf.push_default().unwrap();
+    /// assert_eq!(buf.as_slice::<f32>().unwrap(), &[1.0, 0.0]);
+    /// ```
     #[inline]
-    pub fn check_ref<T: Any>(&self) -> Option<&Self> {
-        if TypeId::of::<T>() != self.element_type_id() {
-            None
-        } else {
-            Some(self)
-        }
+    pub fn push_default(&mut self) -> Option<&mut Self> {
+        let new_len = self.len() + 1;
+        self.resize_with_default(new_len)
     }
 
-    /// Check if the current buffer contains elements of the specified type. Same as `check_ref`
-    /// but consumes and produces a mut reference to self.
+    /// Add an element that is not necessarily `Copy` to this buffer.
+    ///
+    /// The element is moved into the buffer without being dropped.
+    ///
+    /// It is unsafe to call this for `T` that is not `Elem`, since the caller becomes responsible
+    /// for eventually dropping the element stored in the buffer.
     #[inline]
-    pub fn check_mut<T: Any>(&mut self) -> Option<&mut Self> {
-        if TypeId::of::<T>() != self.element_type_id() {
-            None
-        } else {
-            Some(self)
-        }
+    pub(crate) unsafe fn push_non_copy<T: Any>(&mut self, element: T) -> Option<&mut Self> {
+        self.check_ref::<T>()?;
+        self.data.push_typed(element);
+        Some(self)
     }
 
-    /*
-     * Accessors
-     */
-
-    /// Get the `TypeId` of data stored within this buffer.
+    /// Remove the last element from this buffer and return it.
+    ///
+    /// If the buffer is empty or `T` doesn't match the type stored by this buffer, `None` is
+    /// returned and the buffer is left unchanged.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let mut buf = VecCopy::from_vec(vec![1u32, 2]);
+    /// assert_eq!(buf.pop::<u32>(), Some(2));
+    /// assert_eq!(buf.pop::<u32>(), Some(1));
+    /// assert_eq!(buf.pop::<u32>(), None);
+    /// ```
     #[inline]
-    pub fn element_type_id(&self) -> TypeId {
-        self.element_type_id
+    pub fn pop<T: Elem>(&mut self) -> Option<T> {
+        self.check_ref::<T>()?;
+        let bytes = self.pop_bytes()?;
+        // This is safe since the bytes were stored as a `T`. The boxed bytes are not
+        // necessarily aligned for `T`, so we read them unaligned.
+        Some(unsafe { std::ptr::read_unaligned(bytes.as_ptr() as *const T) })
     }
 
-    /// Get the number of elements stored in this buffer.
+    /// Remove the last element from this buffer and return its bytes.
+    ///
+    /// This is the untyped version of `pop`. If the buffer is empty, `None` is returned.
     #[inline]
-    pub fn len(&self) -> usize {
-        debug_assert_eq!(self.data.len() % self.element_size, 0);
-        self.data.len() / self.element_size // element_size is guaranteed to be strictly positive
+    pub fn pop_bytes(&mut self) -> Option<Box<[u8]>> {
+        if self.is_empty() {
+            return None;
+        }
+        let last = self.data.len() - self.element_size();
+        let bytes = Box::from(&self.data[last..]);
+        self.data.truncate(last);
+        Some(bytes)
     }
 
-    /// Check if there are any elements stored in this buffer.
+    /// Insert an element at position `i`, shifting all elements after it to the right.
+    ///
+    /// If the type of the given element coincides with the type stored by this buffer, then the
+    /// modified buffer is returned via a mutable reference. Otherwise, `None` is returned.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `i > len`.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let mut buf = VecCopy::from_vec(vec![1u32, 2, 3]);
+    /// buf.insert(1, 42u32).unwrap();
+    /// assert_eq!(buf.as_slice::<u32>().unwrap(), &[1, 42, 2, 3]);
+    /// ```
     #[inline]
-    pub fn is_empty(&self) -> bool {
-        self.data.is_empty()
+    pub fn insert<T: Elem>(&mut self, i: usize, element: T) -> Option<&mut Self> {
+        self.check_ref::<T>()?;
+        assert!(i <= self.len(), "insertion index {} is out of bounds", i);
+        self.data.insert_typed(i * self.element_size, element);
+        Some(self)
     }
 
-    /// Get the byte capacity of this buffer.
+    /// Remove and return the element at position `i`, shifting all elements after it to the left.
+    ///
+    /// If the type of the element doesn't match the type stored by this buffer, `None` is
+    /// returned and the buffer is left unchanged.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `i` is out of bounds.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let mut buf = VecCopy::from_vec(vec![1u32, 2, 3]);
+    /// assert_eq!(buf.remove::<u32>(1), Some(2));
+    /// assert_eq!(buf.as_slice::<u32>().unwrap(), &[1, 3]);
+    /// ```
     #[inline]
-    pub fn byte_capacity(&self) -> usize {
-        self.data.capacity()
+    pub fn remove<T: Elem>(&mut self, i: usize) -> Option<T> {
+        assert!(i < self.len(), "removal index {} is out of bounds", i);
+        let element = self.get::<T>(i)?;
+        self.remove_bytes(i);
+        Some(element)
     }
 
-    /// Get the size of the element type in bytes.
+    /// Remove the element at position `i` and return its bytes, shifting all elements after it
+    /// to the left.
+    ///
+    /// This is the untyped version of `remove`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `i` is out of bounds.
     #[inline]
-    pub fn element_size(&self) -> usize {
-        self.element_size
+    pub fn remove_bytes(&mut self, i: usize) -> Box<[u8]> {
+        assert!(i < self.len());
+        let element_size = self.element_size();
+        self.data
+            .drain(i * element_size..(i + 1) * element_size)
+            .collect()
     }
 
-    /// Return an iterator to a slice representing typed data.
-    /// Returs `None` if the given type `T` doesn't match the internal.
+    /// Remove and return the element at position `i`, replacing it with the last element.
+    ///
+    /// This does not preserve ordering, but is O(1). If the type of the element doesn't match
+    /// the type stored by this buffer, `None` is returned and the buffer is left unchanged.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `i` is out of bounds.
     ///
     /// # Examples
     /// ```
     /// use data_buffer::VecCopy;
-    /// let vec = vec![1.0_f32, 23.0, 0.01, 42.0, 11.43];
-    /// let buf = VecCopy::from(vec.clone()); // Convert into buffer
-    /// for (i, &val) in buf.iter::<f32>().unwrap().enumerate() {
-    ///     assert_eq!(val, vec[i]);
-    /// }
+    /// let mut buf = VecCopy::from_vec(vec![1u32, 2, 3, 4]);
+    /// assert_eq!(buf.swap_remove::<u32>(0), Some(1));
+    /// assert_eq!(buf.as_slice::<u32>().unwrap(), &[4, 2, 3]);
     /// ```
     #[inline]
-    pub fn iter<'a, T: Any>(&'a self) -> Option<slice::Iter<T>> {
-        self.as_slice::<T>().map(|x| x.iter())
-    }
-
-    /// Return an iterator to a mutable slice representing typed data.
-    /// Returns `None` if the given type `T` doesn't match the internal.
-    #[inline]
-    pub fn iter_mut<'a, T: Any>(&'a mut self) -> Option<slice::IterMut<T>> {
-        self.as_mut_slice::<T>().map(|x| x.iter_mut())
+    pub fn swap_remove<T: Elem>(&mut self, i: usize) -> Option<T> {
+        assert!(i < self.len(), "removal index {} is out of bounds", i);
+        let element = self.get::<T>(i)?;
+        self.swap_remove_bytes(i);
+        Some(element)
     }
 
-    /// Append copied items from this buffer to a given `Vec<T>`. Return the mutable reference
-    /// `Some(vec)` if type matched the internal type and `None` otherwise. This may be faster than
-    /// `append_clone_to_vec`.
+    /// Remove the element at position `i` and return its bytes, replacing it with the last
+    /// element.
+    ///
+    /// This is the untyped version of `swap_remove`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `i` is out of bounds.
     #[inline]
-    pub fn append_to_vec<'a, T: Elem>(&self, vec: &'a mut Vec<T>) -> Option<&'a mut Vec<T>> {
-        let iter = self.iter()?;
-        // Allocate only after we know the type is right to prevent unnecessary allocations.
-        vec.reserve(self.len());
-        vec.extend(iter);
-        Some(vec)
+    pub fn swap_remove_bytes(&mut self, i: usize) -> Box<[u8]> {
+        assert!(i < self.len());
+        let element_size = self.element_size();
+        let last = self.data.len() - element_size;
+        let removed = Box::from(self.get_bytes(i));
+        self.data.copy_within(last.., i * element_size);
+        self.data.truncate(last);
+        removed
     }
 
-    /// Copies contents of `self` into the given `Vec`.
+    /// Add all elements produced by the given iterator to this buffer.
+    ///
+    /// Space is reserved once, based on the lower bound of the iterator's size hint. If `T`
+    /// doesn't match the type stored by this buffer, `None` is returned and the buffer is left
+    /// unchanged.
+    ///
+    /// Note that this method shadows `Extend::extend` for `CopyValueRef`s, which can still be
+    /// called as `Extend::extend(&mut buf, values)`.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let mut buf = VecCopy::from_vec(vec![1u32, 2]);
+    /// buf.extend(3..6u32).unwrap();
+    /// assert_eq!(buf.as_slice::<u32>().unwrap(), &[1, 2, 3, 4, 5]);
+    /// ```
     #[inline]
-    pub fn copy_into_vec<T: Elem>(&self) -> Option<Vec<T>> {
-        let mut vec = Vec::new();
-        match self.append_to_vec(&mut vec) {
-            Some(_) => Some(vec),
-            None => None,
-        }
+    pub fn extend<T: Elem, I: IntoIterator<Item = T>>(&mut self, iter: I) -> Option<&mut Self> {
+        self.check_ref::<T>()?;
+        // This is safe since the type was checked above.
+        unsafe { self.extend_unchecked(iter) };
+        Some(self)
     }
 
-    /// An alternative to using the `Into` trait. This function helps the compiler
-    /// determine the type `T` automatically.
+    /// Add exactly `n` elements produced by the given iterator to this buffer.
+    ///
+    /// Space for `n` elements is reserved up front and the elements are written directly into
+    /// it, avoiding the capacity check of each `push`. At most `n` elements are taken from the
+    /// iterator. If `T` doesn't match the type stored by this buffer or the iterator produces
+    /// fewer than `n` elements, `None` is returned and the length of the buffer is left
+    /// unchanged.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let mut buf = VecCopy::from_vec(vec![1u32]);
+    /// buf.extend_with_exact(2, 2u32..).unwrap();
+    /// assert_eq!(buf.as_slice::<u32>().unwrap(), &[1, 2, 3]);
+    /// assert!(buf.extend_with_exact(3, vec![4u32, 5]).is_none());
+    /// assert_eq!(buf.len(), 3);
+    /// ```
+    pub fn extend_with_exact<T: Elem, I: IntoIterator<Item = T>>(
+        &mut self,
+        n: usize,
+        iter: I,
+    ) -> Option<&mut Self> {
+        self.check_ref::<T>()?;
+        let num_bytes = n.checked_mul(self.element_size).expect("capacity overflow");
+        self.data.reserve_exact(num_bytes);
+        // The end of the data is aligned for `T` since the buffer is aligned and its length is a
+        // multiple of the size of `T`.
+        let dst = self.data.spare_capacity_mut().as_mut_ptr() as *mut T;
+        let mut num_written = 0;
+        for elem in iter.into_iter().take(n) {
+            // This is safe since space for `n` elements was reserved above.
+            unsafe { dst.add(num_written).write(elem) };
+            num_written += 1;
+        }
+        if num_written < n {
+            return None;
+        }
+        let len = self.data.len();
+        // This is safe since all `n` elements were initialized above.
+        unsafe { self.data.set_len(len + num_bytes) };
+        Some(self)
+    }
+
+    /// Append copies of the elements in the given slice to this buffer.
+    ///
+    /// The elements are copied with a single `memcpy`, which is faster than `extend` with an
+    /// iterator over the slice. If `T` doesn't match the type stored by this buffer, `None` is
+    /// returned and the buffer is left unchanged.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let mut buf = VecCopy::from_vec(vec![1u32, 2]);
+    /// buf.extend_from_slice(&[3u32, 4]).unwrap();
+    /// assert_eq!(buf.as_slice::<u32>().unwrap(), &[1, 2, 3, 4]);
+    /// ```
+    #[inline]
+    pub fn extend_from_slice<T: Elem>(&mut self, slice: &[T]) -> Option<&mut Self> {
+        self.check_ref::<T>()?;
+        self.data.extend_from_typed_slice(slice);
+        Some(self)
+    }
+
+    /// Get a mutable view into this buffer with a statically known element type.
+    ///
+    /// If `T` doesn't match the type stored by this buffer, `None` is returned.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let mut buf = VecCopy::with_type::<u8>();
+    /// buf.typed_mut::<u8>().unwrap().extend(&[1u8, 2, 3]);
+    /// assert_eq!(buf.as_slice::<u8>().unwrap(), &[1, 2, 3]);
+    /// ```
+    #[inline]
+    pub fn typed_mut<T: Elem>(&mut self) -> Option<TypedVecCopyMut<'_, T>> {
+        self.check_mut::<T>()?;
+        Some(TypedVecCopyMut {
+            buf: self,
+            phantom: PhantomData,
+        })
+    }
+
+    /// Retain only the elements for which the given predicate returns `true`.
+    ///
+    /// This function operates in place with a single pass over the buffer, preserving the order
+    /// of the retained elements. If `T` doesn't match the type stored by this buffer, `None` is
+    /// returned and the buffer is left unchanged.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let mut buf = VecCopy::from_vec(vec![1.0f32, -2.0, 3.0, -4.0]);
+    /// buf.retain(|&x: &f32| x > 0.0).unwrap();
+    /// assert_eq!(buf.as_slice::<f32>().unwrap(), &[1.0, 3.0]);
+    /// ```
+    #[inline]
+    pub fn retain<T: Elem, F: FnMut(&T) -> bool>(&mut self, mut f: F) -> Option<&mut Self> {
+        let slice = self.as_mut_slice::<T>()?;
+        let mut num_retained = 0;
+        for i in 0..slice.len() {
+            if f(&slice[i]) {
+                slice[num_retained] = slice[i];
+                num_retained += 1;
+            }
+        }
+        self.truncate(num_retained);
+        Some(self)
+    }
+
+    /// Retain only the elements whose bytes satisfy the given predicate.
+    ///
+    /// This is the untyped equivalent of `retain`, which can be used without knowing the element
+    /// type. It operates in place with a single pass over the buffer, preserving the order of the
+    /// retained elements.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let mut buf = VecCopy::from_vec(vec![[1u8, 0], [0, 0], [2, 1]]);
+    /// // Remove zeroed elements.
+    /// buf.retain_bytes(|bytes| bytes.iter().any(|&b| b != 0));
+    /// assert_eq!(buf.as_slice::<[u8; 2]>().unwrap(), &[[1, 0], [2, 1]]);
+    /// ```
+    pub fn retain_bytes<F: FnMut(&[u8]) -> bool>(&mut self, mut f: F) {
+        let size = self.element_size;
+        let len = self.len();
+        let data = &mut *self.data;
+        let mut num_retained = 0;
+        for i in 0..len {
+            if f(&data[i * size..(i + 1) * size]) {
+                data.copy_within(i * size..(i + 1) * size, num_retained * size);
+                num_retained += 1;
+            }
+        }
+        self.truncate(num_retained);
+    }
+
+    /// Remove consecutive repeated elements from this buffer.
+    ///
+    /// Elements are compared with the `PartialEq` implementation of the element type, which is
+    /// captured at construction. This function operates in place with a single pass over the
+    /// buffer, so it removes all duplicates from a sorted buffer.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let mut buf = VecCopy::from_vec(vec![1u32, 1, 2, 2, 2, 1]);
+    /// buf.dedup();
+    /// assert_eq!(buf.as_slice::<u32>().unwrap(), &[1, 2, 1]);
+    /// ```
+    pub fn dedup(&mut self) {
+        let size = self.element_size;
+        let len = self.len();
+        let eq = self.vtable.eq;
+        let data = &mut *self.data;
+        let mut num_retained = 1;
+        for i in 1..len {
+            let prev = &data[(num_retained - 1) * size..num_retained * size];
+            let cur = &data[i * size..(i + 1) * size];
+            let is_duplicate = match eq {
+                // This is safe since the function was captured for the stored element type.
+                Some(EqFn(eq)) => unsafe { eq(prev, cur) },
+                None => prev == cur,
+            };
+            if !is_duplicate {
+                data.copy_within(i * size..(i + 1) * size, num_retained * size);
+                num_retained += 1;
+            }
+        }
+        self.truncate(num_retained.min(len));
+    }
+
+    /// Remove consecutive repeated elements from this buffer, comparing them with the
+    /// `PartialEq` implementation of `T`.
+    ///
+    /// If `T` doesn't match the type stored by this buffer, `None` is returned and the buffer is
+    /// left unchanged.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let mut buf = VecCopy::from_vec(vec![0.0f32, -0.0, 1.0]);
+    /// buf.dedup_typed::<f32>().unwrap();
+    /// assert_eq!(buf.as_slice::<f32>().unwrap(), &[0.0, 1.0]);
+    /// ```
+    #[inline]
+    pub fn dedup_typed<T: Elem>(&mut self) -> Option<&mut Self> {
+        let slice = self.as_mut_slice::<T>()?;
+        let len = slice.len();
+        let mut num_retained = 1;
+        for i in 1..len {
+            if slice[i] != slice[num_retained - 1] {
+                slice[num_retained] = slice[i];
+                num_retained += 1;
+            }
+        }
+        self.truncate(num_retained.min(len));
+        Some(self)
+    }
+
+    /// Check if this buffer contains an element with the given bytes.
+    ///
+    /// Elements are compared with the `PartialEq` implementation of the element type, which is
+    /// captured at construction. Returns `false` if the length of `bytes` differs from the element
+    /// size.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let buf = VecCopy::from_vec(vec![0.0f32, 1.0]);
+    /// assert!(buf.contains_bytes(&(-0.0f32).to_ne_bytes()));
+    /// assert!(!buf.contains_bytes(&2.0f32.to_ne_bytes()));
+    /// ```
+    pub fn contains_bytes(&self, bytes: &[u8]) -> bool {
+        if bytes.len() != self.element_size {
+            return false;
+        }
+        match self.vtable.eq {
+            // This is safe since the function was captured for the stored element type and the
+            // size of `bytes` matches.
+            Some(EqFn(eq)) => self.byte_chunks().any(|elem| unsafe { eq(elem, bytes) }),
+            None => self.byte_chunks().any(|elem| elem == bytes),
+        }
+    }
+
+    /// Check if this buffer contains the given element.
+    ///
+    /// Returns `false` if `T` doesn't match the type stored by this buffer.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let buf = VecCopy::from_vec(vec![1u32, 2, 3]);
+    /// assert!(buf.contains(&2u32));
+    /// assert!(!buf.contains(&2u64));
+    /// ```
+    #[inline]
+    pub fn contains<T: Elem>(&self, x: &T) -> bool {
+        self.as_slice::<T>().is_some_and(|slice| slice.contains(x))
+    }
+
+    /// Find the index of the first element equal to the given element.
+    ///
+    /// Returns `None` if there is no such element or if `T` doesn't match the type stored by this
+    /// buffer.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let buf = VecCopy::from_vec(vec![1u32, 2, 3, 2]);
+    /// assert_eq!(buf.position(&2u32), Some(1));
+    /// assert_eq!(buf.position(&4u32), None);
+    /// ```
+    #[inline]
+    pub fn position<T: Elem>(&self, x: &T) -> Option<usize> {
+        self.as_slice::<T>()?.iter().position(|elem| elem == x)
+    }
+
+    /// Split the elements of this buffer into two new buffers: one with the elements that
+    /// satisfy the given predicate and one with the rest.
+    ///
+    /// The elements are copied in a single pass, preserving their order. Both buffers have the
+    /// same element type and captured functions as this buffer and reserve enough capacity up
+    /// front to hold all of its elements, so no reallocation happens during the pass. If `T`
+    /// doesn't match the type stored by this buffer, `None` is returned.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let buf = VecCopy::from_vec(vec![1u32, 2, 3, 4, 5]);
+    /// let (even, odd) = buf.partition(|&x: &u32| x % 2 == 0).unwrap();
+    /// assert_eq!(even.as_slice::<u32>().unwrap(), &[2, 4]);
+    /// assert_eq!(odd.as_slice::<u32>().unwrap(), &[1, 3, 5]);
+    /// ```
+    pub fn partition<T: Elem, F: FnMut(&T) -> bool>(&self, mut f: F) -> Option<(Self, Self)> {
+        let slice = self.as_slice::<T>()?;
+        let mut left = VecCopy::with_type_from(self);
+        let mut right = VecCopy::with_type_from(self);
+        left.data.reserve_exact(self.data.len());
+        right.data.reserve_exact(self.data.len());
+        for &elem in slice {
+            if f(&elem) {
+                left.data.push_typed(elem);
+            } else {
+                right.data.push_typed(elem);
+            }
+        }
+        Some((left, right))
+    }
+
+    /// Copy the elements whose corresponding `mask` entry is `true` into a new buffer.
+    ///
+    /// The new buffer has the same element type and captured functions as this buffer.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the length of `mask` differs from the length of the buffer.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let buf = VecCopy::from_vec(vec![1u32, 2, 3, 4]);
+    /// let selected = buf.select(&[true, false, false, true]);
+    /// assert_eq!(selected.as_slice::<u32>().unwrap(), &[1, 4]);
+    /// ```
+    pub fn select(&self, mask: &[bool]) -> VecCopy {
+        assert_eq!(
+            mask.len(),
+            self.len(),
+            "mask length doesn't match the buffer length"
+        );
+        let num_selected = mask.iter().filter(|&&m| m).count();
+        let mut buf = VecCopy::with_type_from(self);
+        buf.data.reserve_exact(num_selected * self.element_size);
+        for (bytes, _) in self.byte_chunks().zip(mask).filter(|(_, &m)| m) {
+            buf.data.extend_from_slice(bytes);
+        }
+        buf
+    }
+
+    /// Retain only the elements whose corresponding `mask` entry is `true`.
+    ///
+    /// This is the in-place equivalent of `select`, preserving the order of the retained
+    /// elements.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the length of `mask` differs from the length of the buffer.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let mut buf = VecCopy::from_vec(vec![1u32, 2, 3, 4]);
+    /// buf.compress_by_mask(&[false, true, true, false]);
+    /// assert_eq!(buf.as_slice::<u32>().unwrap(), &[2, 3]);
+    /// ```
+    pub fn compress_by_mask(&mut self, mask: &[bool]) {
+        assert_eq!(
+            mask.len(),
+            self.len(),
+            "mask length doesn't match the buffer length"
+        );
+        let mut mask = mask.iter();
+        self.retain_bytes(|_| *mask.next().unwrap());
+    }
+
+    /// Remove the given range of elements from this buffer and return an iterator over the
+    /// removed elements.
+    ///
+    /// If `T` doesn't match the type stored by this buffer, `None` is returned and the buffer is
+    /// left unchanged. Otherwise the range is removed even if the iterator is not fully consumed,
+    /// just like with `Vec::drain`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the starting point is greater than the end point or if the end
+    /// point is greater than the length of the buffer.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let mut buf = VecCopy::from_vec(vec![1u32, 2, 3, 4, 5]);
+    /// let drained: Vec<u32> = buf.drain::<u32, _>(1..3).unwrap().collect();
+    /// assert_eq!(drained, vec![2, 3]);
+    /// assert_eq!(buf.as_slice::<u32>().unwrap(), &[1, 4, 5]);
+    /// ```
+    #[inline]
+    pub fn drain<T: Elem, R: RangeBounds<usize>>(&mut self, range: R) -> Option<Drain<'_, T>> {
+        self.check_ref::<T>()?;
+        let range = self.byte_range(range);
+        Some(Drain {
+            iter: self.data.drain(range),
+            phantom: PhantomData,
+        })
+    }
+
+    /// Convert the given range of elements into a range of bytes in this buffer.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the starting point is greater than the end point or if the end
+    /// point is greater than the length of the buffer.
+    #[inline]
+    pub(crate) fn byte_range<R: RangeBounds<usize>>(&self, range: R) -> Range<usize> {
+        let len = self.len();
+        let start = match range.start_bound() {
+            Bound::Included(&i) => i,
+            Bound::Excluded(&i) => i + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&i) => i + 1,
+            Bound::Excluded(&i) => i,
+            Bound::Unbounded => len,
+        };
+        assert!(start <= end, "range start {} is greater than range end {}", start, end);
+        assert!(end <= len, "range end {} is out of bounds for length {}", end, len);
+        start * self.element_size..end * self.element_size
+    }
+
+    /// Check if the current buffer contains elements of the specified type. Returns `Some(self)`
+    /// if the type matches and `None` otherwise.
+    #[inline]
+    pub fn check<T: Any>(self) -> Option<Self> {
+        if TypeId::of::<T>() != self.element_type_id() {
+            None
+        } else {
+            Some(self)
+        }
+    }
+
+    /// Check if the current buffer contains elements of the specified type. Returns `None` if the
+    /// check fails, otherwise a reference to self is returned.
+    #[inline]
+    pub fn check_ref<T: Any>(&self) -> Option<&Self> {
+        if TypeId::of::<T>() != self.element_type_id() {
+            None
+        } else {
+            Some(self)
+        }
+    }
+
+    /// Check if the current buffer contains elements of the specified type. Same as `check_ref`
+    /// but consumes and produces a mut reference to self.
+    #[inline]
+    pub fn check_mut<T: Any>(&mut self) -> Option<&mut Self> {
+        if TypeId::of::<T>() != self.element_type_id() {
+            None
+        } else {
+            Some(self)
+        }
+    }
+
+    /*
+     * Trait function capture
+     */
+
+    /// Capture the `Hash` implementation of the element type `T`, which is subsequently used to
+    /// hash this buffer.
+    ///
+    /// By default buffers of integers and arrays of integers are hashed by their bytes, and
+    /// buffers of floats by their values, with all zeros and all NaNs hashed alike. Since bytes
+    /// include any padding, buffers of other types are only hashed by their length, so that
+    /// equal buffers hash equally. Capturing this function hashes the elements themselves, which
+    /// makes buffers of such types usable as keys in a `HashMap` or `HashSet`.
+    ///
+    /// Note that for hashing to be consistent with equality, the function should be captured
+    /// for all buffers of the same type that are hashed together.
+    ///
+    /// Returns `None` if `T` doesn't match the type stored by this buffer.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// use std::collections::HashSet;
+    /// let mut set = HashSet::new();
+    /// set.insert(VecCopy::from_vec(vec![(1u8, 2u32)]).with_hash::<(u8, u32)>().unwrap());
+    /// let key = VecCopy::from_vec(vec![(1u8, 2u32)]).with_hash::<(u8, u32)>().unwrap();
+    /// assert!(set.contains(&key));
+    /// ```
+    #[inline]
+    pub fn with_hash<T: Elem + Hash + Eq>(mut self) -> Option<Self> {
+        self.check_ref::<T>()?;
+        if !vtable::is_bytewise_eq::<T>() {
+            self.vtable.hash = Some(HashFn(T::hash_bytes));
+        }
+        Some(self)
+    }
+
+    /// Capture the `PartialOrd` implementation of the element type `T`, which is subsequently
+    /// used to compare this buffer.
+    ///
+    /// By default unequal elements are ordered by their bytes, which generally doesn't match the
+    /// ordering of the elements themselves.
+    ///
+    /// Returns `None` if `T` doesn't match the type stored by this buffer.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let a = VecCopy::from_vec(vec![1.0f32, f32::NAN]).with_partial_ord::<f32>().unwrap();
+    /// let b = VecCopy::from_vec(vec![2.0f32]);
+    /// assert!(a < b);
+    /// assert_eq!(a.partial_cmp(&a), None);
+    /// ```
+    #[inline]
+    pub fn with_partial_ord<T: Elem + PartialOrd>(mut self) -> Option<Self> {
+        self.check_ref::<T>()?;
+        self.vtable.partial_cmp = Some(PartialCmpFn(T::partial_cmp_bytes));
+        Some(self)
+    }
+
+    /// Capture the `Ord` and `PartialOrd` implementations of the element type `T`, which are
+    /// subsequently used to compare this buffer.
+    ///
+    /// This makes buffers sortable by their elements with `PartialOrd`. Note that for the ordering
+    /// to be consistent, the functions should be captured for all buffers of the same type that
+    /// are compared together.
+    ///
+    /// Returns `None` if `T` doesn't match the type stored by this buffer.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let mut bufs = vec![
+    ///     VecCopy::from_vec(vec![256u32]).with_ord::<u32>().unwrap(),
+    ///     VecCopy::from_vec(vec![1u32, 2]).with_ord::<u32>().unwrap(),
+    /// ];
+    /// bufs.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    /// assert_eq!(bufs[0].as_slice::<u32>().unwrap(), &[1, 2]);
+    /// ```
+    #[inline]
+    pub fn with_ord<T: Elem + Ord>(self) -> Option<Self> {
+        let mut buf = self.with_partial_ord::<T>()?;
+        buf.vtable.cmp = Some(CmpFn(T::cmp_bytes));
+        Some(buf)
+    }
+
+    /// Capture the `Default` implementation of the element type `T`, which is subsequently used
+    /// to grow this buffer with `resize_with_default` and `push_default`.
+    ///
+    /// Returns `None` if `T` doesn't match the type stored by this buffer.
+    #[inline]
+    pub fn with_default<T: Elem + Default>(mut self) -> Option<Self> {
+        self.check_ref::<T>()?;
+        self.vtable.default = Some(DefaultFn(T::default_into_raw_bytes));
+        Some(self)
+    }
+
+    /// Capture the `Debug` implementation of the element type `T`, which is subsequently used
+    /// to print the elements of this buffer in its `Debug` output.
+    ///
+    /// By default elements are printed as arrays of bytes.
+    ///
+    /// Returns `None` if `T` doesn't match the type stored by this buffer.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let buf = VecCopy::from_vec(vec![1.5f32, 2.0]).with_debug::<f32>().unwrap();
+    /// assert_eq!(
+    ///     format!("{:?}", buf),
+    ///     "VecCopy { element_type_name: \"f32\", len: 2, data: [1.5, 2.0] }"
+    /// );
+    /// ```
+    #[inline]
+    pub fn with_debug<T: Elem + fmt::Debug>(mut self) -> Option<Self> {
+        self.check_ref::<T>()?;
+        self.vtable.fmt = Some(FmtFn(T::fmt_bytes));
+        Some(self)
+    }
+
+    /// Capture the `Display` implementation of the element type `T`, which is subsequently used
+    /// to print the elements of this buffer in its `Display` output.
+    ///
+    /// Numeric elements are always displayed with their own `Display` implementation. Elements of
+    /// other types are displayed with their captured `Debug` implementation if there is no
+    /// captured `Display` implementation, and as hexadecimal bytes otherwise.
+    ///
+    /// Returns `None` if `T` doesn't match the type stored by this buffer.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::fmt;
+    /// use data_buffer::VecCopy;
+    ///
+    /// #[derive(Copy, Clone, PartialEq)]
+    /// struct Letter(u8);
+    ///
+    /// impl fmt::Display for Letter {
+    ///     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    ///         write!(f, "{}", self.0 as char)
+    ///     }
+    /// }
+    ///
+    /// let buf = VecCopy::from_vec(vec![Letter(b'a'), Letter(b'b')]);
+    /// assert_eq!(buf.to_string(), "0x61 0x62");
+    /// assert_eq!(buf.with_display::<Letter>().unwrap().to_string(), "a b");
+    /// ```
+    #[inline]
+    pub fn with_display<T: Elem + fmt::Display>(mut self) -> Option<Self> {
+        self.check_ref::<T>()?;
+        self.vtable.display = Some(FmtFn(T::display_bytes));
+        Some(self)
+    }
+
+    /// Record that the element type `T` is `Send`, which allows this buffer to be converted
+    /// into a `SendVecCopy` with `into_send`.
+    ///
+    /// Primitive numeric, boolean and character types are recorded as `Send` and `Sync` at
+    /// construction.
+    ///
+    /// Returns `None` if `T` doesn't match the type stored by this buffer.
+    #[inline]
+    pub fn with_send<T: Elem + Send>(mut self) -> Option<Self> {
+        self.check_ref::<T>()?;
+        self.vtable.send = true;
+        Some(self)
+    }
+
+    /// Record that the element type `T` is `Sync`, which, together with `with_send`, allows this
+    /// buffer to be converted into a `SyncVecCopy` with `into_sync`.
+    ///
+    /// Returns `None` if `T` doesn't match the type stored by this buffer.
+    #[inline]
+    pub fn with_sync<T: Elem + Sync>(mut self) -> Option<Self> {
+        self.check_ref::<T>()?;
+        self.vtable.sync = true;
+        Some(self)
+    }
+
+    /*
+     * Accessors
+     */
+
+    /// Get the `TypeId` of data stored within this buffer.
+    #[inline]
+    pub fn element_type_id(&self) -> TypeId {
+        self.element_type_id
+    }
+
+    /// Get the stable identifier of the type of data stored within this buffer.
+    ///
+    /// Returns `None` if the type was not registered with [`register_type`].
+    #[inline]
+    pub fn stable_type_id(&self) -> Option<StableTypeId> {
+        StableTypeId::of_type_id(self.element_type_id)
+    }
+
+    /// Get the name of the type of data stored within this buffer.
+    ///
+    /// The name is intended for diagnostics only, and `"<unknown>"` is returned when it is not
+    /// known, for instance when the buffer was deserialized or built from untyped values.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let buf = VecCopy::from_vec(vec![1.0_f32, 2.0]);
+    /// assert_eq!(buf.element_type_name(), "f32");
+    /// ```
+    #[inline]
+    pub fn element_type_name(&self) -> &'static str {
+        self.element_type_name.0.unwrap_or("<unknown>")
+    }
+
+    /// Get the number of elements stored in this buffer.
+    #[inline]
+    pub fn len(&self) -> usize {
+        debug_assert_eq!(self.data.len() % self.element_size, 0);
+        self.data.len() / self.element_size // element_size is guaranteed to be strictly positive
+    }
+
+    /// Check if there are any elements stored in this buffer.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Get the byte capacity of this buffer.
+    #[inline]
+    pub fn byte_capacity(&self) -> usize {
+        self.data.capacity()
+    }
+
+    /// Get the number of elements this buffer can hold without reallocating.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.data.capacity() / self.element_size
+    }
+
+    /// Get the number of bytes of capacity occupied by elements of this buffer.
+    #[inline]
+    pub fn capacity_bytes_used(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Get the number of bytes of capacity not occupied by elements of this buffer.
+    ///
+    /// This memory can be released with `shrink_to_fit`.
+    #[inline]
+    pub fn capacity_bytes_wasted(&self) -> usize {
+        self.data.capacity() - self.data.len()
+    }
+
+    /// Get the number of bytes allocated on the heap by this buffer.
+    ///
+    /// This excludes the size of the `VecCopy` itself, and any data stored inline when the
+    /// `smallbuffer` feature is enabled.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let mut buf = VecCopy::with_capacity::<u64>(100);
+    /// buf.extend_from_slice(&[1u64; 10]);
+    /// assert_eq!(buf.heap_size_bytes(), 800);
+    /// assert_eq!(buf.capacity_bytes_used(), 80);
+    /// assert_eq!(buf.capacity_bytes_wasted(), 720);
+    /// ```
+    #[inline]
+    pub fn heap_size_bytes(&self) -> usize {
+        self.data.heap_size()
+    }
+
+    /// Get the size of the element type in bytes.
+    #[inline]
+    pub fn element_size(&self) -> usize {
+        self.element_size
+    }
+
+    /// Get the alignment of the data stored in this buffer in bytes.
+    ///
+    /// This is at least the alignment of the element type.
+    #[inline]
+    pub fn alignment(&self) -> usize {
+        self.data.align()
+    }
+
+    /// Return an iterator to a slice representing typed data.
+    /// Returs `None` if the given type `T` doesn't match the internal.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let vec = vec![1.0_f32, 23.0, 0.01, 42.0, 11.43];
+    /// let buf = VecCopy::from(vec.clone()); // Convert into buffer
+    /// for (i, &val) in buf.iter::<f32>().unwrap().enumerate() {
+    ///     assert_eq!(val, vec[i]);
+    /// }
+    /// ```
+    #[inline]
+    pub fn iter<'a, T: Any>(&'a self) -> Option<slice::Iter<T>> {
+        self.as_slice::<T>().map(|x| x.iter())
+    }
+
+    /// Return an iterator to a mutable slice representing typed data.
+    /// Returns `None` if the given type `T` doesn't match the internal.
+    #[inline]
+    pub fn iter_mut<'a, T: Any>(&'a mut self) -> Option<slice::IterMut<T>> {
+        self.as_mut_slice::<T>().map(|x| x.iter_mut())
+    }
+
+    /// Iterate over pairs of corresponding typed elements in this buffer and `other`.
+    ///
+    /// Returns `None` if `A` doesn't match the type of this buffer, `B` doesn't match the type of
+    /// `other`, or if the lengths of the buffers differ.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let values = VecCopy::from_vec(vec![1.0f32, 2.0, 3.0]);
+    /// let mask = VecCopy::from_vec(vec![true, false, true]);
+    /// let sum: f32 = values
+    ///     .zip_iter::<f32, bool>(&mask)
+    ///     .unwrap()
+    ///     .filter_map(|(&v, &m)| if m { Some(v) } else { None })
+    ///     .sum();
+    /// assert_eq!(sum, 4.0);
+    /// ```
+    #[inline]
+    pub fn zip_iter<'a, A: Any, B: Any>(
+        &'a self,
+        other: &'a VecCopy,
+    ) -> Option<std::iter::Zip<slice::Iter<'a, A>, slice::Iter<'a, B>>> {
+        let (a, b) = (self.as_slice::<A>()?, other.as_slice::<B>()?);
+        if a.len() != b.len() {
+            return None;
+        }
+        Some(a.iter().zip(b))
+    }
+
+    /// Return an iterator over chunks of `chunk_size` typed elements, where the last chunk may be
+    /// shorter.
+    ///
+    /// Returns `None` if the given type `T` doesn't match the internal.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `chunk_size` is zero.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let buf = VecCopy::from_vec(vec![1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0]);
+    /// let mut chunks = buf.chunks::<f32>(3).unwrap();
+    /// assert_eq!(chunks.next(), Some(&[1.0, 2.0, 3.0][..]));
+    /// assert_eq!(chunks.next(), Some(&[4.0, 5.0, 6.0][..]));
+    /// assert_eq!(chunks.next(), Some(&[7.0][..]));
+    /// ```
+    #[inline]
+    pub fn chunks<T: Any>(&self, chunk_size: usize) -> Option<slice::Chunks<'_, T>> {
+        self.as_slice::<T>().map(|x| x.chunks(chunk_size))
+    }
+
+    /// Return an iterator over mutable chunks of `chunk_size` typed elements, where the last
+    /// chunk may be shorter.
+    ///
+    /// Returns `None` if the given type `T` doesn't match the internal.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `chunk_size` is zero.
+    #[inline]
+    pub fn chunks_mut<T: Any>(&mut self, chunk_size: usize) -> Option<slice::ChunksMut<'_, T>> {
+        self.as_mut_slice::<T>().map(|x| x.chunks_mut(chunk_size))
+    }
+
+    /// Return an iterator over chunks of exactly `chunk_size` typed elements.
+    ///
+    /// Trailing elements that don't fill a chunk are skipped, and can be retrieved with the
+    /// `remainder` method of the iterator. Returns `None` if the given type `T` doesn't match the
+    /// internal.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `chunk_size` is zero.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// // Positions stored as flat coordinates.
+    /// let buf = VecCopy::from_vec(vec![0.0f64, 1.0, 2.0, 3.0, 4.0, 5.0]);
+    /// let sums: Vec<f64> = buf.chunks_exact::<f64>(3).unwrap().map(|p| p.iter().sum()).collect();
+    /// assert_eq!(sums, vec![3.0, 12.0]);
+    /// ```
+    #[inline]
+    pub fn chunks_exact<T: Any>(&self, chunk_size: usize) -> Option<slice::ChunksExact<'_, T>> {
+        self.as_slice::<T>().map(|x| x.chunks_exact(chunk_size))
+    }
+
+    /// Return an iterator over mutable chunks of exactly `chunk_size` typed elements.
+    ///
+    /// Trailing elements that don't fill a chunk are skipped, and can be retrieved with the
+    /// `into_remainder` method of the iterator. Returns `None` if the given type `T` doesn't
+    /// match the internal.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `chunk_size` is zero.
+    #[inline]
+    pub fn chunks_exact_mut<T: Any>(
+        &mut self,
+        chunk_size: usize,
+    ) -> Option<slice::ChunksExactMut<'_, T>> {
+        self.as_mut_slice::<T>()
+            .map(|x| x.chunks_exact_mut(chunk_size))
+    }
+
+    /// Return an iterator over all overlapping windows of `size` typed elements.
+    ///
+    /// If the buffer is shorter than `size`, the iterator returns no windows. Returns `None` if
+    /// the given type `T` doesn't match the internal.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `size` is zero.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let buf = VecCopy::from_vec(vec![1.0f32, 4.0, 9.0, 16.0]);
+    /// let diffs: Vec<f32> = buf.windows::<f32>(2).unwrap().map(|w| w[1] - w[0]).collect();
+    /// assert_eq!(diffs, vec![3.0, 5.0, 7.0]);
+    /// ```
+    #[inline]
+    pub fn windows<T: Any>(&self, size: usize) -> Option<slice::Windows<'_, T>> {
+        self.as_slice::<T>().map(|x| x.windows(size))
+    }
+
+    /// Borrow the elements of this buffer as a slice of arrays of `N` typed elements.
+    ///
+    /// Returns `None` if the given type `T` doesn't match the internal, if `N` is zero or if the
+    /// length of the buffer is not a multiple of `N`.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// // Positions stored as flat coordinates.
+    /// let buf = VecCopy::from_vec(vec![0.0f64, 1.0, 2.0, 3.0, 4.0, 5.0]);
+    /// let points = buf.as_arrays::<f64, 3>().unwrap();
+    /// assert_eq!(points, &[[0.0, 1.0, 2.0], [3.0, 4.0, 5.0]]);
+    /// assert!(buf.as_arrays::<f64, 4>().is_none());
+    /// ```
+    #[inline]
+    pub fn as_arrays<T: Any, const N: usize>(&self) -> Option<&[[T; N]]> {
+        let slice = self.as_slice::<T>()?;
+        if N == 0 || slice.len() % N != 0 {
+            return None;
+        }
+        // This is safe since `[T; N]` has the same alignment as `T` and the size of `N`
+        // consecutive elements of `T`, and the length was checked above.
+        Some(unsafe { slice::from_raw_parts(slice.as_ptr() as *const [T; N], slice.len() / N) })
+    }
+
+    /// Mutably borrow the elements of this buffer as a slice of arrays of `N` typed elements.
+    ///
+    /// Returns `None` if the given type `T` doesn't match the internal, if `N` is zero or if the
+    /// length of the buffer is not a multiple of `N`.
+    #[inline]
+    pub fn as_arrays_mut<T: Any, const N: usize>(&mut self) -> Option<&mut [[T; N]]> {
+        let slice = self.as_mut_slice::<T>()?;
+        if N == 0 || slice.len() % N != 0 {
+            return None;
+        }
+        // This is safe for the same reasons as in `as_arrays`.
+        Some(unsafe {
+            slice::from_raw_parts_mut(slice.as_mut_ptr() as *mut [T; N], slice.len() / N)
+        })
+    }
+
+    /// Borrow the elements of a buffer of arrays `[T; N]` as a flat slice of `T`.
+    ///
+    /// This is the inverse of `as_arrays`. Returns `None` if `[T; N]` doesn't match the internal
+    /// type.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let buf = VecCopy::from_vec(vec![[1u32, 2], [3, 4]]);
+    /// assert_eq!(buf.as_flattened::<u32, 2>().unwrap(), &[1, 2, 3, 4]);
+    /// ```
+    #[inline]
+    pub fn as_flattened<T: Any, const N: usize>(&self) -> Option<&[T]> {
+        let arrays = self.as_slice::<[T; N]>()?;
+        // This is safe since `[T; N]` is laid out as `N` consecutive elements of `T`.
+        Some(unsafe { slice::from_raw_parts(arrays.as_ptr() as *const T, arrays.len() * N) })
+    }
+
+    /// Convert a buffer of arrays `[T; N]` into a buffer of `T` with `N` times as many elements.
+    ///
+    /// The data is reused without copying. Functions captured for the array type with methods
+    /// like `with_debug` are discarded. Returns `None` if `[T; N]` doesn't match the internal
+    /// type.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let buf = VecCopy::from_vec(vec![[1.0f32, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+    /// let flat = buf.flatten_arrays::<f32, 3>().unwrap();
+    /// assert_eq!(flat.len(), 6);
+    /// assert_eq!(flat.as_slice::<f32>().unwrap(), &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    /// ```
+    #[inline]
+    pub fn flatten_arrays<T: Elem, const N: usize>(mut self) -> Option<VecCopy> {
+        self.check_ref::<[T; N]>()?;
+        // The data is aligned for `T`, since `[T; N]` has the same alignment as `T`.
+        self.element_size = size_of::<T>();
+        self.element_type_id = TypeId::of::<T>();
+        self.element_type_name = TypeName::of::<T>();
+        self.vtable = CopyVTable::of_elem::<T>();
+        Some(self)
+    }
+
+    /// Append copied items from this buffer to a given `Vec<T>`. Return the mutable reference
+    /// `Some(vec)` if type matched the internal type and `None` otherwise. This may be faster than
+    /// `append_clone_to_vec`.
+    #[inline]
+    pub fn append_to_vec<'a, T: Elem>(&self, vec: &'a mut Vec<T>) -> Option<&'a mut Vec<T>> {
+        // Elements are copied with a single `memcpy`.
+        vec.extend_from_slice(self.as_slice()?);
+        Some(vec)
+    }
+
+    /// Copies contents of `self` into the given `Vec`.
+    #[inline]
+    pub fn copy_into_vec<T: Elem>(&self) -> Option<Vec<T>> {
+        self.as_slice().map(<[T]>::to_vec)
+    }
+
+    /// An alternative to using the `Into` trait. This function helps the compiler
+    /// determine the type `T` automatically.
+    #[inline]
+    pub fn into_vec<T: Any>(self) -> Option<Vec<T>> {
+        // This is safe since `T` is `Elem` guaranteed at construction.
+        unsafe { self.check::<T>().map(|x| x.reinterpret_into_vec()) }
+    }
+
+    /// Convert this buffer into a boxed slice, dropping any excess capacity.
+    ///
+    /// The allocation is reused if it is compatible with `Box<[T]>`. Returns `None` if the given
+    /// type `T` doesn't match the internal.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let boxed: Box<[u32]> = vec![1, 2, 3].into_boxed_slice();
+    /// let mut buf = VecCopy::from(boxed);
+    /// buf.push(4u32);
+    /// assert_eq!(&*buf.into_boxed_slice::<u32>().unwrap(), &[1, 2, 3, 4]);
+    /// ```
+    #[inline]
+    pub fn into_boxed_slice<T: Any>(mut self) -> Option<Box<[T]>> {
+        self.check_ref::<T>()?;
+        self.shrink_to_fit();
+        self.into_vec().map(Vec::into_boxed_slice)
+    }
+
+    /// Convert this buffer into an iterator that moves typed elements out of it.
+    ///
+    /// This reuses the buffer's allocation, so no additional `Vec` is created.
+    /// Returns `None` if the given type `T` doesn't match the internal.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let buf = VecCopy::from_vec(vec![1u32, 2, 3]);
+    /// let mut iter = buf.into_typed_iter::<u32>().unwrap();
+    /// assert_eq!(iter.len(), 3);
+    /// assert_eq!(iter.next_back(), Some(3));
+    /// assert_eq!(iter.collect::<Vec<_>>(), vec![1, 2]);
+    /// ```
+    #[inline]
+    pub fn into_typed_iter<T: Elem>(self) -> Option<IntoIter<T>> {
+        Some(IntoIter {
+            iter: self.check::<T>()?.data.into_iter(),
+            phantom: PhantomData,
+        })
+    }
+
+    /// Convert this buffer into a typed slice.
+    /// Returs `None` if the given type `T` doesn't match the internal.
+    #[inline]
+    pub fn as_slice<T: Any>(&self) -> Option<&[T]> {
+        debug_assert!(self.check_ref::<T>().is_none() || self.data.align() >= align_of::<T>());
+        let ptr = self.check_ref::<T>()?.data.as_ptr() as *const T;
+        Some(unsafe { slice::from_raw_parts(ptr, self.len()) })
+    }
+
+    /// Convert this buffer into a typed mutable slice.
+    /// Returs `None` if the given type `T` doesn't match the internal.
+    #[inline]
+    pub fn as_mut_slice<T: Any>(&mut self) -> Option<&mut [T]> {
+        debug_assert!(self.check_ref::<T>().is_none() || self.data.align() >= align_of::<T>());
+        let ptr = self.check_mut::<T>()?.data.as_mut_ptr() as *mut T;
+        Some(unsafe { slice::from_raw_parts_mut(ptr, self.len()) })
+    }
+
+    /// Get `i`'th element of the buffer by value.
+    ///
+    /// Returns `None` if `T` doesn't match the type stored by this buffer or if `i` is out of
+    /// bounds. Use `try_get` to distinguish between the two.
+    #[inline]
+    pub fn get<T: Elem>(&self, i: usize) -> Option<T> {
+        if i >= self.len() {
+            return None;
+        }
+        let ptr = self.check_ref::<T>()?.data.as_ptr() as *const T;
+        Some(unsafe { *ptr.add(i) })
+    }
+
+    /// Get a `const` reference to the `i`'th element of the buffer.
+    ///
+    /// Returns `None` if `T` doesn't match the type stored by this buffer or if `i` is out of
+    /// bounds. Use `try_get_ref` to distinguish between the two.
+    #[inline]
+    pub fn get_ref<T: Any>(&self, i: usize) -> Option<&T> {
+        if i >= self.len() {
+            return None;
+        }
+        let ptr = self.check_ref::<T>()?.data.as_ptr() as *const T;
+        Some(unsafe { &*ptr.add(i) })
+    }
+
+    /// Get a mutable reference to the `i`'th element of the buffer.
+    ///
+    /// Returns `None` if `T` doesn't match the type stored by this buffer or if `i` is out of
+    /// bounds. Use `try_get_mut` to distinguish between the two.
+    #[inline]
+    pub fn get_mut<T: Any>(&mut self, i: usize) -> Option<&mut T> {
+        if i >= self.len() {
+            return None;
+        }
+        let ptr = self.check_mut::<T>()?.data.as_mut_ptr() as *mut T;
+        Some(unsafe { &mut *ptr.add(i) })
+    }
+
+    /// Get mutable references to the elements at the given distinct indices at once.
+    ///
+    /// Returns `None` if `T` doesn't match the type stored by this buffer, or if any index is out
+    /// of bounds or appears more than once.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use data_buffer::*;
+    /// let mut buf = VecCopy::from_vec(vec![1u32,2,3,4,5]);
+    /// let [a, b] = buf.get_disjoint_mut::<u32, 2>([4, 0]).unwrap();
+    /// std::mem::swap(a, b);
+    /// assert_eq!(buf.as_slice::<u32>().unwrap(), &[5,2,3,4,1]);
+    /// assert!(buf.get_disjoint_mut::<u32, 2>([1, 1]).is_none());
+    /// ```
+    pub fn get_disjoint_mut<T: Any, const N: usize>(
+        &mut self,
+        indices: [usize; N],
+    ) -> Option<[&mut T; N]> {
+        let len = self.len();
+        for (k, &i) in indices.iter().enumerate() {
+            if i >= len || indices[..k].contains(&i) {
+                return None;
+            }
+        }
+        let ptr = self.check_mut::<T>()?.data.as_mut_ptr() as *mut T;
+        // Safety: the indices are in bounds and distinct, so the references don't alias.
+        Some(indices.map(|i| unsafe { &mut *ptr.add(i) }))
+    }
+
+    /// Overwrite the `i`'th element of the buffer with the given value.
+    ///
+    /// Returns `None` if `T` doesn't match the type stored by this buffer or if `i` is out of
+    /// bounds, in which case the buffer is left unchanged.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let mut buf = VecCopy::from_vec(vec![1u32, 2, 3]);
+    /// buf.set(1, 20u32).unwrap();
+    /// assert_eq!(buf.as_slice::<u32>().unwrap(), &[1, 20, 3]);
+    /// assert!(buf.set(3, 4u32).is_none());
+    /// ```
     #[inline]
-    pub fn into_vec<T: Any>(self) -> Option<Vec<T>> {
-        // This is safe since `T` is `Elem` guaranteed at construction.
-        unsafe { self.check::<T>().map(|x| x.reinterpret_into_vec()) }
+    pub fn set<T: Elem>(&mut self, i: usize, value: T) -> Option<&mut Self> {
+        *self.get_mut::<T>(i)? = value;
+        Some(self)
+    }
+
+    /// Get a `const` reference to the first element of the buffer.
+    ///
+    /// Returns `None` if `T` doesn't match the type stored by this buffer or if the buffer is
+    /// empty.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let buf = VecCopy::from_vec(vec![1u32, 2, 3]);
+    /// assert_eq!(buf.first::<u32>(), Some(&1));
+    /// assert_eq!(VecCopy::with_type::<u32>().first::<u32>(), None);
+    /// ```
+    #[inline]
+    pub fn first<T: Any>(&self) -> Option<&T> {
+        self.as_slice::<T>()?.first()
+    }
+
+    /// Get a `const` reference to the last element of the buffer.
+    ///
+    /// Returns `None` if `T` doesn't match the type stored by this buffer or if the buffer is
+    /// empty.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let buf = VecCopy::from_vec(vec![1u32, 2, 3]);
+    /// assert_eq!(buf.last::<u32>(), Some(&3));
+    /// ```
+    #[inline]
+    pub fn last<T: Any>(&self) -> Option<&T> {
+        self.as_slice::<T>()?.last()
+    }
+
+    /*
+     * Result API. These mirror the accessors above but report why the access failed.
+     */
+
+    /// Build a `TypeMismatchError` describing a failed access to elements of type `T`.
+    #[inline]
+    fn type_mismatch<T: Any>(&self) -> TypeMismatchError {
+        TypeMismatchError::new::<T>(self.element_type_id, self.element_type_name.0)
+    }
+
+    /// Check if the current buffer contains elements of the specified type. Returns a
+    /// `TypeMismatchError` describing the expected and found types if the check fails.
+    #[inline]
+    pub fn try_check_ref<T: Any>(&self) -> Result<&Self, TypeMismatchError> {
+        self.check_ref::<T>().ok_or_else(|| self.type_mismatch::<T>())
+    }
+
+    /// Same as `try_check_ref` but consumes and produces a mut reference to self.
+    #[inline]
+    pub fn try_check_mut<T: Any>(&mut self) -> Result<&mut Self, TypeMismatchError> {
+        if TypeId::of::<T>() != self.element_type_id() {
+            Err(self.type_mismatch::<T>())
+        } else {
+            Ok(self)
+        }
+    }
+
+    /// Check that `i` is a valid element index into this buffer.
+    #[inline]
+    fn check_index(&self, i: usize) -> Result<(), AccessError> {
+        let len = self.len();
+        if i < len {
+            Ok(())
+        } else {
+            Err(AccessError::OutOfBounds { index: i, len })
+        }
     }
 
     /// Convert this buffer into a typed slice.
-    /// Returs `None` if the given type `T` doesn't match the internal.
+    ///
+    /// This is the same as `as_slice` but returns an `AccessError` on failure.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::{AccessError, VecCopy};
+    /// let buf = VecCopy::from_vec(vec![1u32, 2, 3]);
+    /// assert_eq!(buf.try_as_slice::<u32>().unwrap(), &[1, 2, 3]);
+    /// match buf.try_as_slice::<f32>() {
+    ///     Err(AccessError::TypeMismatch(err)) => assert_eq!(err.expected_name(), "f32"),
+    ///     _ => unreachable!(),
+    /// }
+    /// ```
     #[inline]
-    pub fn as_slice<T: Any>(&self) -> Option<&[T]> {
-        let ptr = self.check_ref::<T>()?.data.as_ptr() as *const T;
-        Some(unsafe { slice::from_raw_parts(ptr, self.len()) })
+    pub fn try_as_slice<T: Any>(&self) -> Result<&[T], AccessError> {
+        self.try_check_ref::<T>()?;
+        Ok(self.as_slice::<T>().unwrap())
     }
 
     /// Convert this buffer into a typed mutable slice.
-    /// Returs `None` if the given type `T` doesn't match the internal.
+    ///
+    /// This is the same as `as_mut_slice` but returns an `AccessError` on failure.
     #[inline]
-    pub fn as_mut_slice<T: Any>(&mut self) -> Option<&mut [T]> {
-        let ptr = self.check_mut::<T>()?.data.as_mut_ptr() as *mut T;
-        Some(unsafe { slice::from_raw_parts_mut(ptr, self.len()) })
+    pub fn try_as_mut_slice<T: Any>(&mut self) -> Result<&mut [T], AccessError> {
+        self.try_check_mut::<T>()?;
+        Ok(self.as_mut_slice::<T>().unwrap())
+    }
+
+    /// Return an iterator to a slice representing typed data.
+    ///
+    /// This is the same as `iter` but returns an `AccessError` on failure.
+    #[inline]
+    pub fn try_iter<T: Any>(&self) -> Result<slice::Iter<'_, T>, AccessError> {
+        self.try_as_slice::<T>().map(|x| x.iter())
+    }
+
+    /// Return an iterator to a mutable slice representing typed data.
+    ///
+    /// This is the same as `iter_mut` but returns an `AccessError` on failure.
+    #[inline]
+    pub fn try_iter_mut<T: Any>(&mut self) -> Result<slice::IterMut<'_, T>, AccessError> {
+        self.try_as_mut_slice::<T>().map(|x| x.iter_mut())
+    }
+
+    /// Convert this buffer into a `Vec<T>`.
+    ///
+    /// This is the same as `into_vec` but returns an `AccessError` on failure.
+    #[inline]
+    pub fn try_into_vec<T: Any>(self) -> Result<Vec<T>, AccessError> {
+        self.try_check_ref::<T>()?;
+        Ok(self.into_vec::<T>().unwrap())
+    }
+
+    /// Copy the contents of this buffer into a new `Vec<T>`.
+    ///
+    /// This is the same as `copy_into_vec` but returns an `AccessError` on failure.
+    #[inline]
+    pub fn try_copy_into_vec<T: Elem>(&self) -> Result<Vec<T>, AccessError> {
+        Ok(self.try_as_slice::<T>()?.to_vec())
+    }
+
+    /// Add an element to this buffer.
+    ///
+    /// This is the same as `push` but returns an `AccessError` on failure.
+    #[inline]
+    pub fn try_push<T: Elem>(&mut self, element: T) -> Result<&mut Self, AccessError> {
+        self.try_check_mut::<T>()?;
+        Ok(self.push(element).unwrap())
     }
 
     /// Get `i`'th element of the buffer by value.
+    ///
+    /// Unlike `get`, this function reports whether the type or the index was invalid with an
+    /// `AccessError`.
     #[inline]
-    pub fn get<T: Elem>(&self, i: usize) -> Option<T> {
-        assert!(i < self.len());
-        let ptr = self.check_ref::<T>()?.data.as_ptr() as *const T;
-        Some(unsafe { *ptr.add(i) })
+    pub fn try_get<T: Elem>(&self, i: usize) -> Result<T, AccessError> {
+        self.try_get_ref::<T>(i).copied()
     }
 
     /// Get a `const` reference to the `i`'th element of the buffer.
+    ///
+    /// Unlike `get_ref`, this function reports whether the type or the index was invalid with an
+    /// `AccessError`.
     #[inline]
-    pub fn get_ref<T: Any>(&self, i: usize) -> Option<&T> {
-        assert!(i < self.len());
-        let ptr = self.check_ref::<T>()?.data.as_ptr() as *const T;
-        Some(unsafe { &*ptr.add(i) })
+    pub fn try_get_ref<T: Any>(&self, i: usize) -> Result<&T, AccessError> {
+        self.try_check_ref::<T>()?;
+        self.check_index(i)?;
+        Ok(self.get_ref::<T>(i).unwrap())
     }
 
     /// Get a mutable reference to the `i`'th element of the buffer.
+    ///
+    /// Unlike `get_mut`, this function reports whether the type or the index was invalid with an
+    /// `AccessError`.
     #[inline]
-    pub fn get_mut<T: Any>(&mut self, i: usize) -> Option<&mut T> {
-        assert!(i < self.len());
-        let ptr = self.check_mut::<T>()?.data.as_mut_ptr() as *mut T;
-        Some(unsafe { &mut *ptr.add(i) })
+    pub fn try_get_mut<T: Any>(&mut self, i: usize) -> Result<&mut T, AccessError> {
+        self.try_check_mut::<T>()?;
+        self.check_index(i)?;
+        Ok(self.get_mut::<T>(i).unwrap())
     }
 
     /// Move elements from `buf` to this buffer.
     ///
-    /// The given buffer must have the same underlying type as `self`.
+    /// The given buffer must have the same underlying type as `self`.
+    #[inline]
+    pub fn append(&mut self, buf: &mut VecCopy) -> Option<&mut Self> {
+        if buf.element_type_id() == self.element_type_id() {
+            self.data.append(&mut buf.data);
+            Some(self)
+        } else {
+            None
+        }
+    }
+
+    /// Split this buffer into two at the given element index.
+    ///
+    /// Returns a newly allocated buffer containing the elements in the range `[at, len)` with the
+    /// same element type. After the call, `self` is left containing the elements `[0, at)` with
+    /// its previous capacity unchanged.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `at > len`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use data_buffer::*;
+    /// let mut buf = VecCopy::from_vec(vec![1u32,2,3,4,5]);
+    /// let tail = buf.split_off(3);
+    /// assert_eq!(buf.as_slice::<u32>().unwrap(), &[1,2,3]);
+    /// assert_eq!(tail.as_slice::<u32>().unwrap(), &[4,5]);
+    /// ```
+    #[inline]
+    pub fn split_off(&mut self, at: usize) -> VecCopy {
+        assert!(at <= self.len(), "`at` split index {} is out of bounds", at);
+        let mut tail = VecCopy::with_type_from(self);
+        tail.data = self.data.split_off(at * self.element_size);
+        tail
+    }
+
+    /// Borrow the contents of this buffer as an untyped immutable slice.
+    #[inline]
+    pub fn as_slice_copy(&self) -> SliceCopy<'_> {
+        SliceCopy {
+            data: &self.data,
+            element_size: self.element_size,
+            element_type_id: self.element_type_id,
+            element_type_name: self.element_type_name,
+            vtable: self.vtable,
+        }
+    }
+
+    /// Borrow the contents of this buffer as an untyped mutable slice.
+    #[inline]
+    pub fn as_slice_copy_mut(&mut self) -> SliceCopyMut<'_> {
+        SliceCopyMut {
+            data: &mut self.data,
+            element_size: self.element_size,
+            element_type_id: self.element_type_id,
+            element_type_name: self.element_type_name,
+            vtable: self.vtable,
+        }
+    }
+
+    /// Borrow the given range of elements of this buffer as an untyped immutable slice.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the starting point is greater than the end point or if the end
+    /// point is greater than the length of the buffer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use data_buffer::*;
+    /// let buf = VecCopy::from_vec(vec![1u32,2,3,4,5]);
+    /// let view = buf.view(1..3);
+    /// assert_eq!(view.as_slice::<u32>().unwrap(), &[2,3]);
+    /// ```
+    #[inline]
+    pub fn view<R: RangeBounds<usize>>(&self, range: R) -> SliceCopy<'_> {
+        let range = self.byte_range(range);
+        SliceCopy {
+            data: &self.data[range],
+            element_size: self.element_size,
+            element_type_id: self.element_type_id,
+            element_type_name: self.element_type_name,
+            vtable: self.vtable,
+        }
+    }
+
+    /// Borrow the given range of elements of this buffer as an untyped mutable slice.
+    ///
+    /// Elements outside of `range` are not accessible through the returned slice.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the starting point is greater than the end point or if the end
+    /// point is greater than the length of the buffer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use data_buffer::*;
+    /// let mut buf = VecCopy::from_vec(vec![1u32,2,3,4,5]);
+    /// buf.view_mut(3..).as_mut_slice::<u32>().unwrap().fill(0);
+    /// assert_eq!(buf.as_slice::<u32>().unwrap(), &[1,2,3,0,0]);
+    /// ```
+    #[inline]
+    pub fn view_mut<R: RangeBounds<usize>>(&mut self, range: R) -> SliceCopyMut<'_> {
+        let range = self.byte_range(range);
+        SliceCopyMut {
+            data: &mut self.data[range],
+            element_size: self.element_size,
+            element_type_id: self.element_type_id,
+            element_type_name: self.element_type_name,
+            vtable: self.vtable,
+        }
+    }
+
+    /// Divide this buffer into two untyped immutable slices at the element index `mid`.
+    ///
+    /// The first will contain all elements from `[0, mid)` and the second will contain all
+    /// elements from `[mid, len)`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `mid > len`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use data_buffer::*;
+    /// let buf = VecCopy::from_vec(vec![1u32,2,3,4,5]);
+    /// let (l, r) = buf.split_at(2);
+    /// assert_eq!(l.as_slice::<u32>().unwrap(), &[1,2]);
+    /// assert_eq!(r.as_slice::<u32>().unwrap(), &[3,4,5]);
+    /// ```
+    #[inline]
+    pub fn split_at(&self, mid: usize) -> (SliceCopy<'_>, SliceCopy<'_>) {
+        self.as_slice_copy().split_at(mid)
+    }
+
+    /// Divide this buffer into two non-overlapping untyped mutable slices at the element index
+    /// `mid`.
+    ///
+    /// The first will contain all elements from `[0, mid)` and the second will contain all
+    /// elements from `[mid, len)`. The two halves can be modified independently, for instance
+    /// from separate threads.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `mid > len`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use data_buffer::*;
+    /// let mut buf = VecCopy::from_vec(vec![1u32,2,3,4,5]);
+    /// let (mut l, mut r) = buf.split_at_mut(2);
+    /// l.as_mut_slice::<u32>().unwrap()[0] = 10;
+    /// r.as_mut_slice::<u32>().unwrap()[0] = 30;
+    /// assert_eq!(buf.as_slice::<u32>().unwrap(), &[10,2,30,4,5]);
+    /// ```
+    #[inline]
+    pub fn split_at_mut(&mut self, mid: usize) -> (SliceCopyMut<'_>, SliceCopyMut<'_>) {
+        self.as_slice_copy_mut().split_at_mut(mid)
+    }
+
+    /// Divide this buffer into non-overlapping untyped mutable slices of `chunk_size` elements.
+    ///
+    /// If the length of the buffer is not divisible by `chunk_size`, the last chunk is shorter.
+    /// Each chunk can be modified independently, for instance from separate threads.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `chunk_size` is zero.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use data_buffer::*;
+    /// let mut buf = VecCopy::from_vec(vec![1u32,2,3,4,5]);
+    /// for (i, mut chunk) in buf.chunks_mut_erased(2).enumerate() {
+    ///     chunk.as_mut_slice::<u32>().unwrap()[0] = i as u32;
+    /// }
+    /// assert_eq!(buf.as_slice::<u32>().unwrap(), &[0,2,1,4,2]);
+    /// ```
+    #[inline]
+    pub fn chunks_mut_erased(
+        &mut self,
+        chunk_size: usize,
+    ) -> impl ExactSizeIterator<Item = SliceCopyMut<'_>> + '_ {
+        assert_ne!(chunk_size, 0, "chunk size must be non-zero");
+        // Clamp the chunk size to the length so the size in bytes can't overflow.
+        let chunk_size = chunk_size.min(self.len().max(1));
+        let &mut Self {
+            ref mut data,
+            element_size,
+            element_type_id,
+            element_type_name,
+            vtable,
+        } = self;
+        data.chunks_mut(chunk_size * element_size)
+            .map(move |data| SliceCopyMut {
+                data,
+                element_size,
+                element_type_id,
+                element_type_name,
+                vtable,
+            })
+    }
+
+    /// Rotates the slice in-place such that the first `mid` elements of the slice move to the end
+    /// while the last `self.len() - mid` elements move to the front. After calling `rotate_left`,
+    /// the element previously at index `mid` will become the first element in the slice.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use data_buffer::*;
+    /// let mut buf = VecCopy::from_vec(vec![1u32,2,3,4,5]);
+    /// buf.rotate_left(3);
+    /// assert_eq!(buf.as_slice::<u32>().unwrap(), &[4,5,1,2,3]);
+    /// ```
+    #[inline]
+    pub fn rotate_left(&mut self, mid: usize) {
+        self.data.rotate_left(mid * self.element_size);
+    }
+
+    /// Rotates the slice in-place such that the first `self.len() - k` elements of the slice move
+    /// to the end while the last `k` elements move to the front. After calling `rotate_right`, the
+    /// element previously at index `k` will become the first element in the slice.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use data_buffer::*;
+    /// let mut buf = VecCopy::from_vec(vec![1u32,2,3,4,5]);
+    /// buf.rotate_right(3);
+    /// assert_eq!(buf.as_slice::<u32>().unwrap(), &[3,4,5,1,2]);
+    /// ```
+    #[inline]
+    pub fn rotate_right(&mut self, k: usize) {
+        self.data.rotate_right(k * self.element_size);
+    }
+
+    /// Swaps the elements at indices `i` and `j` in this buffer.
+    ///
+    /// The elements are swapped by their bytes, so the element type need not be known.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `i` or `j` are out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use data_buffer::*;
+    /// let mut buf = VecCopy::from_vec(vec![1u32,2,3,4,5]);
+    /// buf.swap(1, 3);
+    /// assert_eq!(buf.as_slice::<u32>().unwrap(), &[1,4,3,2,5]);
+    /// ```
     #[inline]
-    pub fn append(&mut self, buf: &mut VecCopy) -> Option<&mut Self> {
-        if buf.element_type_id() == self.element_type_id() {
-            self.data.append(&mut buf.data);
-            Some(self)
-        } else {
-            None
+    pub fn swap(&mut self, i: usize, j: usize) {
+        let len = self.len();
+        assert!(
+            i < len && j < len,
+            "swap indices {} and {} are out of bounds for length {}",
+            i,
+            j,
+            len
+        );
+        let (lo, hi) = if i < j { (i, j) } else { (j, i) };
+        if lo == hi {
+            return;
         }
+        let size = self.element_size;
+        let (left, right) = self.data.split_at_mut(hi * size);
+        left[lo * size..(lo + 1) * size].swap_with_slice(&mut right[..size]);
     }
 
-    /// Rotates the slice in-place such that the first `mid` elements of the slice move to the end
-    /// while the last `self.len() - mid` elements move to the front. After calling `rotate_left`,
-    /// the element previously at index `mid` will become the first element in the slice.
+    /// Reverses the order of elements in this buffer, in place.
+    ///
+    /// The elements are moved by their bytes, so the element type need not be known.
     ///
     /// # Example
     ///
     /// ```
     /// use data_buffer::*;
     /// let mut buf = VecCopy::from_vec(vec![1u32,2,3,4,5]);
-    /// buf.rotate_left(3);
-    /// assert_eq!(buf.as_slice::<u32>().unwrap(), &[4,5,1,2,3]);
+    /// buf.reverse();
+    /// assert_eq!(buf.as_slice::<u32>().unwrap(), &[5,4,3,2,1]);
     /// ```
     #[inline]
-    pub fn rotate_left(&mut self, mid: usize) {
-        self.data.rotate_left(mid * self.element_size);
+    pub fn reverse(&mut self) {
+        let size = self.element_size;
+        let len = self.len();
+        let half = len / 2;
+        let (front, back) = self.data.split_at_mut(half * size);
+        // The middle element of an odd length buffer stays in place.
+        let back = &mut back[(len % 2) * size..];
+        for (a, b) in front
+            .chunks_exact_mut(size)
+            .zip(back.rchunks_exact_mut(size))
+        {
+            a.swap_with_slice(b);
+        }
     }
 
-    /// Rotates the slice in-place such that the first `self.len() - k` elements of the slice move
-    /// to the end while the last `k` elements move to the front. After calling `rotate_right`, the
-    /// element previously at index `k` will become the first element in the slice.
+    /// Copies the elements in the range `src` to the position starting at the element index
+    /// `dest` in this buffer. The two ranges may overlap.
+    ///
+    /// This is the untyped equivalent of `slice::copy_within`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if either range exceeds the end of the buffer, or if the start of
+    /// `src` is greater than its end.
     ///
     /// # Example
     ///
     /// ```
     /// use data_buffer::*;
     /// let mut buf = VecCopy::from_vec(vec![1u32,2,3,4,5]);
-    /// buf.rotate_right(3);
-    /// assert_eq!(buf.as_slice::<u32>().unwrap(), &[3,4,5,1,2]);
+    /// buf.copy_within(1..4, 2);
+    /// assert_eq!(buf.as_slice::<u32>().unwrap(), &[1,2,2,3,4]);
     /// ```
     #[inline]
-    pub fn rotate_right(&mut self, k: usize) {
-        self.data.rotate_right(k * self.element_size);
+    pub fn copy_within<R: RangeBounds<usize>>(&mut self, src: R, dest: usize) {
+        let src = self.byte_range(src);
+        self.data.copy_within(src, dest * self.element_size);
     }
 
     /*
@@ -579,6 +2602,7 @@ impl VecCopy {
             ref mut data,
             element_size,
             element_type_id,
+            ..
         } = self;
         data.chunks_exact_mut(element_size)
             .map(move |bytes| unsafe { CopyValueMut::from_raw_parts(bytes, element_type_id) })
@@ -625,35 +2649,70 @@ impl VecCopy {
                 .map(|elem: I| cast(elem).unwrap_or(O::zero()))
                 .collect()
         }
+        trace::trace_span!(
+            "cast_into_vec",
+            num_bytes = self.data.len(),
+            from = self.element_type_name(),
+            to = std::any::type_name::<T>()
+        );
         call_numeric_buffer_fn!( convert_into_vec::<_,T>(self) or { Vec::new() } )
     }
+}
 
-    #[cfg(feature = "numeric")]
-    /// Display the contents of this buffer reinterpreted in the given type.
-    unsafe fn reinterpret_display<T: Elem + fmt::Display>(&self, f: &mut fmt::Formatter) {
-        debug_assert_eq!(self.element_type_id(), TypeId::of::<T>()); // Check invariant.
-        for item in self.reinterpret_iter::<T>() {
-            write!(f, "{} ", item).expect("Error occurred while writing an VecCopy.");
-        }
+/// Collect typed elements into a `VecCopy`.
+///
+/// # Examples
+/// ```
+/// use data_buffer::VecCopy;
+/// let buf: VecCopy = (1..4u8).collect();
+/// assert_eq!(buf.as_slice::<u8>().unwrap(), &[1, 2, 3]);
+/// ```
+impl<T: Elem> std::iter::FromIterator<T> for VecCopy {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let iter = iter.into_iter();
+        let mut buf = VecCopy::with_capacity::<T>(iter.size_hint().0);
+        // This is safe since the buffer was created with elements of type `T`.
+        unsafe { buf.extend_unchecked(iter) };
+        buf
     }
 }
 
-impl<'a> std::iter::FromIterator<CopyValueRef<'a>> for VecCopy {
+impl VecCopy {
+    /// Construct a buffer from an iterator over untyped value references.
+    ///
+    /// Since `CopyValueRef` is `Copy`, it is itself a valid element type, so buffers of values
+    /// are collected with this function rather than `FromIterator`. Returns `None` if the
+    /// iterator is empty, since the element type is then unknown.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the values don't all have the same type.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let buf = VecCopy::from_vec(vec![1u32, 2, 3]);
+    /// let copy = VecCopy::from_value_iter(buf.iter_value_ref()).unwrap();
+    /// assert_eq!(copy.as_slice::<u32>().unwrap(), &[1, 2, 3]);
+    /// ```
     #[inline]
-    fn from_iter<T: IntoIterator<Item = CopyValueRef<'a>>>(iter: T) -> Self {
+    pub fn from_value_iter<'a>(iter: impl IntoIterator<Item = CopyValueRef<'a>>) -> Option<Self> {
         let mut iter = iter.into_iter();
-        let next = iter
-            .next()
-            .expect("VecCopy cannot be built from an empty untyped iterator.");
-        let mut data = Vec::with_capacity(next.size() * iter.size_hint().0);
+        let next = iter.next()?;
+        // The element type is unknown, so we allocate with the largest possible alignment for it.
+        let align = aligned_vec::max_align_of_size(next.size());
+        let mut data = AlignedVec::with_capacity(next.size() * (iter.size_hint().0 + 1), align);
         data.extend_from_slice(next.bytes);
         let mut buf = VecCopy {
             data,
             element_size: next.size(),
             element_type_id: next.value_type_id(),
+            element_type_name: TypeName::default(),
+            vtable: CopyVTable::of_type_id(next.value_type_id()),
         };
-        buf.extend(iter);
-        buf
+        Extend::extend(&mut buf, iter);
+        Some(buf)
     }
 }
 
@@ -668,17 +2727,77 @@ impl<'a> Extend<CopyValueRef<'a>> for VecCopy {
     }
 }
 
+/// A mutable view into a `VecCopy` with a statically known element type.
+///
+/// This view is created by the `typed_mut` method on `VecCopy`. It allows the buffer to be used
+/// with APIs that require a statically typed container, such as the `Extend` trait.
+#[derive(Debug)]
+pub struct TypedVecCopyMut<'a, T> {
+    buf: &'a mut VecCopy,
+    phantom: PhantomData<T>,
+}
+
+impl<'a, T: Elem> TypedVecCopyMut<'a, T> {
+    /// Get the underlying typed slice.
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        // This is safe since the type was checked when this view was created.
+        unsafe { self.buf.reinterpret_as_slice() }
+    }
+
+    /// Get the underlying typed mutable slice.
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        // This is safe since the type was checked when this view was created.
+        unsafe { self.buf.reinterpret_as_mut_slice() }
+    }
+
+    /// Add an element to the underlying buffer.
+    #[inline]
+    pub fn push(&mut self, element: T) {
+        self.buf.data.push_typed(element);
+    }
+
+    /// Release the typed view, returning the underlying untyped buffer.
+    #[inline]
+    pub fn into_inner(self) -> &'a mut VecCopy {
+        self.buf
+    }
+}
+
+impl<'a, T: Elem> Extend<T> for TypedVecCopyMut<'a, T> {
+    #[inline]
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        // This is safe since the type was checked when the view was created.
+        unsafe { self.buf.extend_unchecked(iter) };
+    }
+}
+
+impl<'a, 'b, T: Elem> Extend<&'b T> for TypedVecCopyMut<'a, T> {
+    #[inline]
+    fn extend<I: IntoIterator<Item = &'b T>>(&mut self, iter: I) {
+        // This is safe since the type was checked when the view was created.
+        unsafe { self.buf.extend_unchecked(iter.into_iter().cloned()) };
+    }
+}
+
 /*
  * Advanced methods to probe buffer internals.
  */
 
 impl VecCopy {
     /// Clones this `VecCopy` using the given function.
-    pub(crate) fn clone_with(&self, clone: impl FnOnce(&[u8]) -> Vec<u8>) -> Self {
+    ///
+    /// The given function receives a bitwise copy of the data, which it may modify in place.
+    pub(crate) fn clone_with(&self, clone: impl FnOnce(&mut [u8])) -> Self {
+        let mut data = self.data.clone();
+        clone(&mut data);
         VecCopy {
-            data: clone(&self.data),
+            data,
             element_size: self.element_size,
             element_type_id: self.element_type_id,
+            element_type_name: self.element_type_name,
+            vtable: self.vtable,
         }
     }
 
@@ -688,6 +2807,107 @@ impl VecCopy {
         self.data.reserve(additional);
     }
 
+    /// Tries to reserve capacity for at least `additional` more bytes to be inserted in this
+    /// buffer, returning an error instead of aborting if the allocation fails.
+    #[inline]
+    pub fn try_reserve_bytes(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.data.try_reserve(additional)
+    }
+
+    /// Tries to reserve capacity for at least `additional` more elements to be inserted in this
+    /// buffer, returning an error instead of aborting if the allocation fails.
+    #[inline]
+    pub fn try_reserve_elements(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let additional_bytes = additional
+            .checked_mul(self.element_size)
+            .ok_or(TryReserveError::CapacityOverflow)?;
+        self.data.try_reserve(additional_bytes)
+    }
+
+    /// Borrow the spare capacity of this buffer as a slice of possibly uninitialized bytes.
+    ///
+    /// Bytes written here become part of the buffer after a call to `set_len_elements`. This
+    /// allows elements to be decoded directly into the buffer without an intermediate copy.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let mut buf = VecCopy::with_capacity::<u16>(2);
+    /// let bytes = [1u16.to_ne_bytes(), 2u16.to_ne_bytes()].concat();
+    /// for (dst, &src) in buf.spare_capacity_mut().iter_mut().zip(&bytes) {
+    ///     dst.write(src);
+    /// }
+    /// unsafe { buf.set_len_elements(2) };
+    /// assert_eq!(buf.as_slice::<u16>().unwrap(), &[1, 2]);
+    /// ```
+    #[inline]
+    pub fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<u8>] {
+        self.data.spare_capacity_mut()
+    }
+
+    /// Set the number of elements in this buffer.
+    ///
+    /// # Safety
+    ///
+    /// - `new_len` must not exceed `capacity()`.
+    /// - The first `new_len * element_size()` bytes of the buffer must be initialized, for
+    ///   instance through `spare_capacity_mut`, and form valid representations of the element
+    ///   type stored in this buffer.
+    #[inline]
+    pub unsafe fn set_len_elements(&mut self, new_len: usize) {
+        debug_assert!(new_len <= self.capacity());
+        self.data.set_len(new_len * self.element_size);
+    }
+
+    /// Add an element to this buffer without checking its type or the capacity of the buffer.
+    ///
+    /// This is useful in hot loops where the type was checked once up front, and space was
+    /// reserved for all pushed elements, for instance with `reserve`.
+    ///
+    /// # Safety
+    ///
+    /// - `T` must be the element type stored in this buffer.
+    /// - The buffer must have spare capacity for at least one more element.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let mut buf = VecCopy::with_type::<u32>();
+    /// buf.reserve(3);
+    /// for i in 0..3u32 {
+    ///     unsafe { buf.push_unchecked(i) };
+    /// }
+    /// assert_eq!(buf.as_slice::<u32>().unwrap(), &[0, 1, 2]);
+    /// ```
+    #[inline]
+    pub unsafe fn push_unchecked<T: Elem>(&mut self, element: T) {
+        debug_assert_eq!(self.element_type_id(), TypeId::of::<T>()); // Check invariant.
+        debug_assert!(self.len() < self.capacity());
+        let len = self.data.len();
+        let dst = self.data.spare_capacity_mut().as_mut_ptr() as *mut T;
+        dst.write(element);
+        self.data.set_len(len + size_of::<T>());
+    }
+
+    /// Add all elements produced by the given iterator to this buffer without checking their
+    /// type.
+    ///
+    /// Space is reserved once, based on the lower bound of the iterator's size hint, and the
+    /// capacity is checked for every element beyond that.
+    ///
+    /// # Safety
+    ///
+    /// `T` must be the element type stored in this buffer.
+    #[inline]
+    pub unsafe fn extend_unchecked<T: Elem, I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        debug_assert_eq!(self.element_type_id(), TypeId::of::<T>()); // Check invariant.
+        let iter = iter.into_iter();
+        self.data.reserve(iter.size_hint().0 * self.element_size);
+        for element in iter {
+            self.data.push_typed(element);
+        }
+    }
+
     /// Get `i`'th element of the buffer by value without checking type.
     ///
     /// This can be used to reinterpret the internal data as a different type. Note that if the
@@ -742,6 +2962,21 @@ impl VecCopy {
         &self.data[i * element_size..(i + 1) * element_size]
     }
 
+    /// Get a `const` reference to the byte slice of the first element of the buffer, or `None`
+    /// if the buffer is empty.
+    #[inline]
+    pub fn first_bytes(&self) -> Option<&[u8]> {
+        self.data.get(..self.element_size)
+    }
+
+    /// Get a `const` reference to the byte slice of the last element of the buffer, or `None`
+    /// if the buffer is empty.
+    #[inline]
+    pub fn last_bytes(&self) -> Option<&[u8]> {
+        let len = self.data.len();
+        self.data.get(len.checked_sub(self.element_size)?..)
+    }
+
     /// Get a mutable reference to the byte slice of the `i`'th element of the buffer.
     ///
     /// # Safety
@@ -755,15 +2990,100 @@ impl VecCopy {
         &mut self.data[i * element_size..(i + 1) * element_size]
     }
 
+    /// Check that the data in this buffer is sufficiently aligned to be reinterpreted as `T`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the data is not aligned to the alignment of `T`.
+    #[inline]
+    fn assert_aligned<T>(&self) {
+        assert_eq!(
+            self.data.as_ptr() as usize % align_of::<T>(),
+            0,
+            "buffer data is not sufficiently aligned to be reinterpreted as `{}`",
+            std::any::type_name::<T>()
+        );
+    }
+
+    /// Decompose this buffer into its raw components.
+    ///
+    /// Returns a pointer to the allocated data, the length and capacity of the allocation in
+    /// bytes, the element size, the alignment of the allocation and the `TypeId` of the element
+    /// type. The allocation is made by the global allocator with the returned capacity and
+    /// alignment. Ownership of the allocation is transferred to the caller, who is responsible for
+    /// deallocating it, for instance by rebuilding the buffer with `from_raw_parts`.
+    ///
+    /// Functions captured with `with_debug` etc. are discarded, and the rebuilt buffer compares
+    /// its elements by their bytes.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let buf = VecCopy::from_vec(vec![1u32, 2, 3]);
+    /// let (ptr, len_bytes, cap_bytes, element_size, align, type_id) = buf.into_raw_parts();
+    /// assert_eq!((len_bytes, element_size), (12, 4));
+    /// let buf = unsafe {
+    ///     VecCopy::from_raw_parts(ptr, len_bytes, cap_bytes, element_size, align, type_id)
+    /// };
+    /// assert_eq!(buf.as_slice::<u32>().unwrap(), &[1, 2, 3]);
+    /// ```
+    pub fn into_raw_parts(self) -> (*mut u8, usize, usize, usize, usize, TypeId) {
+        let (ptr, len, capacity, align) = self.data.into_raw_parts();
+        (
+            ptr,
+            len,
+            capacity,
+            self.element_size,
+            align,
+            self.element_type_id,
+        )
+    }
+
+    /// Construct a buffer from its raw components as returned by `into_raw_parts`.
+    ///
+    /// Since the element type is known only by its `TypeId`, its name is unknown and only the
+    /// functions captured when it was registered with `register_type` are available.
+    ///
+    /// # Safety
+    ///
+    /// - Unless `cap_bytes` is zero, `ptr` must have been allocated by the global allocator with
+    ///   a size of `cap_bytes` bytes and an alignment of `align`, which is a power of two.
+    /// - `element_size` must be the size of the `Elem` type identified by `element_type_id`, and
+    ///   `align` must be at least its alignment.
+    /// - `len_bytes` must be a multiple of `element_size` no greater than `cap_bytes`, and the
+    ///   first `len_bytes` bytes must be valid elements of that type.
+    ///
+    /// Ownership of the allocation is transferred to the returned buffer.
+    pub unsafe fn from_raw_parts(
+        ptr: *mut u8,
+        len_bytes: usize,
+        cap_bytes: usize,
+        element_size: usize,
+        align: usize,
+        element_type_id: TypeId,
+    ) -> Self {
+        debug_assert_ne!(element_size, 0);
+        debug_assert_eq!(len_bytes % element_size, 0);
+        VecCopy {
+            data: AlignedVec::from_raw_parts(ptr, len_bytes, cap_bytes, align),
+            element_size,
+            element_type_id,
+            element_type_name: TypeName(None),
+            vtable: CopyVTable::of_type_id(element_type_id),
+        }
+    }
+
     /// Move buffer data to a vector with a given type, reinterpreting the data type as
     /// required.
     ///
+    /// The allocation is reused if it is compatible with `Vec<T>`, otherwise the data is copied.
+    ///
     /// # Safety
     ///
     /// The underlying data must be correctly represented by a `Vec<T>`.
     #[inline]
     pub unsafe fn reinterpret_into_vec<T>(self) -> Vec<T> {
-        reinterpret::reinterpret_vec(self.data)
+        self.data.into_vec()
     }
 
     /// Borrow buffer data and reinterpret it as a slice of a given type.
@@ -771,8 +3091,13 @@ impl VecCopy {
     /// # Safety
     ///
     /// The underlying data must be correctly represented by a `&[T]` when borrowed as`&[u8]`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the data is not aligned to the alignment of `T`.
     #[inline]
     pub unsafe fn reinterpret_as_slice<T>(&self) -> &[T] {
+        self.assert_aligned::<T>();
         reinterpret::reinterpret_slice(self.data.as_slice())
     }
 
@@ -782,8 +3107,13 @@ impl VecCopy {
     ///
     /// The underlying data must be correctly represented by a `&mut [T]` when borrowed as`&mut
     /// [u8]`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the data is not aligned to the alignment of `T`.
     #[inline]
     pub unsafe fn reinterpret_as_mut_slice<T>(&mut self) -> &mut [T] {
+        self.assert_aligned::<T>();
         reinterpret::reinterpret_mut_slice(self.data.as_mut_slice())
     }
 
@@ -835,6 +3165,30 @@ impl VecCopy {
         self.data.chunks_exact(chunk_size)
     }
 
+    /// Iterate over pairs of corresponding element bytes in this buffer and `other` without
+    /// interpreting them.
+    ///
+    /// The two buffers may store different types. Returns `None` if their lengths differ.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let ids = VecCopy::from_vec(vec![1u32, 2]);
+    /// let flags = VecCopy::from_vec(vec![true, false]);
+    /// let pairs: Vec<_> = ids.zip_byte_chunks(&flags).unwrap().collect();
+    /// assert_eq!(pairs[1], (&2u32.to_ne_bytes()[..], &[0u8][..]));
+    /// ```
+    #[inline]
+    pub fn zip_byte_chunks<'a>(
+        &'a self,
+        other: &'a VecCopy,
+    ) -> Option<impl Iterator<Item = (&'a [u8], &'a [u8])> + 'a> {
+        if self.len() != other.len() {
+            return None;
+        }
+        Some(self.byte_chunks().zip(other.byte_chunks()))
+    }
+
     /// Mutably iterate over chunks type sized chunks of bytes without interpreting them. This
     /// avoids needing to know what type data you're dealing with. This type of iterator is useful
     /// for transferring data from one place to another for a generic buffer, or modifying the
@@ -871,6 +3225,61 @@ impl VecCopy {
         }
     }
 
+    /// Insert bytes representing a single element at position `i`, shifting all elements after
+    /// it to the right.
+    ///
+    /// If the size of the given slice coincides with the number of bytes occupied by the
+    /// underlying element type, then these bytes are inserted into the underlying data buffer and
+    /// a mutable reference to the buffer is returned.
+    /// Otherwise, `None` is returned, and the buffer remains unmodified.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `i > len`.
+    ///
+    /// # Safety
+    ///
+    /// It is assumed that that the given `bytes` slice is a valid representation of the element
+    /// types stored in this buffer. Otherwise this function will cause undefined behavior.
+    #[inline]
+    pub unsafe fn insert_bytes(&mut self, i: usize, bytes: &[u8]) -> Option<&mut Self> {
+        assert!(i <= self.len());
+        let element_size = self.element_size();
+        if bytes.len() == element_size {
+            let offset = i * element_size;
+            self.data.insert_slice(offset, bytes);
+            Some(self)
+        } else {
+            None
+        }
+    }
+
+    /// Fill this buffer with copies of the given bytes representing a single element.
+    ///
+    /// This is the untyped equivalent of `fill`. If the size of the given slice coincides with
+    /// the number of bytes occupied by the underlying element type, then every element is
+    /// overwritten with these bytes and a mutable reference to the buffer is returned.
+    /// Otherwise, `None` is returned, and the buffer remains unmodified.
+    ///
+    /// # Safety
+    ///
+    /// It is assumed that that the given `bytes` slice is a valid representation of the element
+    /// types stored in this buffer. Otherwise this function will cause undefined behavior.
+    #[inline]
+    pub unsafe fn fill_bytes(&mut self, bytes: &[u8]) -> Option<&mut Self> {
+        if bytes.len() != self.element_size() {
+            return None;
+        }
+        if bytes.iter().all(|&b| b == bytes[0]) {
+            self.data.fill(bytes[0]);
+        } else {
+            for chunk in self.data.chunks_exact_mut(bytes.len()) {
+                chunk.copy_from_slice(bytes);
+            }
+        }
+        Some(self)
+    }
+
     /// Add bytes to this buffer.
     ///
     /// If the size of the given slice is a multiple of the number of bytes occupied by the
@@ -910,12 +3319,73 @@ impl VecCopy {
     pub unsafe fn append_bytes(&mut self, bytes: &mut Vec<u8>) -> Option<&mut Self> {
         let element_size = self.element_size();
         if bytes.len() % element_size == 0 {
-            self.data.append(bytes);
+            self.data.extend_from_slice(bytes);
+            bytes.clear();
             Some(self)
         } else {
             None
         }
     }
+
+    /// Read `count` elements from the given reader and append them to this buffer.
+    ///
+    /// The bytes are read directly into the buffer in chunks, so a `count` from an untrusted
+    /// source doesn't cause a large allocation up front. If the reader runs out of data, an error
+    /// of kind `UnexpectedEof` is returned and the buffer is left unmodified.
+    ///
+    /// # Safety
+    ///
+    /// It is assumed that the bytes produced by the reader are a valid representation of a
+    /// contiguous collection of elements with the same type as stored in this buffer. Otherwise
+    /// this function will cause undefined behavior.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let mut buf = VecCopy::from_vec(vec![1u16]);
+    /// let bytes = [2u16.to_ne_bytes(), 3u16.to_ne_bytes()].concat();
+    /// unsafe { buf.read_elements_from(&mut bytes.as_slice(), 2).unwrap() };
+    /// assert_eq!(buf.as_slice::<u16>().unwrap(), &[1, 2, 3]);
+    /// ```
+    pub unsafe fn read_elements_from<R: io::Read>(
+        &mut self,
+        reader: &mut R,
+        count: usize,
+    ) -> io::Result<&mut Self> {
+        /// The maximum number of bytes reserved at a time.
+        const CHUNK_SIZE: usize = 64 * 1024;
+        let num_bytes = count.checked_mul(self.element_size).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                TryReserveError::CapacityOverflow,
+            )
+        })?;
+        let start = self.data.len();
+        let mut remaining = num_bytes;
+        while remaining > 0 {
+            let chunk_size = remaining.min(CHUNK_SIZE);
+            let offset = self.data.len();
+            self.data.resize(offset + chunk_size, 0);
+            if let Err(err) = reader.read_exact(&mut self.data[offset..]) {
+                self.data.truncate(start);
+                return Err(err);
+            }
+            remaining -= chunk_size;
+        }
+        Ok(self)
+    }
+
+    /// Write the bytes of all elements in this buffer to the given writer.
+    ///
+    /// The bytes are written in chunks directly from the buffer without intermediate copies.
+    pub fn write_bytes_to<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        /// The maximum number of bytes written at a time.
+        const CHUNK_SIZE: usize = 64 * 1024;
+        for chunk in self.data.chunks(CHUNK_SIZE) {
+            writer.write_all(chunk)?;
+        }
+        Ok(())
+    }
 }
 
 /// Convert a `Vec<T>` to a `VecCopy`.
@@ -935,12 +3405,74 @@ where
     T: Elem,
 {
     #[inline]
-    fn from(slice: &'a [T]) -> VecCopy {
-        VecCopy::from_slice(slice)
+    fn from(slice: &'a [T]) -> VecCopy {
+        VecCopy::from_slice(slice)
+    }
+}
+
+/// Convert a `Box<[T]>` to a `VecCopy` reusing its allocation.
+impl<T> From<Box<[T]>> for VecCopy
+where
+    T: Elem,
+{
+    #[inline]
+    fn from(boxed: Box<[T]>) -> VecCopy {
+        VecCopy::from_vec(boxed.into_vec())
+    }
+}
+
+/// Buffers are equal if they store the same type and their elements are equal.
+///
+/// Elements are compared with their own `PartialEq` implementation, which is captured at
+/// construction. Buffers whose element type is only known at runtime, like buffers rebuilt from
+/// raw parts, compare their elements by their bytes.
+impl PartialEq for VecCopy {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice_copy() == other.as_slice_copy()
+    }
+}
+
+/// Buffer equality is an equivalence relation as long as the equality of the elements is. In
+/// particular, a buffer of floats containing NaN is not equal to itself, so such buffers should
+/// not be used as keys in a `HashMap` or `HashSet`.
+impl Eq for VecCopy {}
+
+/// Prints the element type and at most 32 elements of the buffer.
+///
+/// Elements are printed with their own `Debug` implementation if it was captured (see
+/// `with_debug`), and as arrays of bytes otherwise.
+impl fmt::Debug for VecCopy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.as_slice_copy().debug_struct("VecCopy", f)
+    }
+}
+
+/// Buffers are hashed by their element type and elements.
+///
+/// Elements are hashed with their own `Hash` implementation if it was captured (see
+/// `with_hash`). Otherwise floats are hashed by their values and other elements by their bytes,
+/// unless their equality differs from byte equality, in which case only the length is hashed.
+impl Hash for VecCopy {
+    #[inline]
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.as_slice_copy().hash(state)
+    }
+}
+
+/// Buffers storing different types are ordered by their `TypeId`, otherwise buffers are compared
+/// lexicographically.
+///
+/// Elements are compared with their own ordering if it was captured for either buffer (see
+/// `with_ord` and `with_partial_ord`). Otherwise unequal elements are ordered by their bytes, or
+/// are incomparable if their bytes are equal.
+impl PartialOrd for VecCopy {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.as_slice_copy().partial_cmp(&other.as_slice_copy())
     }
 }
 
-/// Convert a `VecCopy` to a `Option<Vec<T>>`.
 impl<T> Into<Option<Vec<T>>> for VecCopy
 where
     T: Elem,
@@ -951,17 +3483,6 @@ where
     }
 }
 
-#[cfg(feature = "numeric")]
-/// Implement pretty printing of numeric `VecCopy` data.
-impl fmt::Display for VecCopy {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        call_numeric_buffer_fn!( self.reinterpret_display::<_>(f) or {
-            println!("Unknown VecCopy type for pretty printing.");
-        } );
-        write!(f, "")
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1002,6 +3523,102 @@ mod tests {
         assert!(a.byte_capacity() >= 10);
     }
 
+    /// Test element based capacity management.
+    #[test]
+    fn capacity() {
+        let mut a = VecCopy::with_type::<u64>();
+        assert_eq!(a.capacity(), 0);
+        a.reserve_exact(3);
+        assert_eq!(a.capacity(), 3);
+        a.reserve(3);
+        assert!(a.capacity() >= 3);
+        a.extend(0..5u64);
+        a.reserve(10);
+        assert!(a.capacity() >= 15);
+        assert_eq!(a.byte_capacity(), a.capacity() * 8);
+
+        a.shrink_to(8);
+        assert_eq!(a.capacity(), 8);
+        a.shrink_to(20);
+        assert_eq!(a.capacity(), 8);
+        a.shrink_to_fit();
+        assert_eq!(a.capacity(), 5);
+        assert_eq!(a.as_slice::<u64>().unwrap(), &[0, 1, 2, 3, 4]);
+
+        a.clear();
+        a.shrink_to_fit();
+        assert_eq!(a.capacity(), 0);
+        a.push(7u64);
+        assert_eq!(a.as_slice::<u64>().unwrap(), &[7]);
+    }
+
+    /// Test conversions to and from boxed slices.
+    #[test]
+    fn boxed_slice() {
+        let boxed: Box<[f64]> = vec![1.0, 2.0].into_boxed_slice();
+        let ptr = boxed.as_ptr();
+        let buf = VecCopy::from(boxed);
+        assert_eq!(buf.as_bytes().as_ptr() as *const f64, ptr);
+        assert_eq!(buf.as_slice::<f64>().unwrap(), &[1.0, 2.0]);
+        assert!(buf.clone().into_boxed_slice::<f32>().is_none());
+
+        // The allocation is reused if it has no excess capacity.
+        let ptr = buf.as_bytes().as_ptr();
+        let boxed = buf.clone().into_boxed_slice::<f64>().unwrap();
+        assert_eq!(&*boxed, &[1.0, 2.0]);
+        let boxed = buf.into_boxed_slice::<f64>().unwrap();
+        assert_eq!(boxed.as_ptr() as *const u8, ptr);
+
+        let mut buf = VecCopy::from(boxed);
+        buf.reserve(100);
+        buf.push(3.0f64);
+        let boxed = buf.into_boxed_slice::<f64>().unwrap();
+        assert_eq!(&*boxed, &[1.0, 2.0, 3.0]);
+    }
+
+    /// Test taking a buffer apart and putting it back together.
+    #[test]
+    fn raw_parts() {
+        let buf = VecCopy::with_type_aligned::<u16>(32);
+        let (ptr, len, cap, size, align, type_id) = buf.into_raw_parts();
+        assert_eq!((len, cap, size, align), (0, 0, 2, 32));
+        let mut buf = unsafe { VecCopy::from_raw_parts(ptr, len, cap, size, align, type_id) };
+        assert_eq!(buf.alignment(), 32);
+
+        buf.extend(0..10u16).unwrap();
+        let (ptr, len, cap, size, align, type_id) = buf.into_raw_parts();
+        assert_eq!(ptr as usize % 32, 0);
+        assert_eq!((len, size, type_id), (20, 2, TypeId::of::<u16>()));
+        let buf = unsafe { VecCopy::from_raw_parts(ptr, len, cap, size, align, type_id) };
+        assert_eq!(buf.byte_capacity(), cap);
+        assert_eq!(buf.into_vec::<u16>().unwrap(), (0..10).collect::<Vec<_>>());
+    }
+
+    /// Test fallible reservation.
+    #[test]
+    fn try_reserve() {
+        let mut a = VecCopy::try_with_capacity::<f32>(4).unwrap();
+        assert_eq!(a.byte_capacity(), 4 * size_of::<f32>());
+        a.try_reserve_elements(8).unwrap();
+        assert!(a.byte_capacity() >= 8 * size_of::<f32>());
+        a.try_reserve_bytes(64).unwrap();
+        assert!(a.byte_capacity() >= 64);
+
+        // Bogus sizes are reported instead of aborting.
+        assert_eq!(
+            a.try_reserve_elements(usize::MAX / 2),
+            Err(TryReserveError::CapacityOverflow)
+        );
+        assert!(a.try_reserve_bytes(usize::MAX).is_err());
+        assert!(matches!(
+            a.try_reserve_bytes(isize::MAX as usize / 2),
+            Err(TryReserveError::AllocError { .. })
+        ));
+        assert!(VecCopy::try_with_capacity::<u64>(usize::MAX / 4).is_err());
+        assert_eq!(a.len(), 0);
+        assert!(a.byte_capacity() >= 64);
+    }
+
     /// Test resizing a buffer.
     #[test]
     fn resize() {
@@ -1026,6 +3643,23 @@ mod tests {
         }
     }
 
+    /// Test truncating a buffer without specifying its type.
+    #[test]
+    fn truncate() {
+        let mut a = VecCopy::from_vec(vec![1u32, 2, 3, 4]);
+        let capacity = a.byte_capacity();
+
+        a.truncate(5);
+        assert_eq!(a.len(), 4);
+
+        a.truncate(2);
+        assert_eq!(a.as_slice::<u32>().unwrap(), &[1, 2]);
+        assert_eq!(a.byte_capacity(), capacity);
+
+        a.truncate(0);
+        assert!(a.is_empty());
+    }
+
     #[test]
     #[should_panic]
     fn zero_size_with_type_test() {
@@ -1264,6 +3898,36 @@ mod tests {
         assert!(buf.get_mut::<i32>(2).is_none());
     }
 
+    /// Test assigning individual elements.
+    #[test]
+    fn set_test() {
+        let mut buf = VecCopy::from_vec(vec![[1u8, 2], [3, 4]]);
+        assert!(buf.set(0, 5u16).is_none());
+        assert!(buf.set(2, [5u8, 6]).is_none());
+        buf.set(1, [5u8, 6]).unwrap().set(0, [7u8, 8]).unwrap();
+        assert_eq!(buf.as_slice::<[u8; 2]>().unwrap(), &[[7, 8], [5, 6]]);
+    }
+
+    /// Test accessing the ends of a buffer.
+    #[test]
+    fn first_last_test() {
+        let mut buf = VecCopy::with_type::<[u16; 2]>();
+        assert_eq!(buf.first::<[u16; 2]>(), None);
+        assert_eq!(buf.last::<[u16; 2]>(), None);
+        assert_eq!(buf.first_bytes(), None);
+        assert_eq!(buf.last_bytes(), None);
+
+        buf.push([1u16, 2]).unwrap();
+        assert_eq!(buf.first::<[u16; 2]>(), Some(&[1, 2]));
+        assert_eq!(buf.first_bytes(), buf.last_bytes());
+
+        buf.push([3u16, 4]).unwrap();
+        assert_eq!(buf.first::<u32>(), None);
+        assert_eq!(buf.last::<[u16; 2]>(), Some(&[3, 4]));
+        assert_eq!(buf.first_bytes(), Some(buf.get_bytes(0)));
+        assert_eq!(buf.last_bytes(), Some(buf.get_bytes(1)));
+    }
+
     /// Test iterating over chunks of data without having to interpret them.
     #[test]
     fn byte_chunks_test() {
@@ -1278,6 +3942,111 @@ mod tests {
         }
     }
 
+    /// Test iterating over two buffers in lockstep.
+    #[test]
+    fn zip_test() {
+        let a = VecCopy::from_vec(vec![1u16, 2, 3]);
+        let b = VecCopy::from_vec(vec![[4u8; 3], [5; 3], [6; 3]]);
+        let c = VecCopy::from_vec(vec![1u16, 2]);
+        assert!(a.zip_iter::<u16, u16>(&b).is_none());
+        assert!(a.zip_iter::<u16, u16>(&c).is_none());
+        assert!(a.zip_byte_chunks(&c).is_none());
+
+        let pairs: Vec<_> = a.zip_iter::<u16, [u8; 3]>(&b).unwrap().collect();
+        assert_eq!(pairs, vec![(&1, &[4; 3]), (&2, &[5; 3]), (&3, &[6; 3])]);
+
+        let mut num_pairs = 0;
+        for (x, y) in a.zip_byte_chunks(&b).unwrap() {
+            assert_eq!((x.len(), y.len()), (2, 3));
+            num_pairs += 1;
+        }
+        assert_eq!(num_pairs, 3);
+    }
+
+    /// Test iterating over chunks of typed elements.
+    #[test]
+    fn chunks_test() {
+        let mut buf = VecCopy::from_vec((0..8u16).collect::<Vec<_>>());
+        assert!(buf.chunks::<u32>(2).is_none());
+        assert!(buf.chunks_exact_mut::<i16>(2).is_none());
+
+        let chunks: Vec<_> = buf.chunks::<u16>(3).unwrap().collect();
+        assert_eq!(chunks, vec![&[0, 1, 2][..], &[3, 4, 5], &[6, 7]]);
+        let chunks = buf.chunks_exact::<u16>(3).unwrap();
+        assert_eq!(chunks.remainder(), &[6, 7]);
+        assert_eq!(chunks.len(), 2);
+
+        for chunk in buf.chunks_mut::<u16>(5).unwrap() {
+            chunk[0] = 10;
+        }
+        for chunk in buf.chunks_exact_mut::<u16>(4).unwrap() {
+            chunk.reverse();
+        }
+        assert_eq!(buf.as_slice::<u16>().unwrap(), &[3, 2, 1, 10, 7, 6, 10, 4]);
+    }
+
+    /// Test iterating over sliding windows of typed elements.
+    #[test]
+    fn windows_test() {
+        let buf = VecCopy::from_vec(vec![1i64, 2, 4, 8]);
+        assert!(buf.windows::<u64>(2).is_none());
+        let sums: Vec<i64> = buf
+            .windows::<i64>(3)
+            .unwrap()
+            .map(|w| w.iter().sum())
+            .collect();
+        assert_eq!(sums, vec![7, 14]);
+        assert_eq!(buf.windows::<i64>(5).unwrap().count(), 0);
+    }
+
+    /// Test viewing a flat buffer as a slice of arrays.
+    #[test]
+    fn as_arrays_test() {
+        let mut buf = VecCopy::from_vec(vec![1u8, 2, 3, 4, 5, 6]);
+        assert!(buf.as_arrays::<i8, 2>().is_none());
+        assert!(buf.as_arrays::<u8, 0>().is_none());
+        assert!(buf.as_arrays_mut::<u8, 4>().is_none());
+        assert_eq!(
+            buf.as_arrays::<u8, 1>().unwrap(),
+            &[[1], [2], [3], [4], [5], [6]]
+        );
+        assert_eq!(buf.as_arrays::<u8, 6>().unwrap(), &[[1, 2, 3, 4, 5, 6]]);
+
+        for pair in buf.as_arrays_mut::<u8, 2>().unwrap() {
+            pair.swap(0, 1);
+        }
+        assert_eq!(buf.as_slice::<u8>().unwrap(), &[2, 1, 4, 3, 6, 5]);
+        assert!(VecCopy::with_type::<u8>()
+            .as_arrays::<u8, 3>()
+            .unwrap()
+            .is_empty());
+    }
+
+    /// Test flattening a buffer of arrays.
+    #[test]
+    fn flatten_arrays_test() {
+        let buf = VecCopy::from_vec(vec![[1i16, 2, 3], [4, 5, 6]]);
+        assert!(buf.as_flattened::<i16, 2>().is_none());
+        assert!(buf.as_flattened::<u16, 3>().is_none());
+        assert_eq!(buf.as_flattened::<i16, 3>().unwrap(), &[1, 2, 3, 4, 5, 6]);
+
+        let flat = buf.clone().flatten_arrays::<i16, 3>().unwrap();
+        assert_eq!(flat.len(), 6);
+        assert_eq!(flat.element_size(), 2);
+        assert_eq!(flat.element_type_id(), TypeId::of::<i16>());
+        assert_eq!(flat, VecCopy::from_vec(vec![1i16, 2, 3, 4, 5, 6]));
+        assert_eq!(flat.as_arrays::<i16, 3>(), buf.as_slice::<[i16; 3]>());
+
+        assert!(buf.flatten_arrays::<i16, 1>().is_none());
+    }
+
+    #[test]
+    #[should_panic]
+    fn chunks_zero_size_test() {
+        let buf = VecCopy::from_vec(vec![1u8, 2]);
+        let _ = buf.chunks::<u8>(0);
+    }
+
     /// Test pushing values and bytes to a buffer.
     #[test]
     fn push_test() {
@@ -1324,6 +4093,842 @@ mod tests {
         }
     }
 
+    /// Test filling a buffer with the bytes of a single element.
+    #[test]
+    fn fill_bytes_test() {
+        let mut buf = VecCopy::from_vec(vec![1.0f32, 2.0, 3.0]);
+        unsafe {
+            assert!(buf.fill_bytes(&[0; 8]).is_none());
+            buf.fill_bytes(&0.5f32.to_ne_bytes()).unwrap();
+            assert_eq!(buf.as_slice::<f32>().unwrap(), &[0.5; 3]);
+            buf.fill_bytes(&[0xff; 4]).unwrap();
+        }
+        assert_eq!(buf.as_bytes(), &[0xff; 12]);
+
+        let mut buf = VecCopy::with_type::<u64>();
+        unsafe { buf.fill_bytes(&[1; 8]).unwrap() };
+        assert!(buf.is_empty());
+    }
+
+    /// Test writing elements directly into the spare capacity of a buffer.
+    #[test]
+    fn spare_capacity_test() {
+        let mut buf = VecCopy::from_vec(vec![1u32]);
+        buf.reserve_exact(2);
+        let spare = buf.spare_capacity_mut();
+        assert_eq!(spare.len(), 8);
+        for (dst, src) in spare
+            .iter_mut()
+            .zip([2u32, 3].iter().flat_map(|x| x.to_ne_bytes()))
+        {
+            dst.write(src);
+        }
+        unsafe { buf.set_len_elements(3) };
+        assert_eq!(buf.as_slice::<u32>().unwrap(), &[1, 2, 3]);
+        assert!(buf.spare_capacity_mut().is_empty());
+
+        unsafe { buf.set_len_elements(1) };
+        assert_eq!(buf.as_slice::<u32>().unwrap(), &[1]);
+        assert_eq!(buf.spare_capacity_mut().len(), 8);
+    }
+
+    /// Test removing elements from the middle of a buffer.
+    #[test]
+    fn remove_test() {
+        let mut buf = VecCopy::from_vec(vec![1.0_f32, 23.0, 0.01, 42.0]);
+        assert!(buf.remove::<f64>(0).is_none());
+        assert_eq!(buf.len(), 4);
+
+        assert_eq!(buf.remove::<f32>(1), Some(23.0));
+        assert_eq!(buf.as_slice::<f32>().unwrap(), &[1.0, 0.01, 42.0]);
+
+        let bytes = buf.remove_bytes(2);
+        assert_eq!(&*bytes, &42.0_f32.to_ne_bytes());
+        assert_eq!(buf.as_slice::<f32>().unwrap(), &[1.0, 0.01]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn remove_out_of_bounds_test() {
+        let mut buf = VecCopy::from_vec(vec![1u32, 2]);
+        buf.remove::<u32>(2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn swap_remove_out_of_bounds_test() {
+        let mut buf = VecCopy::from_vec(vec![1u32, 2]);
+        buf.swap_remove::<u32>(2);
+    }
+
+    /// Test using a buffer as a stack.
+    #[test]
+    fn pop_test() {
+        let mut buf = VecCopy::from_vec(vec![1u8, 2, 3]);
+        assert!(buf.pop::<i8>().is_none());
+        assert_eq!(buf.len(), 3);
+        assert_eq!(buf.pop::<u8>(), Some(3));
+        assert_eq!(&*buf.pop_bytes().unwrap(), &[2]);
+        assert_eq!(buf.pop::<u8>(), Some(1));
+        assert!(buf.pop::<u8>().is_none());
+        assert!(buf.pop_bytes().is_none());
+    }
+
+    /// Test truncating a buffer, including to lengths that overflow in bytes.
+    #[test]
+    fn truncate_test() {
+        let mut buf = VecCopy::from_vec(vec![1u32, 2, 3]);
+        buf.truncate(usize::MAX / 2);
+        assert_eq!(buf.len(), 3);
+        buf.truncate(1);
+        assert_eq!(buf.as_slice::<u32>().unwrap(), &[1]);
+    }
+
+    /// Test extending a buffer with typed iterators.
+    #[test]
+    fn extend_test() {
+        let mut buf = VecCopy::with_type::<i16>();
+        assert!(buf.extend(vec![1u16, 2]).is_none());
+        assert!(buf.is_empty());
+
+        buf.extend(vec![1i16, 2]).unwrap();
+        buf.extend((3..5).map(|x: i16| -x)).unwrap();
+        assert_eq!(buf.as_slice::<i16>().unwrap(), &[1, 2, -3, -4]);
+
+        assert!(buf.typed_mut::<u16>().is_none());
+        let mut typed = buf.typed_mut::<i16>().unwrap();
+        typed.extend(vec![5i16]);
+        typed.extend(&[6i16, 7]);
+        typed.push(8);
+        assert_eq!(typed.as_slice(), &[1, 2, -3, -4, 5, 6, 7, 8]);
+
+        // Untyped values can still extend the buffer via the `Extend` trait.
+        let other = VecCopy::from_vec(vec![9i16]);
+        Extend::extend(&mut buf, other.iter_value_ref());
+        assert_eq!(buf.len(), 9);
+    }
+
+    /// Test adding elements without checking their type.
+    #[test]
+    fn unchecked_push_test() {
+        let mut buf = VecCopy::from_vec(vec![[1u8, 2, 3]]);
+        buf.reserve_exact(2);
+        unsafe {
+            buf.push_unchecked([4u8, 5, 6]);
+            buf.push_unchecked([7u8, 8, 9]);
+            buf.extend_unchecked((10..13u8).map(|x| [x; 3]));
+        }
+        assert_eq!(
+            buf.as_slice::<[u8; 3]>().unwrap(),
+            &[[1, 2, 3], [4, 5, 6], [7, 8, 9], [10; 3], [11; 3], [12; 3]]
+        );
+    }
+
+    /// Test extending a buffer with a known number of elements.
+    #[test]
+    fn extend_with_exact_test() {
+        let mut buf = VecCopy::with_type::<[f32; 2]>();
+        assert!(buf.extend_with_exact(1, vec![[0.0f64; 2]]).is_none());
+        buf.extend_with_exact(0, vec![[0.0f32; 2]]).unwrap();
+        assert!(buf.is_empty());
+
+        let points = (0..).map(|i| [i as f32, -(i as f32)]);
+        buf.extend_with_exact(3, points.clone()).unwrap();
+        assert_eq!(buf.capacity(), 3);
+        assert_eq!(
+            buf.as_slice::<[f32; 2]>().unwrap(),
+            &[[0.0, -0.0], [1.0, -1.0], [2.0, -2.0]]
+        );
+
+        // Producing too few elements leaves the buffer unchanged.
+        assert!(buf.extend_with_exact(5, points.take(4)).is_none());
+        assert_eq!(buf.len(), 3);
+        buf.extend_with_exact(1, vec![[3.0f32, -3.0]]).unwrap();
+        assert_eq!(buf.last::<[f32; 2]>(), Some(&[3.0, -3.0]));
+    }
+
+    /// Test collecting iterators into a buffer.
+    #[test]
+    fn from_iter_test() {
+        let buf: VecCopy = (0..5).map(|x| x as f64 * 0.5).collect();
+        assert_eq!(buf.as_slice::<f64>().unwrap(), &[0.0, 0.5, 1.0, 1.5, 2.0]);
+        assert!(buf.byte_capacity() >= 5 * size_of::<f64>());
+
+        let empty: VecCopy = std::iter::empty::<u8>().collect();
+        assert!(empty.is_empty());
+        assert_eq!(empty.element_type_id(), TypeId::of::<u8>());
+
+        // Untyped values are collected explicitly.
+        let copy = VecCopy::from_value_iter(buf.iter_value_ref()).unwrap();
+        assert_eq!(copy, buf);
+        assert!(VecCopy::from_value_iter(empty.iter_value_ref()).is_none());
+    }
+
+    /// Test consuming a buffer with a typed iterator.
+    #[test]
+    fn into_typed_iter_test() {
+        let buf = VecCopy::from_vec(vec![1u8, 2, 3, 4]);
+        assert!(buf.clone().into_typed_iter::<i8>().is_none());
+
+        let mut iter = buf.into_typed_iter::<u8>().unwrap();
+        assert_eq!(iter.size_hint(), (4, Some(4)));
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.next_back(), Some(4));
+        assert_eq!(iter.rev().collect::<Vec<_>>(), vec![3, 2]);
+
+        let buf = VecCopy::from_vec(vec![[0.5f64; 3], [1.5; 3]]);
+        let vals: Vec<[f64; 3]> = buf.into_typed_iter().unwrap().collect();
+        assert_eq!(vals, vec![[0.5; 3], [1.5; 3]]);
+    }
+
+    #[test]
+    fn try_access_test() {
+        let mut buf = VecCopy::from_vec(vec![1u32, 2, 3]);
+        assert_eq!(buf.try_as_slice::<u32>(), Ok(&[1u32, 2, 3][..]));
+        assert_eq!(buf.try_get::<u32>(1), Ok(2));
+        assert_eq!(
+            buf.try_get_ref::<u32>(3),
+            Err(AccessError::OutOfBounds { index: 3, len: 3 })
+        );
+        // Type mismatches are reported before out of bounds indices.
+        assert!(matches!(
+            buf.try_get_mut::<f32>(5),
+            Err(AccessError::TypeMismatch(_))
+        ));
+        assert_eq!(buf.get::<u32>(3), None);
+        assert_eq!(buf.get_ref::<f32>(0), None);
+        assert_eq!(buf.get_mut::<u32>(usize::MAX), None);
+        *buf.try_get_mut::<u32>(0).unwrap() = 10;
+        buf.try_push(4u32).unwrap();
+        assert_eq!(buf.try_copy_into_vec::<u32>(), Ok(vec![10, 2, 3, 4]));
+
+        let err = match buf.try_push(1.0f32) {
+            Err(AccessError::TypeMismatch(err)) => err,
+            _ => panic!("expected a type mismatch"),
+        };
+        assert_eq!(err.expected(), TypeId::of::<f32>());
+        assert_eq!(err.expected_name(), "f32");
+        assert_eq!(err.found(), TypeId::of::<u32>());
+        assert!(err.to_string().starts_with("type mismatch: expected `f32`"));
+        assert_eq!(buf.len(), 4);
+
+        assert!(buf.try_iter::<i32>().is_err());
+        assert!(buf.try_iter_mut::<u32>().is_ok());
+        assert!(buf.clone().try_into_vec::<u64>().is_err());
+        assert_eq!(buf.try_into_vec::<u32>(), Ok(vec![10, 2, 3, 4]));
+    }
+
+    #[test]
+    fn element_type_name_test() {
+        let mut buf = VecCopy::with_type::<u32>();
+        assert_eq!(buf.element_type_name(), "u32");
+        assert!(format!("{:?}", buf).contains("element_type_name: \"u32\""));
+        assert_eq!(buf.split_at(0).0.element_type_name(), "u32");
+        assert_eq!(VecCopy::with_type_from(&buf).element_type_name(), "u32");
+
+        buf.copy_from_slice(&[1.0f64, 2.0]);
+        assert_eq!(buf.element_type_name(), "f64");
+        match buf.try_as_slice::<u8>() {
+            Err(AccessError::TypeMismatch(err)) => assert_eq!(err.found_name(), Some("f64")),
+            _ => panic!("expected a type mismatch"),
+        }
+
+        // Type names don't affect equality.
+        let untyped = VecCopy::from_value_iter(buf.iter_value_ref()).unwrap();
+        assert_eq!(untyped.element_type_name(), "<unknown>");
+        assert_eq!(untyped, buf);
+    }
+
+    #[test]
+    fn over_aligned_test() {
+        #[repr(align(32))]
+        #[derive(Copy, Clone, Debug, PartialEq)]
+        struct Simd([f32; 3]);
+
+        let mut buf = VecCopy::with_type::<Simd>();
+        assert_eq!(buf.alignment(), 32);
+        for i in 0..50 {
+            buf.push(Simd([i as f32; 3]));
+            assert_eq!(buf.as_bytes().as_ptr() as usize % 32, 0);
+        }
+        assert_eq!(buf.as_slice::<Simd>().unwrap()[49], Simd([49.0; 3]));
+
+        let tail = buf.split_off(10);
+        assert_eq!(tail.alignment(), 32);
+        assert_eq!(tail.get_ref::<Simd>(0), Some(&Simd([10.0; 3])));
+        assert_eq!(tail.clone().into_vec::<Simd>().unwrap().len(), 40);
+
+        // Stronger alignment than required by the element type.
+        let mut buf = VecCopy::with_type_aligned::<u8>(16);
+        buf.extend(0..100u8);
+        assert_eq!(buf.alignment(), 16);
+        assert_eq!(buf.as_bytes().as_ptr() as usize % 16, 0);
+        assert_eq!(buf.into_vec::<u8>().unwrap(), (0..100u8).collect::<Vec<_>>());
+
+        // Changing the type reallocates if the alignment is insufficient.
+        let mut buf = VecCopy::from_vec(vec![1u8, 2, 3]);
+        buf.copy_from_slice(&[Simd([1.0; 3])]);
+        assert_eq!(buf.alignment(), 32);
+        assert_eq!(buf.as_slice::<Simd>().unwrap(), &[Simd([1.0; 3])]);
+    }
+
+    #[test]
+    fn push_padded_test() {
+        // A type with 3 bytes of padding.
+        #[derive(Copy, Clone, Debug, PartialEq)]
+        struct Padded {
+            a: u8,
+            b: u32,
+        }
+
+        let mut buf = VecCopy::with_type::<Padded>();
+        buf.push(Padded { a: 1, b: 2 }).unwrap();
+        buf.insert(0, Padded { a: 3, b: 4 }).unwrap();
+        buf.extend(vec![Padded { a: 5, b: 6 }]).unwrap();
+        assert_eq!(
+            buf.as_slice::<Padded>().unwrap(),
+            &[
+                Padded { a: 3, b: 4 },
+                Padded { a: 1, b: 2 },
+                Padded { a: 5, b: 6 }
+            ]
+        );
+        assert_eq!(buf.pop::<Padded>(), Some(Padded { a: 5, b: 6 }));
+    }
+
+    #[test]
+    fn eq_fn_test() {
+        #[derive(Copy, Clone, Debug, PartialEq)]
+        struct Padded {
+            a: u8,
+            b: u32,
+        }
+
+        let a = VecCopy::from_vec(vec![f64::NAN, 0.0]);
+        let b = VecCopy::from_vec(vec![f64::NAN, -0.0]);
+        assert_ne!(a, a.clone());
+        assert_ne!(a, b);
+        let (_, a_tail) = a.split_at(1);
+        let (_, b_tail) = b.split_at(1);
+        assert_eq!(a_tail, b_tail);
+        assert_eq!(a_tail.to_vec_copy(), b_tail.to_vec_copy());
+
+        assert_eq!(VecCopy::from_vec(vec![1u32]), VecCopy::from_vec(vec![1u32]));
+        assert_ne!(VecCopy::from_vec(vec![1u32]), VecCopy::from_vec(vec![1i32]));
+
+        let a = VecCopy::from_vec(vec![Padded { a: 1, b: 2 }]);
+        let mut b = VecCopy::with_type::<Padded>();
+        b.push(Padded { a: 1, b: 2 }).unwrap();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn hash_fn_test() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::collections::HashMap;
+        use std::hash::Hasher;
+
+        fn hash_of(buf: &VecCopy) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            buf.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        // A type with padding.
+        #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+        struct Padded {
+            a: u8,
+            b: u32,
+        }
+
+        let a = VecCopy::from_vec(vec![Padded { a: 1, b: 2 }])
+            .with_hash::<Padded>()
+            .unwrap();
+        let mut b = VecCopy::with_type::<Padded>().with_hash::<Padded>().unwrap();
+        b.push(Padded { a: 1, b: 2 }).unwrap();
+        assert_eq!(a, b);
+        assert_eq!(hash_of(&a), hash_of(&b));
+        assert_ne!(
+            hash_of(&a),
+            hash_of(&VecCopy::from_vec(vec![Padded { a: 1, b: 3 }]))
+        );
+
+        let mut map = HashMap::new();
+        map.insert(a, "padded");
+        assert_eq!(map.get(&b), Some(&"padded"));
+
+        // Floats are hashed consistently with equality, but by their values.
+        let f = VecCopy::from_vec(vec![0.0f64]);
+        let neg_f = VecCopy::from_vec(vec![-0.0f64]);
+        assert_eq!(f, neg_f);
+        assert_eq!(hash_of(&f), hash_of(&neg_f));
+        assert_ne!(hash_of(&f), hash_of(&VecCopy::from_vec(vec![1.0f64])));
+        let nan = VecCopy::from_vec(vec![[f32::NAN, 1.0]]);
+        let other_nan = VecCopy::from_vec(vec![[-f32::NAN, 1.0]]);
+        assert_eq!(hash_of(&nan), hash_of(&other_nan));
+        assert_ne!(
+            hash_of(&VecCopy::from_vec(vec![[1.0f32, 2.0]])),
+            hash_of(&VecCopy::from_vec(vec![[1.0f32, 3.0]]))
+        );
+
+        // Arrays of integers are hashed by their bytes.
+        let d = VecCopy::from_vec(vec![[1u32, 2, 3]]);
+        assert_ne!(hash_of(&d), hash_of(&VecCopy::from_vec(vec![[1u32, 2, 4]])));
+
+        // Integers are hashed by bytes regardless of the captured function.
+        let c = VecCopy::from_vec(vec![1u32, 2]);
+        assert_eq!(hash_of(&c), hash_of(&c.clone().with_hash::<u32>().unwrap()));
+        assert!(c.with_hash::<i32>().is_none());
+    }
+
+    #[test]
+    fn debug_fn_test() {
+        let buf = VecCopy::from_vec(vec![1u8, 2]);
+        assert_eq!(
+            format!("{:?}", buf),
+            "VecCopy { element_type_name: \"u8\", len: 2, data: [[1], [2]] }"
+        );
+        let buf = buf.with_debug::<u8>().unwrap();
+        assert_eq!(
+            format!("{:?}", buf.split_at(1).1),
+            "SliceCopy { element_type_name: \"u8\", len: 1, data: [2] }"
+        );
+
+        // Long buffers are truncated.
+        let buf = VecCopy::from_vec((0..100u32).collect())
+            .with_debug::<u32>()
+            .unwrap();
+        let out = format!("{:?}", buf);
+        assert!(out.contains("len: 100"));
+        assert!(out.ends_with("30, 31, ...] }"));
+    }
+
+    #[test]
+    fn ord_fn_test() {
+        use std::cmp::Ordering;
+
+        // Bytes of little endian integers don't sort like the integers themselves.
+        let a = VecCopy::from_vec(vec![256u32]).with_ord::<u32>().unwrap();
+        let b = VecCopy::from_vec(vec![1u32, 2]).with_ord::<u32>().unwrap();
+        assert!(b < a);
+        assert!(b.split_at(1).0 < b.as_slice_copy());
+
+        let mut bufs = vec![a.clone(), b.clone(), b.clone()];
+        bufs.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(bufs, vec![b.clone(), b, a]);
+
+        // Different types are ordered but never equal.
+        let c = VecCopy::from_vec(vec![1i32]);
+        assert_ne!(
+            VecCopy::from_vec(vec![1u32]).partial_cmp(&c),
+            Some(Ordering::Equal)
+        );
+
+        // Without a captured ordering, elements are ordered consistently with their equality.
+        let z = VecCopy::from_vec(vec![0.0f32, f32::NAN]);
+        let (z0, z1) = z.split_at(1);
+        let neg_z = VecCopy::from_vec(vec![-0.0f32]);
+        assert_eq!(
+            z0.partial_cmp(&neg_z.as_slice_copy()),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(z1.partial_cmp(&z1), None);
+        assert_eq!(
+            z.partial_cmp(&VecCopy::from_vec(vec![-0.0f32, 1.0])),
+            Some(Ordering::Greater)
+        );
+
+        let f = VecCopy::from_vec(vec![-1.0f32, f32::NAN])
+            .with_partial_ord::<f32>()
+            .unwrap();
+        assert!(VecCopy::from_vec(vec![-2.0f32]) < f);
+        assert_eq!(f.partial_cmp(&f), None);
+        assert!(f.clone().with_ord::<u32>().is_none());
+    }
+
+    #[test]
+    fn default_fn_test() {
+        #[derive(Copy, Clone, Debug, PartialEq)]
+        struct Point {
+            x: f64,
+            y: u8,
+        }
+        impl Default for Point {
+            fn default() -> Self {
+                Point { x: 1.5, y: 7 }
+            }
+        }
+
+        let mut buf = VecCopy::with_type::<Point>();
+        assert!(buf.push_default().is_none());
+        assert!(buf.resize_with_default(2).is_none());
+        assert!(buf.is_empty());
+
+        let mut buf = buf.with_default::<Point>().unwrap();
+        buf.push(Point { x: 0.0, y: 0 }).unwrap();
+        buf.push_default().unwrap();
+        buf.resize_with_default(4).unwrap();
+        let expected = [
+            Point { x: 0.0, y: 0 },
+            Point::default(),
+            Point::default(),
+            Point::default(),
+        ];
+        assert_eq!(buf.as_slice::<Point>().unwrap(), &expected);
+        buf.resize_with_default(1).unwrap();
+        assert_eq!(buf.as_slice::<Point>().unwrap(), &expected[..1]);
+
+        // Cloned buffers keep the captured function.
+        let mut clone = buf.clone();
+        clone.push_default().unwrap();
+        assert_eq!(clone.len(), 2);
+    }
+
+    /// Test filtering a buffer in place.
+    #[test]
+    fn retain_test() {
+        let mut buf = VecCopy::from_vec((0..10u64).collect::<Vec<_>>());
+        assert!(buf.retain(|_: &u32| false).is_none());
+        assert_eq!(buf.len(), 10);
+
+        buf.retain(|&x: &u64| x % 3 == 0).unwrap();
+        assert_eq!(buf.as_slice::<u64>().unwrap(), &[0, 3, 6, 9]);
+
+        buf.retain(|_: &u64| false).unwrap();
+        assert!(buf.is_empty());
+    }
+
+    /// Test filtering a buffer in place by the bytes of its elements.
+    #[test]
+    fn retain_bytes_test() {
+        let mut buf = VecCopy::from_vec((0..10u32).collect::<Vec<_>>());
+        let mut num_calls = 0;
+        buf.retain_bytes(|bytes| {
+            num_calls += 1;
+            bytes.len() == 4 && bytes[0] % 4 == 1
+        });
+        assert_eq!(num_calls, 10);
+        assert_eq!(buf.as_slice::<u32>().unwrap(), &[1, 5, 9]);
+
+        buf.retain_bytes(|_| false);
+        assert!(buf.is_empty());
+    }
+
+    /// Test searching a buffer for an element.
+    #[test]
+    fn contains_test() {
+        let buf = VecCopy::from_vec(vec![1.0f64, f64::NAN, -0.0]);
+        assert!(buf.contains(&0.0f64));
+        assert!(!buf.contains(&f64::NAN));
+        assert!(!buf.contains(&1.0f32));
+        assert_eq!(buf.position(&-0.0f64), Some(2));
+        assert_eq!(buf.position(&1.0f32), None);
+
+        assert!(!buf.contains_bytes(&f64::NAN.to_ne_bytes()));
+        assert!(buf.contains_bytes(&0.0f64.to_ne_bytes()));
+        assert!(!buf.contains_bytes(&[0; 4]));
+    }
+
+    /// Test splitting a buffer in two by a predicate.
+    #[test]
+    fn partition_test() {
+        let buf = VecCopy::from_vec(vec![1.0f32, -2.0, 3.0, -4.0]);
+        assert!(buf.partition(|&x: &f64| x > 0.0).is_none());
+
+        let (pos, neg) = buf.partition(|&x: &f32| x > 0.0).unwrap();
+        assert_eq!(pos.as_slice::<f32>().unwrap(), &[1.0, 3.0]);
+        assert_eq!(neg.as_slice::<f32>().unwrap(), &[-2.0, -4.0]);
+        assert!(pos.capacity() >= buf.len());
+        assert!(neg.capacity() >= buf.len());
+        assert_eq!(pos, VecCopy::from_vec(vec![1.0f32, 3.0]));
+
+        let (all, none) = buf.partition(|_: &f32| true).unwrap();
+        assert_eq!(all, buf);
+        assert!(none.is_empty());
+    }
+
+    /// Test filtering a buffer with a boolean mask.
+    #[test]
+    fn select_test() {
+        let mut buf = VecCopy::from_vec(vec![[1u8, 2], [3, 4], [5, 6]]);
+        let selected = buf.select(&[false, true, true]);
+        assert_eq!(selected.as_slice::<[u8; 2]>().unwrap(), &[[3, 4], [5, 6]]);
+        assert!(buf.select(&[false; 3]).is_empty());
+
+        buf.compress_by_mask(&[true, false, true]);
+        assert_eq!(buf.as_slice::<[u8; 2]>().unwrap(), &[[1, 2], [5, 6]]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn select_mask_length_test() {
+        let buf = VecCopy::from_vec(vec![1u32, 2, 3]);
+        let _ = buf.select(&[true, false]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn compress_by_mask_length_test() {
+        let mut buf = VecCopy::from_vec(vec![1u32, 2, 3]);
+        buf.compress_by_mask(&[true; 4]);
+    }
+
+    /// Test removing consecutive duplicates with the captured equality.
+    #[test]
+    fn dedup_test() {
+        let mut buf = VecCopy::from_vec(vec![0.0f64, -0.0, 0.0, f64::NAN, f64::NAN, 1.0]);
+        let mut typed = buf.clone();
+        assert!(typed.dedup_typed::<f32>().is_none());
+        typed.dedup_typed::<f64>().unwrap();
+        assert_eq!(typed.len(), 4);
+
+        buf.dedup();
+        // `NaN`s are never equal, so compare bytes.
+        assert_eq!(buf.as_bytes(), typed.as_bytes());
+
+        let mut empty = VecCopy::with_type::<u8>();
+        empty.dedup();
+        assert!(empty.dedup_typed::<u8>().unwrap().is_empty());
+    }
+
+    /// Test draining ranges of elements from a buffer.
+    #[test]
+    fn drain_test() {
+        let mut buf = VecCopy::from_vec(vec![1u32, 2, 3, 4, 5, 6]);
+        assert!(buf.drain::<i32, _>(..).is_none());
+        assert_eq!(buf.len(), 6);
+
+        let mut drain = buf.drain::<u32, _>(1..=4).unwrap();
+        assert_eq!(drain.len(), 4);
+        assert_eq!(drain.next_back(), Some(5));
+        assert_eq!(drain.next(), Some(2));
+        assert_eq!(drain.len(), 2);
+        drop(drain);
+        assert_eq!(buf.as_slice::<u32>().unwrap(), &[1, 6]);
+
+        let all: Vec<u32> = buf.drain::<u32, _>(..).unwrap().collect();
+        assert_eq!(all, vec![1, 6]);
+        assert!(buf.is_empty());
+    }
+
+    /// Test inserting elements into the middle of a buffer.
+    #[test]
+    fn insert_test() {
+        let mut buf = VecCopy::from_vec(vec![1i64, 2, 3]);
+        assert!(buf.insert(0, 1i32).is_none());
+        assert_eq!(buf.len(), 3);
+
+        buf.insert(0, 0i64).unwrap();
+        buf.insert(4, 4i64).unwrap();
+        buf.insert(2, 42i64).unwrap();
+        assert_eq!(buf.as_slice::<i64>().unwrap(), &[0, 1, 42, 2, 3, 4]);
+
+        unsafe {
+            assert!(buf.insert_bytes(1, &[0; 4]).is_none());
+            buf.insert_bytes(1, &7i64.to_ne_bytes()).unwrap();
+        }
+        assert_eq!(buf.as_slice::<i64>().unwrap(), &[0, 7, 1, 42, 2, 3, 4]);
+    }
+
+    /// Test removing elements by swapping in the last element.
+    #[test]
+    fn swap_remove_test() {
+        let mut buf = VecCopy::from_vec(vec![1u16, 2, 3, 4, 5]);
+        assert!(buf.swap_remove::<u32>(0).is_none());
+
+        assert_eq!(buf.swap_remove::<u16>(1), Some(2));
+        assert_eq!(buf.as_slice::<u16>().unwrap(), &[1, 5, 3, 4]);
+
+        // Removing the last element should simply truncate.
+        assert_eq!(&*buf.swap_remove_bytes(3), &4u16.to_ne_bytes());
+        assert_eq!(buf.as_slice::<u16>().unwrap(), &[1, 5, 3]);
+    }
+
+    /// Test bulk copies into and out of buffers.
+    #[test]
+    fn bulk_copy_test() {
+        #[derive(Copy, Clone, Debug, PartialEq)]
+        struct Padded {
+            a: u8,
+            b: u32,
+        }
+        let values = [Padded { a: 1, b: 2 }, Padded { a: 3, b: 4 }];
+        let mut buf = VecCopy::from_slice(&values[..1]);
+        assert!(buf.extend_from_slice(&[1u8]).is_none());
+        buf.extend_from_slice(&values[1..]).unwrap();
+        buf.extend_from_slice::<Padded>(&[]).unwrap();
+        assert_eq!(buf.as_slice::<Padded>().unwrap(), &values);
+
+        assert_eq!(buf.copy_into_vec::<Padded>().unwrap(), values.to_vec());
+        assert!(buf.copy_into_vec::<u8>().is_none());
+        let mut vec = vec![Padded { a: 0, b: 0 }];
+        buf.append_to_vec(&mut vec).unwrap();
+        assert_eq!(&vec[1..], &values);
+    }
+
+    /// Test filling buffers with repeated byte patterns and arbitrary values.
+    #[test]
+    fn fill_test() {
+        let mut buf = VecCopy::from_vec(vec![1.5f64; 5]);
+        assert!(buf.fill(0u64).is_none());
+        buf.fill(0.0f64).unwrap();
+        assert_eq!(buf.as_slice::<f64>().unwrap(), &[0.0; 5]);
+        buf.fill(-2.25f64).unwrap();
+        assert_eq!(buf.as_slice::<f64>().unwrap(), &[-2.25; 5]);
+
+        let mut buf = VecCopy::from_vec(vec![0i32; 3]);
+        buf.fill(-1i32).unwrap();
+        assert_eq!(buf.as_slice::<i32>().unwrap(), &[-1; 3]);
+
+        // Types with padding are filled element by element.
+        let mut buf = VecCopy::from_vec(vec![(1u8, 2u32); 3]);
+        buf.fill((0u8, 0u32)).unwrap();
+        assert_eq!(buf.as_slice::<(u8, u32)>().unwrap(), &[(0, 0); 3]);
+    }
+
+    /// Test swapping elements of a buffer without knowing their type.
+    #[test]
+    fn swap_test() {
+        let mut buf = VecCopy::from_vec(vec![[1u8, 2, 3], [4, 5, 6], [7, 8, 9]]);
+        buf.swap(0, 2);
+        assert_eq!(
+            buf.as_slice::<[u8; 3]>().unwrap(),
+            &[[7, 8, 9], [4, 5, 6], [1, 2, 3]]
+        );
+        buf.swap(2, 1);
+        buf.swap(1, 1);
+        assert_eq!(
+            buf.as_slice::<[u8; 3]>().unwrap(),
+            &[[7, 8, 9], [1, 2, 3], [4, 5, 6]]
+        );
+    }
+
+    /// Test reversing buffers without knowing the element type.
+    #[test]
+    fn reverse_test() {
+        let mut buf = VecCopy::from_vec(vec![[1u16, 2], [3, 4], [5, 6]]);
+        buf.reverse();
+        assert_eq!(
+            buf.as_slice::<[u16; 2]>().unwrap(),
+            &[[5, 6], [3, 4], [1, 2]]
+        );
+
+        let mut buf = VecCopy::from_vec((0..10u64).collect::<Vec<_>>());
+        buf.reverse();
+        let expected: Vec<u64> = (0..10).rev().collect();
+        assert_eq!(buf.as_slice::<u64>().unwrap(), expected.as_slice());
+
+        let mut buf = VecCopy::with_type::<u8>();
+        buf.reverse();
+        assert!(buf.is_empty());
+    }
+
+    /// Test copying overlapping ranges of elements within a buffer.
+    #[test]
+    fn copy_within_test() {
+        let mut buf = VecCopy::from_vec(vec![[1u8, 1], [2, 2], [3, 3], [4, 4]]);
+        buf.copy_within(2.., 0);
+        assert_eq!(
+            buf.as_slice::<[u8; 2]>().unwrap(),
+            &[[3, 3], [4, 4], [3, 3], [4, 4]]
+        );
+        buf.copy_within(..=1, 1);
+        assert_eq!(
+            buf.as_slice::<[u8; 2]>().unwrap(),
+            &[[3, 3], [3, 3], [4, 4], [4, 4]]
+        );
+        buf.copy_within(1..1, 4);
+        assert_eq!(buf.len(), 4);
+    }
+
+    #[test]
+    #[should_panic]
+    fn copy_within_out_of_bounds_test() {
+        VecCopy::from_vec(vec![1u8, 2, 3]).copy_within(0..2, 2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn swap_out_of_bounds_test() {
+        VecCopy::from_vec(vec![1u8, 2]).swap(0, 2);
+    }
+
+    /// Test splitting a buffer in two.
+    #[test]
+    fn split_off_test() {
+        let mut buf = VecCopy::from_vec(vec![1.0_f32, 23.0, 0.01, 42.0, 11.43]);
+        let tail = buf.split_off(2);
+        assert_eq!(buf.as_slice::<f32>().unwrap(), &[1.0, 23.0]);
+        assert_eq!(tail.as_slice::<f32>().unwrap(), &[0.01, 42.0, 11.43]);
+
+        let empty = buf.split_off(2);
+        assert!(empty.is_empty());
+        assert_eq!(empty.element_type_id(), TypeId::of::<f32>());
+        assert_eq!(empty.element_size(), size_of::<f32>());
+
+        let all = buf.split_off(0);
+        assert!(buf.is_empty());
+        assert_eq!(all.len(), 2);
+    }
+
+    /// Test borrowing a range of elements of a buffer.
+    #[test]
+    fn view_test() {
+        let mut buf = VecCopy::from_vec(vec![1u16, 2, 3, 4, 5]);
+        assert_eq!(buf.view(..).len(), 5);
+        assert!(buf.view(2..2).is_empty());
+        assert_eq!(buf.view(1..=2).as_slice::<u16>().unwrap(), &[2, 3]);
+        assert_eq!(buf.view(3..).element_type_name(), "u16");
+
+        let mut view = buf.view_mut(..2);
+        view.iter_mut::<u16>().unwrap().for_each(|x| *x *= 10);
+        assert_eq!(view.len(), 2);
+        assert_eq!(buf.as_slice::<u16>().unwrap(), &[10, 20, 3, 4, 5]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn view_out_of_bounds_test() {
+        let buf = VecCopy::from_vec(vec![1u16, 2]);
+        buf.view(1..3);
+    }
+
+    #[test]
+    fn get_disjoint_mut_test() {
+        let mut buf = VecCopy::from_vec(vec![1u8, 2, 3]);
+        assert!(buf.get_disjoint_mut::<u16, 1>([0]).is_none());
+        assert!(buf.get_disjoint_mut::<u8, 2>([0, 3]).is_none());
+        assert!(buf.get_disjoint_mut::<u8, 3>([0, 2, 0]).is_none());
+        assert_eq!(buf.get_disjoint_mut::<u8, 0>([]), Some([]));
+
+        let [a, b, c] = buf.get_disjoint_mut::<u8, 3>([2, 0, 1]).unwrap();
+        *a += *b + *c;
+        assert_eq!(buf.as_slice::<u8>().unwrap(), &[1, 2, 6]);
+    }
+
+    /// Test processing disjoint chunks of a buffer from separate threads.
+    #[test]
+    fn chunks_mut_erased_test() {
+        let mut buf = VecCopy::from_vec((0..10u64).collect::<Vec<_>>());
+        assert_eq!(buf.chunks_mut_erased(3).len(), 4);
+        assert_eq!(buf.chunks_mut_erased(usize::MAX).len(), 1);
+        std::thread::scope(|s| {
+            for mut chunk in buf.chunks_mut_erased(3) {
+                s.spawn(move || {
+                    for x in chunk.as_mut_slice::<u64>().unwrap() {
+                        *x *= 10;
+                    }
+                });
+            }
+        });
+        let expected: Vec<u64> = (0..10).map(|x| x * 10).collect();
+        assert_eq!(buf.as_slice::<u64>().unwrap(), expected.as_slice());
+
+        let chunks: Vec<_> = buf.chunks_mut_erased(4).map(|c| c.len()).collect();
+        assert_eq!(chunks, vec![4, 4, 2]);
+        assert_eq!(VecCopy::with_type::<u8>().chunks_mut_erased(1).len(), 0);
+    }
+
     /// Test appending to a data buffer from another data buffer.
     #[test]
     fn append_test() {
@@ -1341,6 +4946,28 @@ mod tests {
         }
     }
 
+    /// Test streaming elements in and out of a buffer.
+    #[test]
+    fn read_write_bytes_test() {
+        let values: Vec<u32> = (0..40_000).collect();
+        let buf = VecCopy::from_vec(values.clone());
+        let mut bytes = Vec::new();
+        buf.write_bytes_to(&mut bytes).unwrap();
+        assert_eq!(bytes.as_slice(), buf.as_bytes());
+
+        let mut read = VecCopy::with_type::<u32>();
+        let mut reader = bytes.as_slice();
+        unsafe { read.read_elements_from(&mut reader, 30_000) }.unwrap();
+        assert_eq!(read.as_slice::<u32>().unwrap(), &values[..30_000]);
+
+        // The buffer is unmodified if there are not enough bytes to read.
+        let err = unsafe { read.read_elements_from(&mut reader, 10_001) }.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+        assert_eq!(read.len(), 30_000);
+        let err = unsafe { read.read_elements_from(&mut reader, usize::MAX) }.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    }
+
     /// Test appending to a data buffer from other slices and vectors.
     #[test]
     fn extend_append_bytes_test() {
@@ -1386,4 +5013,103 @@ mod tests {
         dbg!(&new_buf);
         assert_eq!(buf, new_buf);
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_binary_test() {
+        let vec_f32: Vec<f32> = (0..1000).map(|i| i as f32).collect();
+        let buf = VecCopy::from_vec(vec_f32.clone());
+
+        // The data is stored as a contiguous blob following a few bytes of metadata.
+        let bytes = bincode::serialize(&buf).expect("Failed to serialize VecCopy.");
+        assert!(bytes.len() < 4000 + 64);
+        let new_buf: VecCopy =
+            bincode::deserialize(&bytes).expect("Failed to deserialize VecCopy.");
+        assert_eq!(new_buf.as_slice::<f32>().unwrap(), vec_f32.as_slice());
+
+        let bytes = postcard::to_allocvec(&buf).expect("Failed to serialize VecCopy.");
+        assert!(bytes.len() < 4000 + 32);
+        let new_buf: VecCopy =
+            postcard::from_bytes(&bytes).expect("Failed to deserialize VecCopy.");
+        assert_eq!(new_buf.as_slice::<f32>().unwrap(), vec_f32.as_slice());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_endianness_test() {
+        fn with_foreign_endianness(buf: &VecCopy) -> String {
+            let (native, foreign) = match Endianness::NATIVE {
+                Endianness::Little => ("\"Little\"", "\"Big\""),
+                Endianness::Big => ("\"Big\"", "\"Little\""),
+            };
+            let buf_str = serde_json::to_string(buf).expect("Failed to serialize VecCopy.");
+            assert!(buf_str.contains(native));
+            buf_str.replace(native, foreign)
+        }
+
+        let buf = VecCopy::from_vec(vec![1u32, 0x01020304]);
+        let swapped: VecCopy = serde_json::from_str(&with_foreign_endianness(&buf)).unwrap();
+        assert_eq!(
+            swapped.as_slice::<u32>().unwrap(),
+            &[0x01000000, 0x04030201]
+        );
+
+        // Composite types are swapped by their scalars.
+        #[derive(Copy, Clone, PartialEq)]
+        struct Vec2([u16; 2]);
+        unsafe impl StableType for Vec2 {
+            const STABLE_NAME: &'static str = "data_buffer::serde_endianness_test::Vec2";
+            const SCALAR_SIZE: Option<usize> = Some(2);
+        }
+        register_type::<Vec2>();
+        let buf = VecCopy::from_vec(vec![Vec2([1, 2])]);
+        let swapped: VecCopy = serde_json::from_str(&with_foreign_endianness(&buf)).unwrap();
+        assert_eq!(swapped.get::<Vec2>(0).unwrap().0, [0x100, 0x200]);
+
+        // Types with an unknown layout can't be converted.
+        #[derive(Copy, Clone, PartialEq)]
+        struct Opaque(u32);
+        unsafe impl StableType for Opaque {
+            const STABLE_NAME: &'static str = "data_buffer::serde_endianness_test::Opaque";
+        }
+        register_type::<Opaque>();
+        let buf = VecCopy::from_vec(vec![Opaque(1)]);
+        assert_eq!(buf.get::<Opaque>(0).map(|Opaque(x)| x), Some(1));
+        let buf_str = with_foreign_endianness(&buf);
+        assert!(serde_json::from_str::<VecCopy>(&buf_str).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_stable_type_test() {
+        #[derive(Copy, Clone, Debug, PartialEq)]
+        struct Point {
+            x: f32,
+            y: u16,
+        }
+        unsafe impl StableType for Point {
+            const STABLE_NAME: &'static str = "data_buffer::serde_stable_type_test::Point";
+        }
+
+        let buf = VecCopy::from_vec(vec![Point { x: 1.0, y: 2 }]);
+        assert!(serde_json::to_string(&buf).is_err());
+
+        register_type::<Point>();
+        let buf_str = serde_json::to_string(&buf).expect("Failed to serialize VecCopy.");
+        assert!(buf_str.contains(&Point::STABLE_ID.as_u64().to_string()));
+        let new_buf: VecCopy =
+            serde_json::from_str(&buf_str).expect("Failed to deserialize VecCopy.");
+        assert_eq!(new_buf.element_type_name(), Point::STABLE_NAME);
+        assert_eq!(
+            new_buf.as_slice::<Point>().unwrap(),
+            &[Point { x: 1.0, y: 2 }]
+        );
+
+        // Unknown types and mismatched sizes are rejected.
+        let unknown = buf_str.replace(&Point::STABLE_ID.as_u64().to_string(), "42");
+        assert!(serde_json::from_str::<VecCopy>(&unknown).is_err());
+        let resized = buf_str.replace("\"element_size\":8", "\"element_size\":4");
+        assert_ne!(resized, buf_str);
+        assert!(serde_json::from_str::<VecCopy>(&resized).is_err());
+    }
 }
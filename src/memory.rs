@@ -0,0 +1,136 @@
+//! This module defines a summary of the memory used by a collection of buffers.
+//!
+//! Applications holding many runtime typed buffers, for instance the attributes of all meshes in a
+//! scene, can use a `MemoryReport` to track how much memory the buffers occupy and how much of it
+//! is reserved but unused.
+
+use std::fmt;
+use std::iter::FromIterator;
+
+use crate::{DataTable, VecCopy};
+
+/// The total memory usage of a collection of buffers.
+///
+/// # Examples
+/// ```
+/// use data_buffer::{MemoryReport, VecCopy};
+/// let mut a = VecCopy::with_capacity::<f32>(64);
+/// a.extend_from_slice(&[1.0f32; 32]);
+/// let b = VecCopy::from_vec(vec![0u8; 100]);
+/// let report = MemoryReport::of(&[a, b]);
+/// assert_eq!(report.num_buffers, 2);
+/// assert_eq!(report.used_bytes, 228);
+/// assert_eq!(report.wasted_bytes(), 128);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct MemoryReport {
+    /// The number of buffers included in this report.
+    pub num_buffers: usize,
+    /// The number of bytes occupied by elements.
+    pub used_bytes: usize,
+    /// The number of bytes of capacity reserved for elements.
+    pub capacity_bytes: usize,
+    /// The number of bytes allocated on the heap.
+    pub heap_bytes: usize,
+}
+
+impl MemoryReport {
+    /// Compute the total memory usage of the given buffers.
+    #[inline]
+    pub fn of<'a>(bufs: impl IntoIterator<Item = &'a VecCopy>) -> Self {
+        bufs.into_iter().collect()
+    }
+
+    /// Add the memory usage of the given buffer to this report.
+    #[inline]
+    pub fn add(&mut self, buf: &VecCopy) {
+        self.num_buffers += 1;
+        self.used_bytes += buf.capacity_bytes_used();
+        self.capacity_bytes += buf.byte_capacity();
+        self.heap_bytes += buf.heap_size_bytes();
+    }
+
+    /// Get the number of bytes of capacity not occupied by elements.
+    #[inline]
+    pub fn wasted_bytes(&self) -> usize {
+        self.capacity_bytes - self.used_bytes
+    }
+}
+
+impl<'a> FromIterator<&'a VecCopy> for MemoryReport {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = &'a VecCopy>>(iter: I) -> Self {
+        let mut report = MemoryReport::default();
+        for buf in iter {
+            report.add(buf);
+        }
+        report
+    }
+}
+
+impl<'a> Extend<&'a VecCopy> for MemoryReport {
+    #[inline]
+    fn extend<I: IntoIterator<Item = &'a VecCopy>>(&mut self, iter: I) {
+        for buf in iter {
+            self.add(buf);
+        }
+    }
+}
+
+impl fmt::Display for MemoryReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} buffers using {} of {} reserved bytes ({} bytes on the heap)",
+            self.num_buffers, self.used_bytes, self.capacity_bytes, self.heap_bytes
+        )
+    }
+}
+
+impl DataTable {
+    /// Compute the total memory usage of the columns of this table.
+    #[inline]
+    pub fn memory_report(&self) -> MemoryReport {
+        MemoryReport::of(self.columns.values())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memory_report_test() {
+        let mut buf = VecCopy::with_capacity::<u32>(16);
+        buf.extend_from_slice(&[1u32; 8]);
+        assert_eq!(buf.capacity_bytes_used(), 32);
+        assert_eq!(buf.capacity_bytes_wasted(), 32);
+        assert_eq!(buf.heap_size_bytes(), 64);
+        buf.shrink_to_fit();
+        assert_eq!(buf.capacity_bytes_wasted(), 0);
+        assert_eq!(VecCopy::with_type::<u8>().heap_size_bytes(), 0);
+
+        let mut table = DataTable::new();
+        table.insert("a", buf.clone()).unwrap();
+        table.insert("b", VecCopy::from_vec(vec![0u64; 8])).unwrap();
+        let report = table.memory_report();
+        assert_eq!(
+            report,
+            MemoryReport {
+                num_buffers: 2,
+                used_bytes: 96,
+                capacity_bytes: 96,
+                heap_bytes: 96,
+            }
+        );
+        assert_eq!(
+            report.to_string(),
+            "2 buffers using 96 of 96 reserved bytes (96 bytes on the heap)"
+        );
+
+        let mut total = report;
+        total.extend(vec![&buf]);
+        assert_eq!(total.num_buffers, 3);
+        assert_eq!(total.used_bytes, 128);
+    }
+}
@@ -0,0 +1,226 @@
+//! This module implements read-only views of memory-mapped files in the raw binary format.
+//!
+//! Files written with [`VecCopy::encode_raw`] can be mapped into memory and viewed as a typed
+//! slice without reading them into memory first. The operating system pages in the data on
+//! demand, which makes it possible to work with datasets larger than the available RAM.
+//!
+//! The header is validated before any data is borrowed, so the file must store elements of the
+//! requested type in the byte order of the running platform. Since the data follows the 32 byte
+//! header in a page aligned mapping, elements with alignments of up to 32 bytes can be borrowed.
+
+use std::fmt;
+use std::fs::File;
+use std::io;
+use std::marker::PhantomData;
+use std::mem::{align_of, size_of};
+use std::ops::Deref;
+use std::path::Path;
+
+use memmap2::Mmap;
+
+use crate::raw::{Header, HEADER_SIZE};
+use crate::{DecodeError, Endianness, SliceCopy, StableType, VecCopy};
+
+/// A read-only view of the elements of a memory-mapped file in the raw binary format.
+///
+/// This dereferences to a slice of the elements stored in the file.
+pub struct MappedBuffer<T> {
+    mmap: Mmap,
+    len: usize,
+    phantom: PhantomData<T>,
+}
+
+impl VecCopy {
+    /// Map the file at the given path, written with [`encode_raw`], into memory and view its
+    /// elements as type `T`.
+    ///
+    /// Malformed data or data with elements other than `T` produces an error of kind
+    /// `InvalidData` wrapping a [`DecodeError`], and `UnexpectedEof` is returned if the file is
+    /// truncated. If the data is followed by a checksum, it is verified before mapping the file
+    /// succeeds, which reads the whole file.
+    ///
+    /// [`encode_raw`]: VecCopy::encode_raw
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified while it is mapped, and its data must be valid values of
+    /// type `T`.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let path = std::env::temp_dir().join("data_buffer_map_file_doctest.bin");
+    /// let buf = VecCopy::from_vec(vec![1u32, 2, 3]);
+    /// buf.encode_raw(&mut std::fs::File::create(&path).unwrap()).unwrap();
+    /// let mapped = unsafe { VecCopy::map_file::<u32>(&path).unwrap() };
+    /// assert_eq!(&mapped[..], &[1, 2, 3]);
+    /// # drop(mapped);
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub unsafe fn map_file<T: StableType>(path: impl AsRef<Path>) -> io::Result<MappedBuffer<T>> {
+        let invalid = |err: DecodeError| io::Error::new(io::ErrorKind::InvalidData, err);
+        let file = File::open(path)?;
+        let mmap = Mmap::map(&file)?;
+        if mmap.len() < HEADER_SIZE {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        let mut header = [0u8; HEADER_SIZE];
+        header.copy_from_slice(&mmap[..HEADER_SIZE]);
+        let header = Header::decode_uncompressed(&header).map_err(invalid)?;
+        if header.element_type != T::STABLE_ID {
+            return Err(invalid(DecodeError::TypeMismatch {
+                expected: T::STABLE_NAME,
+                found: header.element_type,
+            }));
+        }
+        if header.element_size != size_of::<T>() {
+            return Err(invalid(DecodeError::ElementSizeMismatch {
+                type_name: T::STABLE_NAME,
+                expected: size_of::<T>(),
+                found: header.element_size,
+            }));
+        }
+        if header.endianness != Endianness::NATIVE {
+            return Err(invalid(DecodeError::ForeignEndianness(header.endianness)));
+        }
+        if (mmap.as_ptr() as usize + HEADER_SIZE) % align_of::<T>() != 0 {
+            return Err(invalid(DecodeError::Misaligned {
+                align: align_of::<T>(),
+            }));
+        }
+        let payload_size = header.payload_size().map_err(invalid)?;
+        if ((mmap.len() - HEADER_SIZE) as u64) < payload_size {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        header
+            .verify_checksum(&mmap[HEADER_SIZE..])
+            .map_err(invalid)?;
+        Ok(MappedBuffer {
+            mmap,
+            len: header.len as usize,
+            phantom: PhantomData,
+        })
+    }
+}
+
+impl<T: StableType> MappedBuffer<T> {
+    /// Borrow the mapped elements as a typed slice.
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        // This is safe since the header was validated and the data is sufficiently aligned.
+        unsafe {
+            std::slice::from_raw_parts(self.mmap.as_ptr().add(HEADER_SIZE) as *const T, self.len)
+        }
+    }
+
+    /// Borrow the mapped elements as a type-erased slice.
+    #[inline]
+    pub fn as_slice_copy(&self) -> SliceCopy<'_> {
+        SliceCopy::from_slice(self.as_slice())
+    }
+
+    /// Copy the mapped elements into an owned buffer.
+    #[inline]
+    pub fn to_vec_copy(&self) -> VecCopy {
+        VecCopy::from_slice(self.as_slice())
+    }
+}
+
+impl<T: StableType> Deref for MappedBuffer<T> {
+    type Target = [T];
+    #[inline]
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T: StableType> AsRef<[T]> for MappedBuffer<T> {
+    #[inline]
+    fn as_ref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T: StableType + fmt::Debug> fmt::Debug for MappedBuffer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.as_slice().fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn map_file_test() {
+        let dir = std::env::temp_dir();
+        let path = dir.join(format!("data_buffer_mmap_test_{}.bin", std::process::id()));
+        let buf = VecCopy::from_vec((0..1000).map(f64::from).collect::<Vec<_>>());
+        let mut bytes = Vec::new();
+        buf.encode_raw(&mut bytes).unwrap();
+        File::create(&path).unwrap().write_all(&bytes).unwrap();
+
+        let mapped = unsafe { VecCopy::map_file::<f64>(&path).unwrap() };
+        assert_eq!(mapped.len(), 1000);
+        assert_eq!(mapped[999], 999.0);
+        assert_eq!(mapped.to_vec_copy(), buf);
+        assert_eq!(mapped.as_slice_copy().len(), 1000);
+        drop(mapped);
+
+        let decode_err = |bytes: &[u8]| {
+            File::create(&path).unwrap().write_all(bytes).unwrap();
+            let err = unsafe { VecCopy::map_file::<f64>(&path).unwrap_err() };
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+            *err.into_inner().unwrap().downcast::<DecodeError>().unwrap()
+        };
+
+        let err = unsafe { VecCopy::map_file::<u64>(&path).unwrap_err() };
+        assert_eq!(
+            *err.into_inner().unwrap().downcast::<DecodeError>().unwrap(),
+            DecodeError::TypeMismatch {
+                expected: "u64",
+                found: f64::STABLE_ID
+            }
+        );
+        let mut foreign = bytes.clone();
+        foreign[6] ^= 1;
+        assert!(matches!(
+            decode_err(&foreign),
+            DecodeError::ForeignEndianness(_)
+        ));
+        let mut corrupt = bytes.clone();
+        corrupt[0] = b'X';
+        assert_eq!(decode_err(&corrupt), DecodeError::InvalidMagic);
+
+        // Truncated data.
+        File::create(&path)
+            .unwrap()
+            .write_all(&bytes[..bytes.len() - 1])
+            .unwrap();
+        let err = unsafe { VecCopy::map_file::<f64>(&path).unwrap_err() };
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+
+        // Checksums are verified.
+        let mut bytes = Vec::new();
+        buf.encode_raw_with_checksum(&mut bytes).unwrap();
+        File::create(&path).unwrap().write_all(&bytes).unwrap();
+        let mapped = unsafe { VecCopy::map_file::<f64>(&path).unwrap() };
+        assert_eq!(mapped.to_vec_copy(), buf);
+        drop(mapped);
+        let mut corrupt = bytes.clone();
+        corrupt[HEADER_SIZE] ^= 1;
+        assert!(matches!(
+            decode_err(&corrupt),
+            DecodeError::CorruptData { .. }
+        ));
+        File::create(&path)
+            .unwrap()
+            .write_all(&bytes[..bytes.len() - 1])
+            .unwrap();
+        let err = unsafe { VecCopy::map_file::<f64>(&path).unwrap_err() };
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,94 @@
+//! This module implements conversions between buffers and `ndarray` arrays.
+//!
+//! Buffers can be viewed as one dimensional arrays, or as two dimensional arrays in row-major
+//! order, without copying the data.
+
+use ndarray::{Array1, ArrayView1, ArrayView2, ArrayViewMut1};
+
+use crate::VecCopy;
+
+impl VecCopy {
+    /// Borrow the buffer as a one dimensional array view with elements of type `T`.
+    ///
+    /// Returns `None` if `T` doesn't match the element type of the buffer.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let buf = VecCopy::from_vec(vec![1.0f64, 2.0, 3.0]);
+    /// let view = buf.as_array_view::<f64>().unwrap();
+    /// assert_eq!(view.sum(), 6.0);
+    /// ```
+    #[inline]
+    pub fn as_array_view<T: 'static>(&self) -> Option<ArrayView1<'_, T>> {
+        self.as_slice::<T>().map(ArrayView1::from)
+    }
+
+    /// Mutably borrow the buffer as a one dimensional array view with elements of type `T`.
+    ///
+    /// Returns `None` if `T` doesn't match the element type of the buffer.
+    #[inline]
+    pub fn as_array_view_mut<T: 'static>(&mut self) -> Option<ArrayViewMut1<'_, T>> {
+        self.as_mut_slice::<T>().map(ArrayViewMut1::from)
+    }
+
+    /// Borrow the buffer as a two dimensional array view with the given number of rows and
+    /// columns in row-major order.
+    ///
+    /// Returns `None` if `T` doesn't match the element type of the buffer, or if the length of
+    /// the buffer is not `rows * cols`.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let buf = VecCopy::from_vec(vec![1u32, 2, 3, 4, 5, 6]);
+    /// let view = buf.as_array_view2::<u32>(2, 3).unwrap();
+    /// assert_eq!(view[[1, 0]], 4);
+    /// assert!(buf.as_array_view2::<u32>(4, 2).is_none());
+    /// ```
+    #[inline]
+    pub fn as_array_view2<T: 'static>(
+        &self,
+        rows: usize,
+        cols: usize,
+    ) -> Option<ArrayView2<'_, T>> {
+        let slice = self.as_slice::<T>()?;
+        // `from_shape` accepts slices longer than the shape requires.
+        if rows.checked_mul(cols)? != slice.len() {
+            return None;
+        }
+        ArrayView2::from_shape((rows, cols), slice).ok()
+    }
+
+    /// Convert this buffer into a one dimensional array with elements of type `T`.
+    ///
+    /// The allocation is reused if possible (see `into_vec`). Returns `None` if `T` doesn't match
+    /// the element type of the buffer.
+    #[inline]
+    pub fn into_array1<T: 'static>(self) -> Option<Array1<T>> {
+        self.into_vec::<T>().map(Array1::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ndarray_test() {
+        let mut buf = VecCopy::from_vec(vec![1i32, 2, 3, 4]);
+        assert!(buf.as_array_view::<u32>().is_none());
+        assert_eq!(buf.as_array_view::<i32>().unwrap().len(), 4);
+
+        buf.as_array_view_mut::<i32>()
+            .unwrap()
+            .mapv_inplace(|x| x * 10);
+        let view = buf.as_array_view2::<i32>(2, 2).unwrap();
+        assert_eq!(view.row(1).to_vec(), vec![30, 40]);
+        assert!(buf.as_array_view2::<i32>(3, 1).is_none());
+        assert!(buf.as_array_view2::<i64>(2, 2).is_none());
+
+        let array = buf.into_array1::<i32>().unwrap();
+        assert_eq!(array.to_vec(), vec![10, 20, 30, 40]);
+    }
+}
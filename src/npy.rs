@@ -0,0 +1,289 @@
+//! This module implements reading and writing buffers in the NumPy `.npy` format.
+//!
+//! Arrays with boolean, integer and floating point element types are supported in either byte
+//! order. Since buffers are one dimensional, multidimensional arrays are read in row-major order
+//! and their shape is discarded. See the [format specification] for details.
+//!
+//! [format specification]: https://numpy.org/doc/stable/reference/generated/numpy.lib.format.html
+
+use std::any::TypeId;
+use std::io::{self, Read, Write};
+use std::mem::size_of;
+
+use crate::trace::trace_span;
+use crate::{DecodeError, Endianness, ScalarType, StableType, StableTypeId, VecCopy};
+
+/// The magic bytes identifying the `.npy` format.
+const MAGIC: &[u8; 6] = b"\x93NUMPY";
+
+/// Get the stable identifier and size of the type with the given `kind` and `size` of a dtype.
+///
+/// Booleans are read as bytes, which must be validated before they are converted to `bool`.
+fn npy_type(kind: char, size: usize) -> Option<(StableTypeId, usize)> {
+    fn ty<T: StableType>() -> Option<(StableTypeId, usize)> {
+        Some((T::STABLE_ID, size_of::<T>()))
+    }
+    match (kind, size) {
+        ('b', 1) => ty::<u8>(),
+        ('i', 1) => ty::<i8>(),
+        ('i', 2) => ty::<i16>(),
+        ('i', 4) => ty::<i32>(),
+        ('i', 8) => ty::<i64>(),
+        ('u', 1) => ty::<u8>(),
+        ('u', 2) => ty::<u16>(),
+        ('u', 4) => ty::<u32>(),
+        ('u', 8) => ty::<u64>(),
+        ('f', 4) => ty::<f32>(),
+        ('f', 8) => ty::<f64>(),
+        _ => None,
+    }
+}
+
+/// Get the dtype kind and size of the type with the given `TypeId`.
+fn npy_kind(type_id: TypeId) -> Option<(char, usize)> {
+    let ty = ScalarType::from_type_id(type_id)?;
+    let kind = match ty {
+        ScalarType::Bool => 'b',
+        ScalarType::I8 | ScalarType::I16 | ScalarType::I32 | ScalarType::I64 => 'i',
+        ScalarType::U8 | ScalarType::U16 | ScalarType::U32 | ScalarType::U64 => 'u',
+        ScalarType::F32 | ScalarType::F64 => 'f',
+    };
+    Some((kind, ty.size()))
+}
+
+/// Get the value of the given key in the header dictionary as a string slice.
+fn header_value<'a>(header: &'a str, key: &str) -> Option<&'a str> {
+    let start = header.find(&format!("'{}'", key))? + key.len() + 2;
+    let value = header[start..].trim_start().strip_prefix(':')?.trim_start();
+    let end = if value.starts_with('(') {
+        value.find(')')? + 1
+    } else {
+        value.find(&[',', '}'][..])?
+    };
+    Some(value[..end].trim())
+}
+
+/// Construct an error for an unsupported or malformed `.npy` header.
+fn invalid_header(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+impl VecCopy {
+    /// Read a buffer from the given reader in the NumPy `.npy` format.
+    ///
+    /// Data with a byte order different from the target platform is converted. Arrays with more
+    /// than one dimension are flattened, which requires them to be stored in row-major order.
+    ///
+    /// Unsupported element types or malformed headers produce an error of kind `InvalidData`, and
+    /// `UnexpectedEof` is returned if the data is truncated.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let buf = VecCopy::from_vec(vec![1.0f32, 2.0, 3.0]);
+    /// let mut bytes = Vec::new();
+    /// buf.to_npy(&mut bytes).unwrap();
+    /// assert_eq!(bytes.len() % 64, 12);
+    /// let read = VecCopy::from_npy(&mut bytes.as_slice()).unwrap();
+    /// assert_eq!(read, buf);
+    /// ```
+    pub fn from_npy<R: Read>(reader: &mut R) -> io::Result<VecCopy> {
+        let invalid = |err: DecodeError| io::Error::new(io::ErrorKind::InvalidData, err);
+        let mut preamble = [0u8; 8];
+        reader.read_exact(&mut preamble)?;
+        if &preamble[..6] != MAGIC {
+            return Err(invalid(DecodeError::InvalidMagic));
+        }
+        let header_len = match preamble[6] {
+            1 => {
+                let mut len = [0u8; 2];
+                reader.read_exact(&mut len)?;
+                u16::from_le_bytes(len) as u64
+            }
+            2 | 3 => {
+                let mut len = [0u8; 4];
+                reader.read_exact(&mut len)?;
+                u32::from_le_bytes(len) as u64
+            }
+            major => {
+                let version = u16::from_le_bytes([preamble[7], major]);
+                return Err(invalid(DecodeError::UnsupportedVersion(version)));
+            }
+        };
+        let mut header = String::new();
+        reader.take(header_len).read_to_string(&mut header)?;
+        if (header.len() as u64) < header_len {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+
+        let value = |key: &str| {
+            header_value(&header, key)
+                .ok_or_else(|| invalid_header(format!("missing `{}` in npy header", key)))
+        };
+        let descr = value("descr")?.trim_matches(|c| c == '\'' || c == '"');
+        let mut chars = descr.chars();
+        let endianness = match chars.next() {
+            Some('<') => Endianness::Little,
+            Some('>') => Endianness::Big,
+            Some('|') | Some('=') => Endianness::NATIVE,
+            _ => return Err(invalid_header(format!("invalid npy dtype `{}`", descr))),
+        };
+        let kind = chars.next().unwrap_or(' ');
+        let (element_type, element_size) = chars
+            .as_str()
+            .parse()
+            .ok()
+            .and_then(|size| npy_type(kind, size))
+            .ok_or_else(|| invalid_header(format!("unsupported npy dtype `{}`", descr)))?;
+        let shape_str = value("shape")?;
+        let shape = shape_str
+            .trim_matches(|c| c == '(' || c == ')')
+            .split(',')
+            .map(str::trim)
+            .filter(|dim| !dim.is_empty())
+            .map(|dim| dim.parse::<u64>().ok())
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| invalid_header(format!("invalid npy shape `{}`", shape_str)))?;
+        if shape.len() > 1 && value("fortran_order")? != "False" {
+            return Err(invalid_header(
+                "multidimensional npy arrays in column-major order are not supported".to_string(),
+            ));
+        }
+
+        let len = shape
+            .iter()
+            .try_fold(1u64, |acc, &dim| acc.checked_mul(dim));
+        let num_bytes = len
+            .and_then(|len| len.checked_mul(element_size as u64))
+            .ok_or_else(|| {
+                invalid(DecodeError::InvalidLength {
+                    len: len.unwrap_or(u64::MAX),
+                    element_size,
+                })
+            })?;
+        trace_span!("decode_npy", num_bytes);
+        // Don't trust the header with the size of the allocation.
+        let mut data = Vec::new();
+        reader.take(num_bytes).read_to_end(&mut data)?;
+        if (data.len() as u64) < num_bytes {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        if kind == 'b' {
+            // Any byte other than 0 or 1 is not a valid `bool`.
+            if let Some(index) = data.iter().position(|&byte| byte > 1) {
+                return Err(invalid(DecodeError::InvalidValue {
+                    type_name: "bool",
+                    index: index as u64,
+                }));
+            }
+            return Ok(VecCopy::from_vec(
+                data.into_iter()
+                    .map(|byte| byte == 1)
+                    .collect::<Vec<bool>>(),
+            ));
+        }
+        VecCopy::from_portable_bytes(data, element_size, element_type, endianness).map_err(invalid)
+    }
+
+    /// Write this buffer to the given writer as a one dimensional array in the NumPy `.npy`
+    /// format.
+    ///
+    /// Only buffers of `bool`, fixed size integer and floating point types can be written,
+    /// otherwise an error of kind `InvalidInput` is returned.
+    pub fn to_npy<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        trace_span!("encode_npy", num_bytes = self.data.len());
+        let (kind, size) = npy_kind(self.element_type_id()).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "element type `{}` has no npy dtype",
+                    self.element_type_name()
+                ),
+            )
+        })?;
+        let byte_order = match (size, Endianness::NATIVE) {
+            (1, _) => '|',
+            (_, Endianness::Little) => '<',
+            (_, Endianness::Big) => '>',
+        };
+        let mut header = format!(
+            "{{'descr': '{}{}{}', 'fortran_order': False, 'shape': ({},), }}",
+            byte_order,
+            kind,
+            size,
+            self.len()
+        );
+        // Pad the header with spaces, such that the data is aligned to 64 bytes.
+        let unpadded_len = MAGIC.len() + 4 + header.len() + 1;
+        header.push_str(&" ".repeat((64 - unpadded_len % 64) % 64));
+        header.push('\n');
+
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[1, 0])?;
+        writer.write_all(&(header.len() as u16).to_le_bytes())?;
+        writer.write_all(header.as_bytes())?;
+        writer.write_all(self.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build `.npy` data with the given header and data bytes.
+    fn npy_bytes(header: &str, data: &[u8]) -> Vec<u8> {
+        let mut bytes = b"\x93NUMPY\x01\x00".to_vec();
+        bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(header.as_bytes());
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    #[test]
+    fn npy_test() {
+        let buf = VecCopy::from_vec(vec![-1i16, 2, 300]);
+        let mut bytes = Vec::new();
+        buf.to_npy(&mut bytes).unwrap();
+        assert_eq!((bytes.len() - 6) % 64, 0);
+        assert_eq!(VecCopy::from_npy(&mut bytes.as_slice()).unwrap(), buf);
+
+        // An array written by NumPy with `np.save(f, np.array([[1, 2], [3, 4]], dtype='>u4'))`.
+        let header = "{'descr': '>u4', 'fortran_order': False, 'shape': (2, 2), }";
+        let data: Vec<u8> = (1u32..=4).flat_map(u32::to_be_bytes).collect();
+        let bytes = npy_bytes(header, &data);
+        let buf = VecCopy::from_npy(&mut bytes.as_slice()).unwrap();
+        assert_eq!(buf.as_slice::<u32>().unwrap(), &[1, 2, 3, 4]);
+
+        let err = VecCopy::from_npy(&mut &bytes[..bytes.len() - 1]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+        for header in &[
+            header.replace("False", "True"),
+            header.replace("u4", "c8"),
+            header.replace("2, 2", "2, x"),
+        ] {
+            let err = VecCopy::from_npy(&mut npy_bytes(header, &data).as_slice()).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        }
+
+        // Booleans are validated.
+        let buf = VecCopy::from_vec(vec![true, false]);
+        let mut bytes = Vec::new();
+        buf.to_npy(&mut bytes).unwrap();
+        assert_eq!(VecCopy::from_npy(&mut bytes.as_slice()).unwrap(), buf);
+        let header = "{'descr': '|b1', 'fortran_order': False, 'shape': (3,), }";
+        let err = VecCopy::from_npy(&mut npy_bytes(header, &[1, 0, 2]).as_slice()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            err.get_ref().unwrap().downcast_ref::<DecodeError>(),
+            Some(&DecodeError::InvalidValue {
+                type_name: "bool",
+                index: 2
+            })
+        );
+
+        let err = VecCopy::from_vec(vec!['a'])
+            .to_npy(&mut Vec::new())
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+}
@@ -0,0 +1,177 @@
+//! This module implements parsing text tokens into a buffer of an inferred numeric type.
+//!
+//! Loaders of text formats typically don't know the type of a column of numbers before reading
+//! it. `VecCopy::parse_auto` scans the tokens once and selects the narrowest numeric type that
+//! represents all of them, so the data can be stored without first inspecting it separately.
+
+use crate::{ParseError, ScalarType, VecCopy};
+
+/// Options controlling which types are selected by `VecCopy::parse_auto_with`.
+///
+/// By default tokens are parsed as `i64` if they are all integers, and as `f64` otherwise.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct InferOptions {
+    integers: bool,
+    single_precision: bool,
+}
+
+impl Default for InferOptions {
+    #[inline]
+    fn default() -> Self {
+        InferOptions {
+            integers: true,
+            single_precision: false,
+        }
+    }
+}
+
+impl InferOptions {
+    /// Construct the default options.
+    #[inline]
+    pub fn new() -> Self {
+        InferOptions::default()
+    }
+
+    /// Select whether tokens that are all integers are parsed as `i64`.
+    ///
+    /// If disabled, all tokens are parsed as floating point numbers.
+    #[inline]
+    pub fn integers(mut self, integers: bool) -> Self {
+        self.integers = integers;
+        self
+    }
+
+    /// Select whether floating point tokens are parsed as `f32` if no precision is lost.
+    ///
+    /// Precision is lost if the shortest decimal representation of a number as `f32` differs
+    /// from its value as `f64`. For instance, `0.1` and `2.5` can be parsed as `f32`, while
+    /// `0.123456789` cannot.
+    #[inline]
+    pub fn single_precision(mut self, single_precision: bool) -> Self {
+        self.single_precision = single_precision;
+        self
+    }
+}
+
+/// Check if the given number keeps its value when stored as `f32`.
+fn fits_f32(x: f64) -> bool {
+    x.is_nan() || (x as f32).to_string().parse::<f64>() == Ok(x)
+}
+
+impl VecCopy {
+    /// Parse the given tokens into a buffer of the narrowest numeric type representing all of
+    /// them, using the default `InferOptions`.
+    ///
+    /// Returns the buffer together with the selected type. See `parse_auto_with` for details.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::{ScalarType, VecCopy};
+    /// let (buf, ty) = VecCopy::parse_auto("1 2 3".split(' ')).unwrap();
+    /// assert_eq!(ty, ScalarType::I64);
+    /// assert_eq!(buf.as_slice::<i64>().unwrap(), &[1, 2, 3]);
+    ///
+    /// let (buf, ty) = VecCopy::parse_auto("1 2.5 1e3".split(' ')).unwrap();
+    /// assert_eq!(ty, ScalarType::F64);
+    /// assert_eq!(buf.as_slice::<f64>().unwrap(), &[1.0, 2.5, 1000.0]);
+    /// ```
+    #[inline]
+    pub fn parse_auto<'a>(
+        tokens: impl IntoIterator<Item = &'a str>,
+    ) -> Result<(VecCopy, ScalarType), ParseError> {
+        VecCopy::parse_auto_with(tokens, &InferOptions::default())
+    }
+
+    /// Parse the given tokens into a buffer of the narrowest numeric type representing all of
+    /// them, with the given options.
+    ///
+    /// Tokens are trimmed of surrounding whitespace. The tokens are parsed as `i64` if they are
+    /// all integers and `options` allows it, and as floating point numbers otherwise. Floating
+    /// point numbers are stored as `f32` if `options` allows it and no precision is lost, and as
+    /// `f64` otherwise. An empty sequence of tokens produces an empty buffer of the narrowest
+    /// allowed type.
+    ///
+    /// Returns a `ParseError` identifying the index of the first token that is not a number.
+    pub fn parse_auto_with<'a>(
+        tokens: impl IntoIterator<Item = &'a str>,
+        options: &InferOptions,
+    ) -> Result<(VecCopy, ScalarType), ParseError> {
+        let mut ints = Vec::<i64>::new();
+        let mut floats = Vec::<f64>::new();
+        let mut is_int = options.integers;
+        let mut is_f32 = options.single_precision;
+        for (row, token) in tokens.into_iter().enumerate() {
+            let token = token.trim();
+            if is_int {
+                if let Ok(x) = token.parse() {
+                    ints.push(x);
+                    continue;
+                }
+                // Integers are converted with the same rounding as parsing them as floats.
+                is_int = false;
+                floats = ints.drain(..).map(|x| x as f64).collect();
+                is_f32 = is_f32 && floats.iter().all(|&x| fits_f32(x));
+            }
+            let x = token.parse().map_err(|_| ParseError {
+                row,
+                token: token.to_string(),
+                ty: ScalarType::F64,
+            })?;
+            is_f32 = is_f32 && fits_f32(x);
+            floats.push(x);
+        }
+        Ok(if is_int {
+            (VecCopy::from_vec(ints), ScalarType::I64)
+        } else if is_f32 {
+            let floats: Vec<f32> = floats.into_iter().map(|x| x as f32).collect();
+            (VecCopy::from_vec(floats), ScalarType::F32)
+        } else {
+            (VecCopy::from_vec(floats), ScalarType::F64)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_auto_test() {
+        let (buf, ty) = VecCopy::parse_auto(vec![" -1", "0 ", "9223372036854775807"]).unwrap();
+        assert_eq!(ty, ScalarType::I64);
+        assert_eq!(buf.as_slice::<i64>().unwrap(), &[-1, 0, i64::MAX]);
+
+        let (buf, ty) = VecCopy::parse_auto(Vec::new()).unwrap();
+        assert_eq!(ty, ScalarType::I64);
+        assert!(buf.is_empty());
+
+        // Floats switch the type of previously parsed integers.
+        let (buf, ty) = VecCopy::parse_auto(vec!["1", "2", "0.5", "NaN"]).unwrap();
+        assert_eq!(ty, ScalarType::F64);
+        assert_eq!(&buf.as_slice::<f64>().unwrap()[..3], &[1.0, 2.0, 0.5]);
+        assert!(buf.get::<f64>(3).unwrap().is_nan());
+
+        let options = InferOptions::new().integers(false).single_precision(true);
+        let (buf, ty) = VecCopy::parse_auto_with(vec!["1", "0.1", "-inf"], &options).unwrap();
+        assert_eq!(ty, ScalarType::F32);
+        assert_eq!(
+            buf.as_slice::<f32>().unwrap(),
+            &[1.0, 0.1, f32::NEG_INFINITY]
+        );
+        for token in &["0.123456789", "16777217", "1e300"] {
+            let (_, ty) = VecCopy::parse_auto_with(vec!["0.5", token], &options).unwrap();
+            assert_eq!(ty, ScalarType::F64);
+        }
+        let (_, ty) = VecCopy::parse_auto_with(Vec::new(), &options).unwrap();
+        assert_eq!(ty, ScalarType::F32);
+
+        assert_eq!(
+            VecCopy::parse_auto(vec!["1", "2.0", "x"]).unwrap_err(),
+            ParseError {
+                row: 2,
+                token: String::from("x"),
+                ty: ScalarType::F64,
+            }
+        );
+    }
+}
@@ -0,0 +1,166 @@
+//! This module defines a pool of buffers for reusing allocations.
+//!
+//! Pipelines that allocate large temporary buffers on every iteration, for instance once per
+//! frame, spend much of their time in the allocator. A `BufferPool` keeps the allocations of
+//! buffers that are no longer needed, and hands them back out cleared when a buffer of the same
+//! element type is requested.
+
+use std::any::TypeId;
+use std::collections::HashMap;
+
+use crate::vtable::CopyVTable;
+use crate::{Elem, VecCopy};
+
+/// A collection of empty buffers with retained capacity, keyed by element type.
+///
+/// # Examples
+/// ```
+/// use data_buffer::BufferPool;
+/// let mut pool = BufferPool::new();
+/// for frame in 0..3 {
+///     let mut buf = pool.get_with_capacity::<f32>(1000);
+///     buf.extend((0..1000).map(|i| (i * frame) as f32));
+///     // ...
+///     pool.recycle(buf);
+/// }
+/// // The same allocation was reused for every frame.
+/// assert_eq!(pool.len(), 1);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct BufferPool {
+    buffers: HashMap<TypeId, Vec<VecCopy>>,
+}
+
+impl BufferPool {
+    /// Construct an empty pool.
+    #[inline]
+    pub fn new() -> Self {
+        BufferPool::default()
+    }
+
+    /// Get the number of buffers stored in this pool.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.buffers.values().map(Vec::len).sum()
+    }
+
+    /// Check if there are any buffers stored in this pool.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the total capacity in bytes of all buffers stored in this pool.
+    #[inline]
+    pub fn capacity_bytes(&self) -> usize {
+        self.buffers
+            .values()
+            .flatten()
+            .map(|buf| buf.capacity() * buf.element_size())
+            .sum()
+    }
+
+    /// Drop all buffers stored in this pool, releasing their memory.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.buffers.clear();
+    }
+
+    /// Get an empty buffer of elements of type `T`.
+    ///
+    /// A buffer from the pool is reused if there is one, otherwise a new buffer is constructed.
+    /// The returned buffer doesn't carry any functions captured by the buffer it reuses.
+    #[inline]
+    pub fn get<T: Elem>(&mut self) -> VecCopy {
+        self.get_with_capacity::<T>(0)
+    }
+
+    /// Get an empty buffer of elements of type `T` with capacity for at least `n` elements.
+    ///
+    /// The pooled buffer with the smallest sufficient capacity is reused if there is one.
+    /// Otherwise the largest pooled buffer is grown, or a new buffer is constructed if there are
+    /// no buffers of type `T` in the pool.
+    pub fn get_with_capacity<T: Elem>(&mut self, n: usize) -> VecCopy {
+        let pooled = self
+            .buffers
+            .get_mut(&TypeId::of::<T>())
+            .and_then(|buffers| {
+                let best = buffers
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, buf)| buf.capacity() >= n)
+                    .min_by_key(|(_, buf)| buf.capacity())
+                    .or_else(|| {
+                        buffers
+                            .iter()
+                            .enumerate()
+                            .max_by_key(|(_, buf)| buf.capacity())
+                    })
+                    .map(|(i, _)| i)?;
+                Some(buffers.swap_remove(best))
+            });
+        match pooled {
+            Some(mut buf) => {
+                buf.vtable = CopyVTable::of_elem::<T>();
+                buf.reserve(n);
+                buf
+            }
+            None => VecCopy::with_capacity::<T>(n),
+        }
+    }
+
+    /// Return a buffer to this pool, so its allocation can be reused.
+    ///
+    /// The buffer is cleared, retaining its capacity. Buffers without an allocation are dropped.
+    pub fn recycle(&mut self, mut buf: VecCopy) {
+        if buf.capacity() == 0 {
+            return;
+        }
+        buf.clear();
+        self.buffers
+            .entry(buf.element_type_id())
+            .or_default()
+            .push(buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buffer_pool_test() {
+        let mut pool = BufferPool::new();
+        assert!(pool.is_empty());
+        pool.recycle(VecCopy::with_type::<u8>());
+        assert!(pool.is_empty());
+
+        let small = VecCopy::from_vec(vec![1u32; 10]);
+        let large = VecCopy::from_vec(vec![2u32; 100]);
+        let large_ptr = large.as_bytes().as_ptr();
+        pool.recycle(small);
+        pool.recycle(large);
+        pool.recycle(VecCopy::from_vec(vec![3u64; 50]));
+        assert_eq!(pool.len(), 3);
+        assert_eq!(pool.capacity_bytes(), 110 * 4 + 50 * 8);
+
+        // The smallest buffer with enough capacity is reused.
+        let buf = pool.get_with_capacity::<u32>(20);
+        assert!(buf.is_empty());
+        assert_eq!(buf.as_bytes().as_ptr(), large_ptr);
+        assert_eq!(buf.capacity(), 100);
+        // Otherwise the largest buffer is grown.
+        let buf = pool.get_with_capacity::<u32>(200);
+        assert!(buf.capacity() >= 200);
+        assert_eq!(pool.len(), 1);
+
+        let buf = pool.get::<i8>();
+        assert_eq!(buf.capacity(), 0);
+        assert_eq!(pool.get::<u64>().capacity(), 50);
+        assert!(pool.is_empty());
+
+        pool.recycle(VecCopy::from_vec(vec![1u8]));
+        pool.clear();
+        assert!(pool.is_empty());
+    }
+}
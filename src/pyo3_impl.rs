@@ -0,0 +1,226 @@
+//! This module exposes buffers to Python through `pyo3`.
+//!
+//! A [`PyVecCopy`] wraps a buffer in a Python object implementing the buffer protocol, so its
+//! data can be viewed from Python without copying, for instance with `memoryview` or
+//! `numpy.frombuffer`. Buffers of `bool`, fixed size integer and floating point types are exposed
+//! with their element type, while all other buffers are exposed as raw bytes.
+//!
+//! The wrapped buffer can't be modified once it is shared with Python, since views into its data
+//! may outlive any borrow of the Python object.
+
+use std::any::TypeId;
+use std::ffi::c_void;
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+
+use numpy::{Element, PyArrayDyn, PyArrayMethods, PyUntypedArray, PyUntypedArrayMethods};
+use pyo3::exceptions::{PyBufferError, PyTypeError};
+use pyo3::ffi;
+use pyo3::prelude::*;
+
+use crate::VecCopy;
+
+/// Get the `struct` module format string of the type with the given `TypeId`.
+fn buffer_format(type_id: TypeId) -> Option<&'static [u8]> {
+    macro_rules! formats {
+        ($($t:ty => $format:expr),*) => {
+            $(
+                if type_id == TypeId::of::<$t>() {
+                    return Some($format);
+                }
+            )*
+        };
+    }
+    formats!(
+        bool => b"?\0", i8 => b"b\0", i16 => b"h\0", i32 => b"i\0", i64 => b"q\0",
+        u8 => b"B\0", u16 => b"H\0", u32 => b"I\0", u64 => b"Q\0", f32 => b"f\0", f64 => b"d\0"
+    );
+    None
+}
+
+impl VecCopy {
+    /// Construct a buffer by copying the elements of the given NumPy array.
+    ///
+    /// Multidimensional arrays are flattened in row-major order. Arrays of `bool`, fixed size
+    /// integer and floating point types in native byte order are supported, otherwise a
+    /// `TypeError` is returned.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use data_buffer::VecCopy;
+    /// use numpy::{PyArray1, PyArrayMethods};
+    /// use pyo3::Python;
+    /// Python::attach(|py| {
+    ///     let array = PyArray1::from_slice(py, &[1.0f32, 2.0, 3.0]);
+    ///     let buf = VecCopy::from_numpy(array.as_untyped()).unwrap();
+    ///     assert_eq!(buf.as_slice::<f32>().unwrap(), &[1.0, 2.0, 3.0]);
+    /// });
+    /// ```
+    pub fn from_numpy(array: &Bound<'_, PyUntypedArray>) -> PyResult<VecCopy> {
+        fn copy<T: Element + Copy + PartialEq + 'static>(
+            array: &Bound<'_, PyUntypedArray>,
+        ) -> Option<VecCopy> {
+            let array = array.cast::<PyArrayDyn<T>>().ok()?.readonly();
+            let vec = match array.as_slice() {
+                Ok(slice) => slice.to_vec(),
+                Err(_) => array.as_array().iter().copied().collect(),
+            };
+            Some(VecCopy::from_vec(vec))
+        }
+        copy::<bool>(array)
+            .or_else(|| copy::<i8>(array))
+            .or_else(|| copy::<i16>(array))
+            .or_else(|| copy::<i32>(array))
+            .or_else(|| copy::<i64>(array))
+            .or_else(|| copy::<u8>(array))
+            .or_else(|| copy::<u16>(array))
+            .or_else(|| copy::<u32>(array))
+            .or_else(|| copy::<u64>(array))
+            .or_else(|| copy::<f32>(array))
+            .or_else(|| copy::<f64>(array))
+            .ok_or_else(|| {
+                PyTypeError::new_err(format!("unsupported array dtype `{}`", array.dtype()))
+            })
+    }
+}
+
+/// A buffer shared with Python.
+///
+/// This can be registered with a Python module like any other `pyo3` class. From Python, it can
+/// be constructed from a NumPy array and supports `len` and the buffer protocol.
+#[pyclass(name = "VecCopy", module = "data_buffer", frozen)]
+#[derive(Clone, Debug)]
+pub struct PyVecCopy(VecCopy);
+
+impl PyVecCopy {
+    /// Get a reference to the wrapped buffer.
+    #[inline]
+    pub fn as_vec_copy(&self) -> &VecCopy {
+        &self.0
+    }
+
+    /// Unwrap the buffer.
+    #[inline]
+    pub fn into_inner(self) -> VecCopy {
+        self.0
+    }
+}
+
+impl From<VecCopy> for PyVecCopy {
+    #[inline]
+    fn from(buf: VecCopy) -> Self {
+        PyVecCopy(buf)
+    }
+}
+
+#[pymethods]
+impl PyVecCopy {
+    #[new]
+    fn new(array: &Bound<'_, PyUntypedArray>) -> PyResult<Self> {
+        VecCopy::from_numpy(array).map(PyVecCopy)
+    }
+
+    fn __len__(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Export the buffer data read-only.
+    ///
+    /// # Safety
+    ///
+    /// `view` must be a valid pointer to a `Py_buffer`, as guaranteed by the Python interpreter.
+    unsafe fn __getbuffer__(
+        slf: Bound<'_, Self>,
+        view: *mut ffi::Py_buffer,
+        flags: c_int,
+    ) -> PyResult<()> {
+        if view.is_null() {
+            return Err(PyBufferError::new_err("view is null"));
+        }
+        if flags & ffi::PyBUF_WRITABLE == ffi::PyBUF_WRITABLE {
+            return Err(PyBufferError::new_err("buffer is not writable"));
+        }
+        let buf = &slf.get().0;
+        let bytes = buf.as_bytes();
+        // Elements without a format are exposed as bytes.
+        let (format, itemsize) = match buffer_format(buf.element_type_id()) {
+            Some(format) => (format, buf.element_size()),
+            None => (&b"B\0"[..], 1),
+        };
+
+        (*view).buf = bytes.as_ptr() as *mut c_void;
+        (*view).len = bytes.len() as isize;
+        (*view).readonly = 1;
+        (*view).itemsize = itemsize as isize;
+        (*view).format = if flags & ffi::PyBUF_FORMAT == ffi::PyBUF_FORMAT {
+            format.as_ptr() as *mut c_char
+        } else {
+            ptr::null_mut()
+        };
+        (*view).ndim = 1;
+        // The shape is allocated here and freed in `__releasebuffer__`.
+        (*view).internal = if flags & ffi::PyBUF_ND == ffi::PyBUF_ND {
+            let len = bytes.len() / itemsize;
+            Box::into_raw(Box::new(len as isize)) as *mut c_void
+        } else {
+            ptr::null_mut()
+        };
+        (*view).shape = (*view).internal as *mut isize;
+        (*view).strides = if flags & ffi::PyBUF_STRIDES == ffi::PyBUF_STRIDES {
+            &mut (*view).itemsize
+        } else {
+            ptr::null_mut()
+        };
+        (*view).suboffsets = ptr::null_mut();
+        // The view keeps the buffer alive.
+        (*view).obj = slf.into_any().into_ptr();
+        Ok(())
+    }
+
+    /// Release a view created by `__getbuffer__`.
+    ///
+    /// # Safety
+    ///
+    /// `view` must have been filled by `__getbuffer__`.
+    unsafe fn __releasebuffer__(&self, view: *mut ffi::Py_buffer) {
+        if !(*view).internal.is_null() {
+            drop(Box::from_raw((*view).internal as *mut isize));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pyo3::types::PyDict;
+
+    #[test]
+    fn buffer_protocol_test() {
+        // The interpreter is initialized explicitly, so tests without the `pyo3` feature don't
+        // need to link against Python.
+        Python::initialize();
+        Python::attach(|py| {
+            let locals = PyDict::new(py);
+            let buf = VecCopy::from_vec(vec![1.5f64, -2.0, 3.25]);
+            locals.set_item("buf", PyVecCopy::from(buf)).unwrap();
+            let raw = VecCopy::from_vec(vec![[1u8, 2]; 2]);
+            locals.set_item("raw", PyVecCopy::from(raw)).unwrap();
+            py.run(
+                ffi::c_str!(
+                    "
+view = memoryview(buf)
+assert len(buf) == 3
+assert view.readonly
+assert (view.format, view.itemsize, view.shape) == ('d', 8, (3,))
+assert view.tolist() == [1.5, -2.0, 3.25]
+assert memoryview(raw).tolist() == [1, 2, 1, 2]
+assert raw.__class__.__name__ == 'VecCopy'
+"
+                ),
+                None,
+                Some(&locals),
+            )
+            .unwrap();
+        });
+    }
+}
@@ -0,0 +1,436 @@
+//! This module implements a minimal self-describing binary format for buffers.
+//!
+//! The format consists of a fixed size header followed by the raw element bytes:
+//!
+//! | Offset | Size | Content                                             |
+//! |--------|------|-----------------------------------------------------|
+//! | 0      | 4    | Magic bytes `DBUF`                                  |
+//! | 4      | 2    | Format version (currently 1)                        |
+//! | 6      | 1    | Endianness of the data (0 for little, 1 for big)    |
+//! | 7      | 1    | Flags (see below)                                   |
+//! | 8      | 8    | `StableTypeId` of the element type                  |
+//! | 16     | 8    | Element size in bytes                               |
+//! | 24     | 8    | Number of elements                                  |
+//! | 32     |      | Element bytes in the byte order given in the header |
+//!
+//! All header integers are stored in little endian byte order.
+//!
+//! The lower 4 bits of the flags hold the compression method (0 for none, 1 for zstd).
+//! Compressed data (see `encode_compressed`) is preceded by its size in bytes as an additional 8
+//! byte integer, so the header can be inspected without decompressing the data.
+//!
+//! If bit 4 of the flags is set, the element bytes are followed by their CRC-32 checksum as a
+//! little endian 4 byte integer. For compressed data, the checksum is compressed together with
+//! the element bytes. The remaining bits of the flags are reserved and must be zero.
+
+use std::convert::TryFrom;
+use std::io::{self, Read, Write};
+
+use crate::aligned_vec::{self, AlignedVec};
+use crate::checksum;
+use crate::endian::{self, Endianness};
+use crate::stable_id::RegisteredType;
+use crate::trace::trace_span;
+use crate::{DecodeError, StableTypeId, TypeName, VecCopy};
+
+/// The magic bytes identifying the raw binary format.
+const MAGIC: [u8; 4] = *b"DBUF";
+/// The current version of the raw binary format.
+const VERSION: u16 = 1;
+/// The size of the header in bytes.
+pub(crate) const HEADER_SIZE: usize = 32;
+/// The bits of the header flags holding the compression method.
+const COMPRESSION_MASK: u8 = 0x0F;
+/// The header flag indicating that the data is followed by a checksum.
+pub(crate) const CHECKSUM_FLAG: u8 = 0x10;
+/// The bits of the header flags that are reserved for future use.
+const RESERVED_FLAGS: u8 = !(COMPRESSION_MASK | CHECKSUM_FLAG);
+/// The compression method tag of uncompressed data.
+pub(crate) const COMPRESSION_NONE: u8 = 0;
+/// The compression method tag of data compressed with zstd.
+#[cfg(feature = "zstd")]
+pub(crate) const COMPRESSION_ZSTD: u8 = 1;
+
+/// The decoded header of the raw binary format.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct Header {
+    pub(crate) endianness: Endianness,
+    pub(crate) compression: u8,
+    pub(crate) checksum: bool,
+    pub(crate) element_type: StableTypeId,
+    pub(crate) element_size: usize,
+    pub(crate) len: u64,
+}
+
+impl Header {
+    /// Decode and validate the header at the start of the raw binary format.
+    pub(crate) fn decode(header: &[u8; HEADER_SIZE]) -> Result<Header, DecodeError> {
+        let u64_at = |offset: usize| {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(&header[offset..offset + 8]);
+            u64::from_le_bytes(bytes)
+        };
+        if header[0..4] != MAGIC {
+            return Err(DecodeError::InvalidMagic);
+        }
+        let version = u16::from_le_bytes([header[4], header[5]]);
+        if version != VERSION {
+            return Err(DecodeError::UnsupportedVersion(version));
+        }
+        let endianness = match header[6] {
+            0 => Endianness::Little,
+            1 => Endianness::Big,
+            tag => return Err(DecodeError::InvalidEndianness(tag)),
+        };
+        if header[7] & RESERVED_FLAGS != 0 {
+            return Err(DecodeError::UnsupportedFlags(header[7]));
+        }
+        let element_size = u64_at(16);
+        Ok(Header {
+            endianness,
+            compression: header[7] & COMPRESSION_MASK,
+            checksum: header[7] & CHECKSUM_FLAG != 0,
+            element_type: StableTypeId::from_u64(u64_at(8)),
+            element_size: usize::try_from(element_size)
+                .map_err(|_| DecodeError::ElementSizeOverflow(element_size))?,
+            len: u64_at(24),
+        })
+    }
+
+    /// Decode and validate the header of uncompressed data in the raw binary format.
+    pub(crate) fn decode_uncompressed(header: &[u8; HEADER_SIZE]) -> Result<Header, DecodeError> {
+        let header = Header::decode(header)?;
+        if header.compression != COMPRESSION_NONE {
+            return Err(DecodeError::UnsupportedCompression(header.compression));
+        }
+        Ok(header)
+    }
+
+    /// The number of data bytes following the header.
+    pub(crate) fn num_bytes(&self) -> Result<u64, DecodeError> {
+        self.len
+            .checked_mul(self.element_size as u64)
+            .ok_or(DecodeError::InvalidLength {
+                len: self.len,
+                element_size: self.element_size,
+            })
+    }
+
+    /// The number of bytes following the header, including the checksum.
+    pub(crate) fn payload_size(&self) -> Result<u64, DecodeError> {
+        let num_bytes = self.num_bytes()?;
+        if self.checksum {
+            num_bytes.checked_add(4).ok_or(DecodeError::InvalidLength {
+                len: self.len,
+                element_size: self.element_size,
+            })
+        } else {
+            Ok(num_bytes)
+        }
+    }
+
+    /// Verify the checksum following the element bytes in the given payload, if this header
+    /// indicates that there is one.
+    ///
+    /// The payload must hold at least `payload_size()` bytes.
+    pub(crate) fn verify_checksum(&self, payload: &[u8]) -> Result<(), DecodeError> {
+        if self.checksum {
+            let num_bytes = self.num_bytes()? as usize;
+            let mut expected = [0u8; 4];
+            expected.copy_from_slice(&payload[num_bytes..num_bytes + 4]);
+            checksum::verify(&payload[..num_bytes], u32::from_le_bytes(expected))?;
+        }
+        Ok(())
+    }
+}
+
+impl VecCopy {
+    /// Write this buffer to the given writer in the raw binary format.
+    ///
+    /// The data is written in the byte order of the target platform, which is recorded in the
+    /// header. The element type must be registered with [`register_type`], otherwise an error of
+    /// kind `InvalidInput` is returned.
+    ///
+    /// [`register_type`]: crate::register_type
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let buf = VecCopy::from_vec(vec![1.0f32, 2.0, 3.0]);
+    /// let mut bytes = Vec::new();
+    /// buf.encode_raw(&mut bytes).unwrap();
+    /// assert_eq!(bytes.len(), 32 + 12);
+    /// let decoded = VecCopy::decode_raw(&mut bytes.as_slice()).unwrap();
+    /// assert_eq!(decoded.as_slice::<f32>().unwrap(), &[1.0, 2.0, 3.0]);
+    /// ```
+    pub fn encode_raw<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        trace_span!("encode_raw", num_bytes = self.data.len());
+        writer.write_all(&self.raw_header()?)?;
+        writer.write_all(&self.data)
+    }
+
+    /// Write this buffer to the given writer in the raw binary format followed by a checksum of
+    /// the data.
+    ///
+    /// The checksum is verified by [`decode_raw`] to detect corrupted data.
+    ///
+    /// [`decode_raw`]: VecCopy::decode_raw
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::{DecodeError, VecCopy};
+    /// let buf = VecCopy::from_vec(vec![1.0f32, 2.0, 3.0]);
+    /// let mut bytes = Vec::new();
+    /// buf.encode_raw_with_checksum(&mut bytes).unwrap();
+    /// bytes[40] ^= 1;
+    /// let err = VecCopy::decode_raw(&mut bytes.as_slice()).unwrap_err();
+    /// let err = err.into_inner().unwrap().downcast::<DecodeError>().unwrap();
+    /// assert!(matches!(*err, DecodeError::CorruptData { .. }));
+    /// ```
+    pub fn encode_raw_with_checksum<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        trace_span!("encode_raw", num_bytes = self.data.len());
+        let mut header = self.raw_header()?;
+        header[7] |= CHECKSUM_FLAG;
+        writer.write_all(&header)?;
+        writer.write_all(&self.data)?;
+        writer.write_all(&checksum::crc32(&self.data).to_le_bytes())
+    }
+
+    /// Build the header of the raw binary format for this buffer.
+    pub(crate) fn raw_header(&self) -> io::Result<[u8; HEADER_SIZE]> {
+        let element_type = self.stable_type_id().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "element type `{}` is not registered",
+                    self.element_type_name()
+                ),
+            )
+        })?;
+        let mut header = [0u8; HEADER_SIZE];
+        header[0..4].copy_from_slice(&MAGIC);
+        header[4..6].copy_from_slice(&VERSION.to_le_bytes());
+        header[6] = match Endianness::NATIVE {
+            Endianness::Little => 0,
+            Endianness::Big => 1,
+        };
+        header[8..16].copy_from_slice(&element_type.as_u64().to_le_bytes());
+        header[16..24].copy_from_slice(&(self.element_size as u64).to_le_bytes());
+        header[24..32].copy_from_slice(&(self.len() as u64).to_le_bytes());
+        Ok(header)
+    }
+
+    /// Read a buffer in the raw binary format from the given reader.
+    ///
+    /// The element type must be registered with [`register_type`]. If the data was written on a
+    /// platform with a different byte order, the elements are converted using the scalar size
+    /// of the registered type.
+    ///
+    /// Malformed or incompatible data produces an error of kind `InvalidData` wrapping a
+    /// [`DecodeError`], and `UnexpectedEof` is returned if the data is truncated. If the data is
+    /// followed by a checksum, it is verified and a mismatch is reported as
+    /// [`DecodeError::CorruptData`].
+    ///
+    /// [`register_type`]: crate::register_type
+    pub fn decode_raw<R: Read>(reader: &mut R) -> io::Result<VecCopy> {
+        let invalid = |err: DecodeError| io::Error::new(io::ErrorKind::InvalidData, err);
+        let mut header = [0u8; HEADER_SIZE];
+        reader.read_exact(&mut header)?;
+        let header = Header::decode_uncompressed(&header).map_err(invalid)?;
+        VecCopy::decode_raw_data(&header, reader)
+    }
+
+    /// Read the data following the given header in the raw binary format.
+    pub(crate) fn decode_raw_data<R: Read>(header: &Header, reader: &mut R) -> io::Result<VecCopy> {
+        let invalid = |err: DecodeError| io::Error::new(io::ErrorKind::InvalidData, err);
+        // Validate the type before reading any data.
+        registered_type(header.element_type, header.element_size).map_err(invalid)?;
+        let payload_size = header.payload_size().map_err(invalid)?;
+        trace_span!("decode_raw", num_bytes = payload_size);
+        // Don't trust the header with the size of the allocation.
+        let mut payload = Vec::new();
+        reader.take(payload_size).read_to_end(&mut payload)?;
+        VecCopy::decode_raw_payload(header, payload)
+    }
+
+    /// Decode the bytes following the given header in the raw binary format.
+    ///
+    /// The payload must be read with at most `header.payload_size()` bytes, and is checked to be
+    /// complete.
+    pub(crate) fn decode_raw_payload(header: &Header, mut data: Vec<u8>) -> io::Result<VecCopy> {
+        let invalid = |err: DecodeError| io::Error::new(io::ErrorKind::InvalidData, err);
+        if (data.len() as u64) < header.payload_size().map_err(invalid)? {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        header.verify_checksum(&data).map_err(invalid)?;
+        if header.checksum {
+            data.truncate(data.len() - 4);
+        }
+        VecCopy::from_portable_bytes(
+            data,
+            header.element_size,
+            header.element_type,
+            header.endianness,
+        )
+        .map_err(invalid)
+    }
+
+    /// Construct a buffer from bytes of a registered type, which were produced on a platform
+    /// with the given byte order.
+    pub(crate) fn from_portable_bytes(
+        mut data: Vec<u8>,
+        element_size: usize,
+        element_type: StableTypeId,
+        endianness: Endianness,
+    ) -> Result<VecCopy, DecodeError> {
+        let ty = registered_type(element_type, element_size)?;
+        if !data.len().is_multiple_of(ty.size) {
+            return Err(DecodeError::InvalidLength {
+                len: data.len() as u64,
+                element_size,
+            });
+        }
+        if endianness != Endianness::NATIVE {
+            match ty.scalar_size {
+                Some(scalar_size) if ty.size % scalar_size == 0 => {
+                    endian::swap_bytes(&mut data, scalar_size)
+                }
+                _ => {
+                    return Err(DecodeError::UnknownScalarLayout {
+                        type_name: ty.name,
+                        endianness,
+                    })
+                }
+            }
+        }
+        let align = ty.align.max(aligned_vec::max_align_of_size(ty.size));
+        Ok(VecCopy {
+            data: AlignedVec::from_slice(&data, align),
+            element_size: ty.size,
+            element_type_id: ty.type_id,
+            element_type_name: TypeName(Some(ty.name)),
+            vtable: ty.vtable,
+        })
+    }
+}
+
+/// Get the registered type with the given identifier, and check that it has the given size.
+pub(crate) fn registered_type(
+    element_type: StableTypeId,
+    element_size: usize,
+) -> Result<RegisteredType, DecodeError> {
+    let ty = element_type
+        .registered()
+        .ok_or(DecodeError::UnregisteredType(element_type))?;
+    if ty.size != element_size {
+        return Err(DecodeError::ElementSizeMismatch {
+            type_name: ty.name,
+            expected: ty.size,
+            found: element_size,
+        });
+    }
+    Ok(ty)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{register_type, StableType};
+
+    #[test]
+    fn raw_round_trip_test() {
+        let buf = VecCopy::from_vec(vec![1u16, 0x0102]);
+        let mut bytes = Vec::new();
+        buf.encode_raw(&mut bytes).unwrap();
+        assert_eq!(&bytes[0..4], b"DBUF");
+        assert_eq!(VecCopy::decode_raw(&mut bytes.as_slice()).unwrap(), buf);
+
+        // Data from a platform with a different byte order is converted.
+        let mut foreign = bytes.clone();
+        foreign[6] ^= 1;
+        let swapped = VecCopy::decode_raw(&mut foreign.as_slice()).unwrap();
+        assert_eq!(swapped.as_slice::<u16>().unwrap(), &[0x0100, 0x0201]);
+
+        // Truncated data.
+        let err = VecCopy::decode_raw(&mut &bytes[..bytes.len() - 1]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+
+        // Corrupted headers.
+        let decode_err = |bytes: &[u8]| {
+            let err = VecCopy::decode_raw(&mut &bytes[..]).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+            *err.into_inner().unwrap().downcast::<DecodeError>().unwrap()
+        };
+        let mut corrupt = bytes.clone();
+        corrupt[0] = b'X';
+        assert_eq!(decode_err(&corrupt), DecodeError::InvalidMagic);
+        let mut corrupt = bytes.clone();
+        corrupt[4] = 2;
+        assert_eq!(decode_err(&corrupt), DecodeError::UnsupportedVersion(2));
+        let mut corrupt = bytes.clone();
+        corrupt[7] = 0x80;
+        assert_eq!(decode_err(&corrupt), DecodeError::UnsupportedFlags(0x80));
+        let mut corrupt = bytes.clone();
+        corrupt[16] = 4;
+        assert_eq!(
+            decode_err(&corrupt),
+            DecodeError::ElementSizeMismatch {
+                type_name: "u16",
+                expected: 2,
+                found: 4
+            }
+        );
+        let mut corrupt = bytes;
+        corrupt[8..16].copy_from_slice(&42u64.to_le_bytes());
+        assert_eq!(
+            decode_err(&corrupt),
+            DecodeError::UnregisteredType(StableTypeId::from_u64(42))
+        );
+    }
+
+    #[test]
+    fn raw_checksum_test() {
+        let buf = VecCopy::from_vec(vec![1u32, 2, 3]);
+        let mut bytes = Vec::new();
+        buf.encode_raw_with_checksum(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), HEADER_SIZE + 12 + 4);
+        assert_eq!(bytes[7], CHECKSUM_FLAG);
+        assert_eq!(VecCopy::decode_raw(&mut bytes.as_slice()).unwrap(), buf);
+
+        // Flipped bits in the data are detected.
+        let mut corrupt = bytes.clone();
+        corrupt[HEADER_SIZE + 5] ^= 0x20;
+        let err = VecCopy::decode_raw(&mut corrupt.as_slice()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            *err.into_inner().unwrap().downcast::<DecodeError>().unwrap(),
+            DecodeError::CorruptData {
+                expected: checksum::crc32(buf.as_bytes()),
+                found: checksum::crc32(&corrupt[HEADER_SIZE..HEADER_SIZE + 12]),
+            }
+        );
+
+        // A missing checksum is reported as truncated data.
+        let err = VecCopy::decode_raw(&mut &bytes[..bytes.len() - 1]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn raw_unregistered_test() {
+        #[derive(Copy, Clone, Debug, PartialEq)]
+        struct Pair(u8, u8);
+
+        let buf = VecCopy::from_vec(vec![Pair(1, 2)]);
+        let err = buf.encode_raw(&mut Vec::new()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+        unsafe impl StableType for Pair {
+            const STABLE_NAME: &'static str = "data_buffer::raw::tests::Pair";
+        }
+        register_type::<Pair>();
+        let mut bytes = Vec::new();
+        buf.encode_raw(&mut bytes).unwrap();
+        let decoded = VecCopy::decode_raw(&mut bytes.as_slice()).unwrap();
+        assert_eq!(decoded.as_slice::<Pair>().unwrap(), &[Pair(1, 2)]);
+    }
+}
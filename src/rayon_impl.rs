@@ -0,0 +1,191 @@
+//! This module implements parallel iteration over buffers with `rayon`.
+
+use std::any::Any;
+#[cfg(feature = "numeric")]
+use std::any::TypeId;
+use std::cmp::Ordering;
+
+#[cfg(feature = "numeric")]
+use num_traits::{cast, NumCast, Zero};
+use rayon::prelude::*;
+use rayon::slice::{ChunksMut, Iter, IterMut};
+
+#[cfg(feature = "numeric")]
+use crate::Elem;
+use crate::VecCopy;
+
+/// The minimum number of elements converted by a single task.
+///
+/// Converting an element is cheap, so smaller tasks are dominated by scheduling overhead.
+#[cfg(feature = "numeric")]
+const MIN_CAST_CHUNK_LEN: usize = 1 << 14;
+
+impl VecCopy {
+    /// Return a parallel iterator over the typed elements of this buffer.
+    ///
+    /// Returns `None` if the given type `T` doesn't match the internal.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// use rayon::prelude::*;
+    /// let buf = VecCopy::from_vec((0..1000u64).collect::<Vec<_>>());
+    /// let sum: u64 = buf.par_iter::<u64>().unwrap().sum();
+    /// assert_eq!(sum, 499_500);
+    /// ```
+    #[inline]
+    pub fn par_iter<T: Any + Sync>(&self) -> Option<Iter<'_, T>> {
+        self.as_slice::<T>().map(|x| x.par_iter())
+    }
+
+    /// Return a parallel iterator over mutable references to the typed elements of this buffer.
+    ///
+    /// Returns `None` if the given type `T` doesn't match the internal.
+    #[inline]
+    pub fn par_iter_mut<T: Any + Send>(&mut self) -> Option<IterMut<'_, T>> {
+        self.as_mut_slice::<T>().map(|x| x.par_iter_mut())
+    }
+
+    /// Return a parallel iterator over non-overlapping mutable chunks of `chunk_size` typed
+    /// elements of this buffer.
+    ///
+    /// If the length of the buffer is not divisible by `chunk_size`, the last chunk is shorter.
+    /// Returns `None` if the given type `T` doesn't match the internal.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `chunk_size` is zero.
+    #[inline]
+    pub fn par_chunks_mut<T: Any + Send>(&mut self, chunk_size: usize) -> Option<ChunksMut<'_, T>> {
+        self.as_mut_slice::<T>()
+            .map(|x| x.par_chunks_mut(chunk_size))
+    }
+
+    /// Sort the typed elements of this buffer in parallel.
+    ///
+    /// The sort is stable. Returns `None` if the given type `T` doesn't match the internal.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let mut buf = VecCopy::from_vec(vec![3u32, 1, 2]);
+    /// buf.par_sort::<u32>().unwrap();
+    /// assert_eq!(buf.as_slice::<u32>().unwrap(), &[1, 2, 3]);
+    /// ```
+    #[inline]
+    pub fn par_sort<T: Any + Ord + Send>(&mut self) -> Option<&mut Self> {
+        self.as_mut_slice::<T>()?.par_sort();
+        Some(self)
+    }
+
+    /// Sort the typed elements of this buffer in parallel with the given comparator function.
+    ///
+    /// The sort is stable. Returns `None` if the given type `T` doesn't match the internal.
+    #[inline]
+    pub fn par_sort_by<T, F>(&mut self, compare: F) -> Option<&mut Self>
+    where
+        T: Any + Send,
+        F: Fn(&T, &T) -> Ordering + Sync,
+    {
+        self.as_mut_slice::<T>()?.par_sort_by(compare);
+        Some(self)
+    }
+
+    #[cfg(feature = "numeric")]
+    /// Cast a numeric `VecCopy` into the given output `Vec` type, converting elements in
+    /// parallel.
+    ///
+    /// This produces the same result as `cast_into_vec`, but is much faster for large buffers.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let buf = VecCopy::from_vec(vec![1u8, 2, 3]);
+    /// assert_eq!(buf.par_cast_into_vec::<f32>(), vec![1.0f32, 2.0, 3.0]);
+    /// ```
+    pub fn par_cast_into_vec<T>(self) -> Vec<T>
+    where
+        T: Elem + NumCast + Zero + Send,
+    {
+        // Helper function (generic on the input) to convert the given VecCopy into Vec.
+        unsafe fn convert_into_vec<I, O>(buf: VecCopy) -> Vec<O>
+        where
+            I: Any + Copy + NumCast + Sync,
+            O: Elem + NumCast + Zero + Send,
+        {
+            debug_assert_eq!(buf.element_type_id(), TypeId::of::<I>()); // Check invariant.
+            buf.reinterpret_as_slice::<I>()
+                .par_iter()
+                .with_min_len(MIN_CAST_CHUNK_LEN)
+                .map(|&elem| cast(elem).unwrap_or(O::zero()))
+                .collect()
+        }
+        call_numeric_buffer_fn!( convert_into_vec::<_,T>(self) or { Vec::new() } )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn par_iter_test() {
+        let mut buf = VecCopy::from_vec((0..10_000u32).collect::<Vec<_>>());
+        assert!(buf.par_iter::<i32>().is_none());
+        assert!(buf.par_iter_mut::<i32>().is_none());
+
+        buf.par_iter_mut::<u32>().unwrap().for_each(|x| *x *= 2);
+        let iter = buf.par_iter::<u32>().unwrap();
+        assert_eq!(iter.len(), 10_000);
+        let evens = iter.enumerate().all(|(i, &x)| x == 2 * i as u32);
+        assert!(evens);
+    }
+
+    #[test]
+    fn par_chunks_mut_test() {
+        let mut buf = VecCopy::from_vec(vec![0u16; 10]);
+        assert!(buf.par_chunks_mut::<u8>(3).is_none());
+        buf.par_chunks_mut::<u16>(3)
+            .unwrap()
+            .enumerate()
+            .for_each(|(i, chunk)| chunk.iter_mut().for_each(|x| *x = i as u16));
+        assert_eq!(
+            buf.as_slice::<u16>().unwrap(),
+            &[0, 0, 0, 1, 1, 1, 2, 2, 2, 3]
+        );
+    }
+
+    #[test]
+    fn par_sort_test() {
+        let mut buf = VecCopy::from_vec((0..10_000u32).map(|i| (i * 7919) % 10_000).collect());
+        assert!(buf.par_sort::<i32>().is_none());
+        buf.par_sort::<u32>().unwrap();
+        assert!(buf
+            .as_slice::<u32>()
+            .unwrap()
+            .windows(2)
+            .all(|w| w[0] <= w[1]));
+
+        let mut buf = VecCopy::from_vec(vec![1.5f64, -2.0, 0.5]);
+        buf.par_sort_by::<f64, _>(|a, b| b.partial_cmp(a).unwrap())
+            .unwrap();
+        assert_eq!(buf.as_slice::<f64>().unwrap(), &[1.5, 0.5, -2.0]);
+    }
+
+    #[cfg(feature = "numeric")]
+    #[test]
+    fn par_cast_into_vec_test() {
+        let buf = VecCopy::from_vec((0..100_000i64).map(|i| i - 300).collect::<Vec<_>>());
+        assert_eq!(
+            buf.clone().par_cast_into_vec::<u8>(),
+            buf.clone().cast_into_vec::<u8>()
+        );
+        assert_eq!(
+            buf.clone().par_cast_into_vec::<f64>(),
+            buf.cast_into_vec::<f64>()
+        );
+        assert!(VecCopy::from_vec(vec!['a'])
+            .par_cast_into_vec::<f32>()
+            .is_empty());
+    }
+}
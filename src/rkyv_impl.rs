@@ -0,0 +1,261 @@
+//! This module implements zero-copy archiving of buffers with `rkyv`.
+//!
+//! The element bytes of an archived buffer are stored contiguously and aligned to the alignment of
+//! the buffer, so they can be viewed as a typed slice directly in the archive (e.g. a memory
+//! mapped file) without deserialization. Since the archive may have been produced by a different
+//! program, the element type is identified by its `StableTypeId`, which is checked together with
+//! the element size, byte order and alignment on every typed access.
+
+use std::mem::{align_of, size_of};
+
+use rkyv::bytecheck::CheckBytes;
+use rkyv::ser::Serializer;
+use rkyv::vec::{ArchivedVec, VecResolver};
+use rkyv::{out_field, Archive, Archived, Deserialize, Fallible, Serialize};
+
+use crate::{DecodeError, Endianness, StableType, StableTypeId, VecCopy};
+
+/// An archived `VecCopy`.
+#[derive(CheckBytes)]
+#[check_bytes(crate = "rkyv::bytecheck")]
+#[repr(C)]
+pub struct ArchivedVecCopy {
+    data: ArchivedVec<u8>,
+    element_size: ArchivedElementSize,
+    /// The stable identifier of the element type, or zero if the type was not registered.
+    element_type: Archived<u64>,
+    /// The byte order of the data: 0 for little and 1 for big endian.
+    endianness: u8,
+}
+
+/// An archived element size, which is checked to be non-zero on validation.
+#[repr(transparent)]
+struct ArchivedElementSize(Archived<usize>);
+
+impl<C: ?Sized> CheckBytes<C> for ArchivedElementSize {
+    type Error = DecodeError;
+
+    #[inline]
+    unsafe fn check_bytes<'a>(value: *const Self, _: &mut C) -> Result<&'a Self, DecodeError> {
+        // Any bit pattern is a valid integer.
+        let value = &*value;
+        if value.0 == 0 {
+            return Err(DecodeError::ZeroElementSize);
+        }
+        Ok(value)
+    }
+}
+
+/// The resolver for an archived `VecCopy`.
+pub struct VecCopyResolver {
+    data: VecResolver,
+    element_type: u64,
+}
+
+impl ArchivedVecCopy {
+    /// Get the number of elements stored in this buffer.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.data.len() / self.element_size()
+    }
+
+    /// Check if there are any elements stored in this buffer.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Get the size of the element type in bytes.
+    #[inline]
+    pub fn element_size(&self) -> usize {
+        self.element_size.0 as usize
+    }
+
+    /// Get the stable identifier of the element type.
+    ///
+    /// Returns `None` if the element type was not registered when the buffer was archived.
+    #[inline]
+    pub fn stable_type_id(&self) -> Option<StableTypeId> {
+        if self.element_type == 0 {
+            None
+        } else {
+            Some(StableTypeId::from_u64(self.element_type))
+        }
+    }
+
+    /// Get the byte order of the archived data.
+    ///
+    /// Returns `None` if the archived byte order is invalid.
+    #[inline]
+    pub fn endianness(&self) -> Option<Endianness> {
+        match self.endianness {
+            0 => Some(Endianness::Little),
+            1 => Some(Endianness::Big),
+            _ => None,
+        }
+    }
+
+    /// Get the archived element bytes.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        self.data.as_slice()
+    }
+
+    /// Borrow the archived elements as a typed slice without copying.
+    ///
+    /// Returns `None` if `T` doesn't match the archived type tag or element size, if the data
+    /// was archived with a foreign byte order, or if the data is not sufficiently aligned for
+    /// `T` in memory.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let buf = VecCopy::from_vec(vec![1.0f64, 2.0]);
+    /// let bytes = rkyv::to_bytes::<_, 256>(&buf).unwrap();
+    /// let archived = rkyv::check_archived_root::<VecCopy>(&bytes).unwrap();
+    /// assert_eq!(archived.as_slice::<f64>(), Some(&[1.0, 2.0][..]));
+    /// assert_eq!(archived.as_slice::<u64>(), None);
+    /// ```
+    #[inline]
+    pub fn as_slice<T: StableType>(&self) -> Option<&[T]> {
+        let bytes = self.as_bytes();
+        if self.stable_type_id() != Some(T::STABLE_ID)
+            || self.element_size() != size_of::<T>()
+            || self.endianness() != Some(Endianness::NATIVE)
+            || bytes.as_ptr() as usize % align_of::<T>() != 0
+        {
+            return None;
+        }
+        // This is safe since the type tag, size and alignment of the elements were checked above.
+        Some(unsafe { std::slice::from_raw_parts(bytes.as_ptr() as *const T, self.len()) })
+    }
+
+    /// Copy the archived buffer into a new `VecCopy`.
+    ///
+    /// The element type must be registered with [`register_type`] in the running program, and
+    /// data with a foreign byte order is converted as in `VecCopy::decode_raw`.
+    ///
+    /// [`register_type`]: crate::register_type
+    pub fn to_vec_copy(&self) -> Result<VecCopy, DecodeError> {
+        let element_type = self
+            .stable_type_id()
+            .ok_or(DecodeError::UnregisteredType(StableTypeId::from_u64(0)))?;
+        let endianness = self
+            .endianness()
+            .ok_or(DecodeError::InvalidEndianness(self.endianness))?;
+        VecCopy::from_portable_bytes(
+            self.as_bytes().to_vec(),
+            self.element_size(),
+            element_type,
+            endianness,
+        )
+    }
+}
+
+impl Archive for VecCopy {
+    type Archived = ArchivedVecCopy;
+    type Resolver = VecCopyResolver;
+
+    #[inline]
+    unsafe fn resolve(&self, pos: usize, resolver: VecCopyResolver, out: *mut ArchivedVecCopy) {
+        let (fp, fo) = out_field!(out.data);
+        ArchivedVec::resolve_from_len(self.data.len(), pos + fp, resolver.data, fo);
+        let (fp, fo) = out_field!(out.element_size);
+        self.element_size.resolve(pos + fp, (), fo.cast());
+        let (fp, fo) = out_field!(out.element_type);
+        resolver.element_type.resolve(pos + fp, (), fo);
+        let (fp, fo) = out_field!(out.endianness);
+        let endianness: u8 = match Endianness::NATIVE {
+            Endianness::Little => 0,
+            Endianness::Big => 1,
+        };
+        endianness.resolve(pos + fp, (), fo);
+    }
+}
+
+/// Buffers of types not registered with [`register_type`] are archived without a type tag, so
+/// their elements can't be accessed through the typed API of the archive.
+///
+/// [`register_type`]: crate::register_type
+impl<S: Serializer + ?Sized> Serialize<S> for VecCopy {
+    #[inline]
+    fn serialize(&self, serializer: &mut S) -> Result<VecCopyResolver, S::Error> {
+        // Align the element bytes to support zero-copy access of typed slices.
+        serializer.align(self.alignment())?;
+        // This is safe since the bytes are copied as is.
+        let data =
+            unsafe { ArchivedVec::serialize_copy_from_slice(self.data.as_slice(), serializer)? };
+        Ok(VecCopyResolver {
+            data,
+            element_type: self.stable_type_id().map_or(0, StableTypeId::as_u64),
+        })
+    }
+}
+
+impl<D> Deserialize<VecCopy, D> for ArchivedVecCopy
+where
+    D: Fallible + ?Sized,
+    D::Error: From<DecodeError>,
+{
+    #[inline]
+    fn deserialize(&self, _: &mut D) -> Result<VecCopy, D::Error> {
+        Ok(self.to_vec_copy()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aligned_vec::AlignedVec;
+
+    #[test]
+    fn archive_test() {
+        #[derive(Copy, Clone, Debug, PartialEq)]
+        #[repr(align(32))]
+        struct Aligned([u8; 32]);
+        unsafe impl StableType for Aligned {
+            const STABLE_NAME: &'static str = "data_buffer::rkyv_impl::tests::Aligned";
+        }
+
+        let buf = VecCopy::from_vec(vec![1u8, 2, 3]);
+        let bytes = rkyv::to_bytes::<_, 256>(&buf).unwrap();
+        let archived = rkyv::check_archived_root::<VecCopy>(&bytes).unwrap();
+        assert_eq!(archived.len(), 3);
+        assert_eq!(archived.as_slice::<u8>(), Some(&[1u8, 2, 3][..]));
+        assert_eq!(archived.as_slice::<i8>(), None);
+        assert_eq!(archived.to_vec_copy().unwrap(), buf);
+
+        // A zero element size is rejected by the validator.
+        let mut corrupt = bytes.to_vec();
+        let offset = corrupt.len() - size_of::<ArchivedVecCopy>() + size_of::<ArchivedVec<u8>>();
+        corrupt[offset..offset + size_of::<ArchivedElementSize>()].fill(0);
+        let err = rkyv::check_archived_root::<VecCopy>(&corrupt)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("element size is zero"));
+
+        // The element bytes are aligned within the archive.
+        let values = vec![Aligned([1; 32]), Aligned([2; 32])];
+        let buf = VecCopy::from_vec(values.clone());
+        let mut serializer = rkyv::ser::serializers::AllocSerializer::<256>::default();
+        serializer.write(&[0u8; 3]).unwrap();
+        serializer.serialize_value(&buf).unwrap();
+        let bytes = serializer.into_serializer().into_inner();
+        let archived = rkyv::check_archived_root::<VecCopy>(&bytes).unwrap();
+        assert_eq!(archived.stable_type_id(), None);
+        let offset = archived.as_bytes().as_ptr() as usize - bytes.as_ptr() as usize;
+        assert_eq!(offset % 32, 0);
+        assert!(archived.to_vec_copy().is_err());
+
+        crate::register_type::<Aligned>();
+        let bytes = rkyv::to_bytes::<_, 256>(&buf).unwrap();
+        // Copy the archive to a sufficiently aligned allocation to borrow the elements.
+        let bytes = AlignedVec::from_slice(&bytes, 32);
+        let archived = rkyv::check_archived_root::<VecCopy>(&bytes).unwrap();
+        assert_eq!(archived.as_slice::<Aligned>(), Some(values.as_slice()));
+        assert_eq!(
+            archived.to_vec_copy().unwrap().as_slice::<Aligned>(),
+            Some(values.as_slice())
+        );
+    }
+}
@@ -0,0 +1,127 @@
+//! This module defines a runtime tag for the primitive element types of buffers.
+//!
+//! Data read from text formats like JSON or CSV doesn't carry a Rust type, so the element type of
+//! the buffer it is read into must be selected at runtime. `ScalarType` enumerates the primitive
+//! types supported by such conversions.
+
+use std::any::TypeId;
+use std::fmt;
+use std::mem::size_of;
+
+use crate::VecCopy;
+
+/// The primitive element types of buffers.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ScalarType {
+    /// `u8`.
+    U8,
+    /// `i8`.
+    I8,
+    /// `u16`.
+    U16,
+    /// `i16`.
+    I16,
+    /// `u32`.
+    U32,
+    /// `i32`.
+    I32,
+    /// `u64`.
+    U64,
+    /// `i64`.
+    I64,
+    /// `f32`.
+    F32,
+    /// `f64`.
+    F64,
+    /// `bool`.
+    Bool,
+}
+
+impl ScalarType {
+    /// Get the scalar type corresponding to the given `TypeId`.
+    ///
+    /// Returns `None` if the type is not a primitive scalar type.
+    pub fn from_type_id(type_id: TypeId) -> Option<ScalarType> {
+        macro_rules! types {
+            ($($t:ty => $variant:ident),*) => {
+                $(
+                    if type_id == TypeId::of::<$t>() {
+                        return Some(ScalarType::$variant);
+                    }
+                )*
+            };
+        }
+        types!(
+            u8 => U8, i8 => I8, u16 => U16, i16 => I16, u32 => U32, i32 => I32,
+            u64 => U64, i64 => I64, f32 => F32, f64 => F64, bool => Bool
+        );
+        None
+    }
+
+    /// Get the name of the corresponding Rust type.
+    pub fn name(self) -> &'static str {
+        match self {
+            ScalarType::U8 => "u8",
+            ScalarType::I8 => "i8",
+            ScalarType::U16 => "u16",
+            ScalarType::I16 => "i16",
+            ScalarType::U32 => "u32",
+            ScalarType::I32 => "i32",
+            ScalarType::U64 => "u64",
+            ScalarType::I64 => "i64",
+            ScalarType::F32 => "f32",
+            ScalarType::F64 => "f64",
+            ScalarType::Bool => "bool",
+        }
+    }
+
+    /// Get the size of the corresponding Rust type in bytes.
+    pub fn size(self) -> usize {
+        match self {
+            ScalarType::U8 | ScalarType::I8 | ScalarType::Bool => size_of::<u8>(),
+            ScalarType::U16 | ScalarType::I16 => size_of::<u16>(),
+            ScalarType::U32 | ScalarType::I32 | ScalarType::F32 => size_of::<u32>(),
+            ScalarType::U64 | ScalarType::I64 | ScalarType::F64 => size_of::<u64>(),
+        }
+    }
+
+    /// Construct an empty buffer with elements of this type.
+    pub fn new_buffer(self) -> VecCopy {
+        match self {
+            ScalarType::U8 => VecCopy::with_type::<u8>(),
+            ScalarType::I8 => VecCopy::with_type::<i8>(),
+            ScalarType::U16 => VecCopy::with_type::<u16>(),
+            ScalarType::I16 => VecCopy::with_type::<i16>(),
+            ScalarType::U32 => VecCopy::with_type::<u32>(),
+            ScalarType::I32 => VecCopy::with_type::<i32>(),
+            ScalarType::U64 => VecCopy::with_type::<u64>(),
+            ScalarType::I64 => VecCopy::with_type::<i64>(),
+            ScalarType::F32 => VecCopy::with_type::<f32>(),
+            ScalarType::F64 => VecCopy::with_type::<f64>(),
+            ScalarType::Bool => VecCopy::with_type::<bool>(),
+        }
+    }
+}
+
+impl fmt::Display for ScalarType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl VecCopy {
+    /// Get the scalar type of the elements of this buffer.
+    ///
+    /// Returns `None` if the elements are not of a primitive scalar type.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::{ScalarType, VecCopy};
+    /// assert_eq!(VecCopy::from_vec(vec![1.0f32]).scalar_type(), Some(ScalarType::F32));
+    /// assert_eq!(VecCopy::from_vec(vec![[1u8; 2]]).scalar_type(), None);
+    /// ```
+    #[inline]
+    pub fn scalar_type(&self) -> Option<ScalarType> {
+        ScalarType::from_type_id(self.element_type_id())
+    }
+}
@@ -0,0 +1,279 @@
+//! This module implements buffers shared between processes through named shared memory segments.
+//!
+//! A segment stores a buffer in the raw binary format, so the element size and stable type
+//! identifier are recorded in the header in front of the data. Segments live in `/dev/shm` where
+//! available (and in the temporary directory otherwise), so they are backed by memory rather
+//! than disk.
+//!
+//! A segment is fully written under a temporary name before it is published under its final
+//! name, and is never modified afterwards. Segments are only accessible by the user that created
+//! them. This allows consumers to attach to a segment and view its contents read-only without
+//! copying, as long as segments are only created through this module.
+
+use std::any::TypeId;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use memmap2::Mmap;
+
+use crate::raw::{self, Header, HEADER_SIZE};
+use crate::vtable::CopyVTable;
+use crate::{DecodeError, Endianness, SliceCopy, TypeName, VecCopy};
+
+/// Get the path of the shared memory segment with the given name.
+fn segment_path(name: &str) -> io::Result<PathBuf> {
+    if name.is_empty() || name.contains(&['/', '\\', '\0'][..]) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid shared memory segment name `{}`", name),
+        ));
+    }
+    let shm = Path::new("/dev/shm");
+    let dir = if shm.is_dir() {
+        shm.to_path_buf()
+    } else {
+        std::env::temp_dir()
+    };
+    Ok(dir.join(name))
+}
+
+/// A named shared memory segment created by this process.
+///
+/// The segment is removed when this handle is dropped. Processes that are already attached to
+/// the segment can continue to read it.
+#[derive(Debug)]
+pub struct SharedSegment {
+    name: String,
+    path: PathBuf,
+}
+
+impl SharedSegment {
+    /// The name of this segment.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Attach to this segment from the current process.
+    ///
+    /// # Safety
+    ///
+    /// The same requirements as for [`SharedBuffer::attach`] apply.
+    #[inline]
+    pub unsafe fn attach(&self) -> io::Result<SharedBuffer> {
+        SharedBuffer::attach(&self.name)
+    }
+}
+
+impl Drop for SharedSegment {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+impl VecCopy {
+    /// Create a named shared memory segment containing a copy of this buffer.
+    ///
+    /// Other processes of the same user can attach to the segment with [`SharedBuffer::attach`]
+    /// using the same name until the returned handle is dropped. The element type must be registered with
+    /// [`register_type`], otherwise an error of kind `InvalidInput` is returned. An error of kind
+    /// `AlreadyExists` is returned if a segment with the given name exists.
+    ///
+    /// [`register_type`]: crate::register_type
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::{SharedBuffer, VecCopy};
+    /// let buf = VecCopy::from_vec(vec![1u32, 2, 3]);
+    /// let segment = buf.create_shared("data_buffer_create_shared_doctest").unwrap();
+    ///
+    /// // Typically in another process:
+    /// let shared = unsafe { SharedBuffer::attach("data_buffer_create_shared_doctest").unwrap() };
+    /// assert_eq!(shared.as_slice_copy().as_slice::<u32>().unwrap(), &[1, 2, 3]);
+    /// ```
+    pub fn create_shared(&self, name: &str) -> io::Result<SharedSegment> {
+        let path = segment_path(name)?;
+        let mut tmp_name = path.clone().into_os_string();
+        tmp_name.push(format!(".{}.tmp", std::process::id()));
+        let tmp_path = PathBuf::from(tmp_name);
+
+        let write_tmp = || -> io::Result<()> {
+            let mut options = OpenOptions::new();
+            options.write(true).create_new(true);
+            #[cfg(unix)]
+            std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+            let mut writer = BufWriter::new(options.open(&tmp_path)?);
+            self.encode_raw(&mut writer)?;
+            writer.flush()
+        };
+        // Linking fails if the segment already exists, which makes publishing atomic.
+        let result = write_tmp().and_then(|_| fs::hard_link(&tmp_path, &path));
+        let _ = fs::remove_file(&tmp_path);
+        result.map(|_| SharedSegment {
+            name: name.to_string(),
+            path,
+        })
+    }
+}
+
+/// A read-only view of a buffer stored in a named shared memory segment.
+///
+/// The element type is determined at run time from the segment header, and must be registered
+/// with [`register_type`] in the attaching process.
+///
+/// [`register_type`]: crate::register_type
+pub struct SharedBuffer {
+    mmap: Mmap,
+    len: usize,
+    element_size: usize,
+    element_type_id: TypeId,
+    element_type_name: &'static str,
+    vtable: CopyVTable,
+}
+
+impl SharedBuffer {
+    /// Attach to the named shared memory segment created with [`VecCopy::create_shared`].
+    ///
+    /// The segment header is validated before any data is borrowed. Malformed data or data of an
+    /// unregistered type produces an error of kind `InvalidData` wrapping a [`DecodeError`].
+    ///
+    /// # Safety
+    ///
+    /// The segment is mapped into memory without copying, so it must have been created with
+    /// [`VecCopy::create_shared`], and must not be modified while the returned buffer is alive.
+    /// In particular, no other process may write to the segment file.
+    pub unsafe fn attach(name: &str) -> io::Result<SharedBuffer> {
+        let invalid = |err: DecodeError| io::Error::new(io::ErrorKind::InvalidData, err);
+        let file = File::open(segment_path(name)?)?;
+        let mmap = Mmap::map(&file)?;
+        if mmap.len() < HEADER_SIZE {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        let mut header = [0u8; HEADER_SIZE];
+        header.copy_from_slice(&mmap[..HEADER_SIZE]);
+        let header = Header::decode_uncompressed(&header).map_err(invalid)?;
+        let ty = raw::registered_type(header.element_type, header.element_size).map_err(invalid)?;
+        if header.endianness != Endianness::NATIVE {
+            return Err(invalid(DecodeError::ForeignEndianness(header.endianness)));
+        }
+        if (mmap.as_ptr() as usize + HEADER_SIZE) % ty.align != 0 {
+            return Err(invalid(DecodeError::Misaligned { align: ty.align }));
+        }
+        let payload_size = header.payload_size().map_err(invalid)?;
+        if ((mmap.len() - HEADER_SIZE) as u64) != payload_size {
+            return Err(invalid(DecodeError::InvalidLength {
+                len: (mmap.len() - HEADER_SIZE) as u64,
+                element_size: ty.size,
+            }));
+        }
+        header
+            .verify_checksum(&mmap[HEADER_SIZE..])
+            .map_err(invalid)?;
+        Ok(SharedBuffer {
+            mmap,
+            len: header.len as usize,
+            element_size: ty.size,
+            element_type_id: ty.type_id,
+            element_type_name: ty.name,
+            vtable: ty.vtable,
+        })
+    }
+
+    /// Borrow the shared buffer as a type-erased slice.
+    #[inline]
+    pub fn as_slice_copy(&self) -> SliceCopy<'_> {
+        SliceCopy {
+            data: &self.mmap[HEADER_SIZE..HEADER_SIZE + self.len * self.element_size],
+            element_size: self.element_size,
+            element_type_id: self.element_type_id,
+            element_type_name: TypeName(Some(self.element_type_name)),
+            vtable: self.vtable,
+        }
+    }
+
+    /// Get the number of elements in the shared buffer.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check if there are any elements in the shared buffer.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Copy the shared buffer into an owned buffer.
+    #[inline]
+    pub fn to_vec_copy(&self) -> VecCopy {
+        self.as_slice_copy().to_vec_copy()
+    }
+}
+
+impl std::fmt::Debug for SharedBuffer {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("SharedBuffer")
+            .field("element_type", &self.element_type_name)
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shared_memory_test() {
+        let name = format!("data_buffer_shared_memory_test_{}", std::process::id());
+        let buf = VecCopy::from_vec(vec![1u16, 2, 3]);
+        let segment = buf.create_shared(&name).unwrap();
+        assert_eq!(segment.name(), name);
+        let err = buf.create_shared(&name).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+
+        let shared = unsafe { segment.attach().unwrap() };
+        assert_eq!(shared.len(), 3);
+        assert_eq!(shared.as_slice_copy().element_type_name(), "u16");
+        assert_eq!(
+            shared.as_slice_copy().as_slice::<u16>(),
+            Some(&[1, 2, 3][..])
+        );
+        assert_eq!(shared.to_vec_copy(), buf);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let metadata = fs::metadata(segment_path(&name).unwrap()).unwrap();
+            assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+        }
+
+        // Attached buffers remain readable after the segment is removed.
+        drop(segment);
+        assert_eq!(shared.as_slice_copy().len(), 3);
+        let err = unsafe { SharedBuffer::attach(&name).unwrap_err() };
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+
+        assert_eq!(
+            buf.create_shared("a/b").unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+
+        // Segments with checksums are verified.
+        let mut bytes = Vec::new();
+        buf.encode_raw_with_checksum(&mut bytes).unwrap();
+        let path = segment_path(&name).unwrap();
+        fs::write(&path, &bytes).unwrap();
+        let shared = unsafe { SharedBuffer::attach(&name).unwrap() };
+        assert_eq!(shared.to_vec_copy(), buf);
+        drop(shared);
+        bytes[HEADER_SIZE] ^= 1;
+        fs::write(&path, &bytes).unwrap();
+        let err = unsafe { SharedBuffer::attach(&name).unwrap_err() };
+        assert!(matches!(
+            *err.into_inner().unwrap().downcast::<DecodeError>().unwrap(),
+            DecodeError::CorruptData { .. }
+        ));
+        fs::remove_file(&path).unwrap();
+    }
+}
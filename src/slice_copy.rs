@@ -0,0 +1,555 @@
+//! This module defines borrowed views into contiguous ranges of `VecCopy` elements.
+//!
+//! These views keep track of the element type in the same way as `VecCopy`, which allows parts of
+//! a buffer to be passed around without knowing the element type at compile time.
+
+use std::any::{Any, TypeId};
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::slice;
+
+use crate::aligned_vec::{max_align_of_size, AlignedVec};
+use crate::value::{CopyValueMut, CopyValueRef};
+use crate::vtable::{CopyVTable, DebugElements};
+use crate::{Elem, TypeName, VecCopy};
+
+/// An immutable view into a contiguous range of elements of a `VecCopy`.
+#[derive(Copy, Clone)]
+pub struct SliceCopy<'a> {
+    /// Raw data stored as bytes.
+    pub(crate) data: &'a [u8],
+    /// Number of bytes occupied by an element of this slice.
+    pub(crate) element_size: usize,
+    /// Type encoding for hiding the type of data from the compiler.
+    pub(crate) element_type_id: TypeId,
+    /// Name of the element type used for diagnostics.
+    pub(crate) element_type_name: TypeName,
+    /// Optional trait functions captured for the element type.
+    pub(crate) vtable: CopyVTable,
+}
+
+/// A mutable view into a contiguous range of elements of a `VecCopy`.
+pub struct SliceCopyMut<'a> {
+    /// Raw data stored as bytes.
+    pub(crate) data: &'a mut [u8],
+    /// Number of bytes occupied by an element of this slice.
+    pub(crate) element_size: usize,
+    /// Type encoding for hiding the type of data from the compiler.
+    pub(crate) element_type_id: TypeId,
+    /// Name of the element type used for diagnostics.
+    pub(crate) element_type_name: TypeName,
+    /// Optional trait functions captured for the element type.
+    pub(crate) vtable: CopyVTable,
+}
+
+// Implement the read-only API shared by both slice types.
+macro_rules! impl_slice_copy_base {
+    () => {
+        /// Check if the current slice contains elements of the specified type. Returns `None` if
+        /// the check fails, otherwise a reference to self is returned.
+        #[inline]
+        pub fn check_ref<T: Any>(&self) -> Option<&Self> {
+            if TypeId::of::<T>() != self.element_type_id() {
+                None
+            } else {
+                Some(self)
+            }
+        }
+
+        /// Get the `TypeId` of data stored within this slice.
+        #[inline]
+        pub fn element_type_id(&self) -> TypeId {
+            self.element_type_id
+        }
+
+        /// Get the name of the type of data stored within this slice.
+        ///
+        /// See `VecCopy::element_type_name` for details.
+        #[inline]
+        pub fn element_type_name(&self) -> &'static str {
+            self.element_type_name.0.unwrap_or("<unknown>")
+        }
+
+        /// Get the size of the element type in bytes.
+        #[inline]
+        pub fn element_size(&self) -> usize {
+            self.element_size
+        }
+
+        /// Get the number of elements in this slice.
+        #[inline]
+        pub fn len(&self) -> usize {
+            debug_assert_eq!(self.data.len() % self.element_size, 0);
+            self.data.len() / self.element_size
+        }
+
+        /// Check if there are any elements in this slice.
+        #[inline]
+        pub fn is_empty(&self) -> bool {
+            self.data.is_empty()
+        }
+
+        /// Convert this view into a typed slice.
+        /// Returns `None` if the given type `T` doesn't match the internal.
+        #[inline]
+        pub fn as_slice<T: Any>(&self) -> Option<&[T]> {
+            let ptr = self.check_ref::<T>()?.data.as_ptr() as *const T;
+            Some(unsafe { slice::from_raw_parts(ptr, self.len()) })
+        }
+
+        /// Return an iterator over typed elements of this slice.
+        /// Returns `None` if the given type `T` doesn't match the internal.
+        #[inline]
+        pub fn iter<T: Any>(&self) -> Option<slice::Iter<'_, T>> {
+            self.as_slice::<T>().map(|x| x.iter())
+        }
+
+        /// Get a `const` reference to the `i`'th element of the slice.
+        ///
+        /// Returns `None` if `T` doesn't match the internal type or if `i` is out of bounds.
+        #[inline]
+        pub fn get_ref<T: Any>(&self, i: usize) -> Option<&T> {
+            self.as_slice::<T>()?.get(i)
+        }
+
+        /// Peek at the internal representation of the data.
+        #[inline]
+        pub fn as_bytes(&self) -> &[u8] {
+            &*self.data
+        }
+
+        /// Get a `const` reference to the byte slice of the `i`'th element of the slice.
+        #[inline]
+        pub fn get_bytes(&self, i: usize) -> &[u8] {
+            debug_assert!(i < self.len());
+            &self.data[i * self.element_size..(i + 1) * self.element_size]
+        }
+
+        /// Iterate over element sized chunks of bytes without interpreting them.
+        #[inline]
+        pub fn byte_chunks(&self) -> slice::ChunksExact<'_, u8> {
+            self.data.chunks_exact(self.element_size)
+        }
+
+        /// Get a reference to a value stored in this slice at index `i`.
+        #[inline]
+        pub fn value_ref(&self, i: usize) -> CopyValueRef<'_> {
+            // This call is safe since the slice guarantees that the given bytes have the
+            // corresponding TypeId.
+            unsafe { CopyValueRef::from_raw_parts(self.get_bytes(i), self.element_type_id) }
+        }
+
+        /// Return an iterator over untyped value references stored in this slice.
+        #[inline]
+        pub fn iter_value_ref(&self) -> impl Iterator<Item = CopyValueRef<'_>> + '_ {
+            let element_type_id = self.element_type_id;
+            self.byte_chunks()
+                .map(move |bytes| unsafe { CopyValueRef::from_raw_parts(bytes, element_type_id) })
+        }
+
+        /// Copy the elements of this slice into a new `VecCopy`.
+        #[inline]
+        pub fn to_vec_copy(&self) -> VecCopy {
+            VecCopy {
+                data: AlignedVec::from_slice(&*self.data, max_align_of_size(self.element_size)),
+                element_size: self.element_size,
+                element_type_id: self.element_type_id,
+                element_type_name: self.element_type_name,
+                vtable: self.vtable,
+            }
+        }
+    };
+}
+
+impl<'a> SliceCopy<'a> {
+    impl_slice_copy_base!();
+
+    /// Construct a `SliceCopy` from a typed slice.
+    #[inline]
+    pub fn from_slice<T: Elem>(slice: &'a [T]) -> SliceCopy<'a> {
+        let element_size = std::mem::size_of::<T>();
+        assert_ne!(
+            element_size, 0,
+            "SliceCopy doesn't support zero sized types."
+        );
+        SliceCopy {
+            // This is safe since any memory can be represented by bytes.
+            data: unsafe {
+                slice::from_raw_parts(slice.as_ptr() as *const u8, std::mem::size_of_val(slice))
+            },
+            element_size,
+            element_type_id: TypeId::of::<T>(),
+            element_type_name: TypeName::of::<T>(),
+            vtable: CopyVTable::of_elem::<T>(),
+        }
+    }
+
+    /// Divide this slice into two at the element index `mid`.
+    ///
+    /// The first will contain all elements from `[0, mid)` and the second will contain all
+    /// elements from `[mid, len)`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `mid > len`.
+    #[inline]
+    pub fn split_at(&self, mid: usize) -> (SliceCopy<'a>, SliceCopy<'a>) {
+        assert!(
+            mid <= self.len(),
+            "`mid` split index {} is out of bounds",
+            mid
+        );
+        let (l, r) = self.data.split_at(mid * self.element_size);
+        (
+            SliceCopy { data: l, ..*self },
+            SliceCopy { data: r, ..*self },
+        )
+    }
+}
+
+impl<'a> SliceCopyMut<'a> {
+    impl_slice_copy_base!();
+
+    /// Construct a `SliceCopyMut` from a typed mutable slice.
+    #[inline]
+    pub fn from_slice_mut<T: Elem>(slice: &'a mut [T]) -> SliceCopyMut<'a> {
+        let element_size = std::mem::size_of::<T>();
+        assert_ne!(
+            element_size, 0,
+            "SliceCopyMut doesn't support zero sized types."
+        );
+        SliceCopyMut {
+            // This is safe since any memory can be represented by bytes and the bytes can only
+            // be modified through the typed or value API.
+            data: unsafe {
+                slice::from_raw_parts_mut(
+                    slice.as_mut_ptr() as *mut u8,
+                    std::mem::size_of_val(slice),
+                )
+            },
+            element_size,
+            element_type_id: TypeId::of::<T>(),
+            element_type_name: TypeName::of::<T>(),
+            vtable: CopyVTable::of_elem::<T>(),
+        }
+    }
+
+    /// Reborrow this mutable slice as an immutable `SliceCopy`.
+    #[inline]
+    pub fn as_slice_copy(&self) -> SliceCopy<'_> {
+        SliceCopy {
+            data: &*self.data,
+            element_size: self.element_size,
+            element_type_id: self.element_type_id,
+            element_type_name: self.element_type_name,
+            vtable: self.vtable,
+        }
+    }
+
+    /// Reborrow this mutable slice with a shorter lifetime.
+    #[inline]
+    pub fn reborrow(&mut self) -> SliceCopyMut<'_> {
+        SliceCopyMut {
+            data: &mut *self.data,
+            element_size: self.element_size,
+            element_type_id: self.element_type_id,
+            element_type_name: self.element_type_name,
+            vtable: self.vtable,
+        }
+    }
+
+    /// Check if the current slice contains elements of the specified type. Same as `check_ref`
+    /// but consumes and produces a mut reference to self.
+    #[inline]
+    pub fn check_mut<T: Any>(&mut self) -> Option<&mut Self> {
+        if TypeId::of::<T>() != self.element_type_id() {
+            None
+        } else {
+            Some(self)
+        }
+    }
+
+    /// Convert this view into a typed mutable slice.
+    /// Returns `None` if the given type `T` doesn't match the internal.
+    #[inline]
+    pub fn as_mut_slice<T: Any>(&mut self) -> Option<&mut [T]> {
+        let len = self.len();
+        let ptr = self.check_mut::<T>()?.data.as_mut_ptr() as *mut T;
+        Some(unsafe { slice::from_raw_parts_mut(ptr, len) })
+    }
+
+    /// Consume this view, producing a typed mutable slice with the original lifetime.
+    /// Returns `None` if the given type `T` doesn't match the internal.
+    #[inline]
+    pub fn into_mut_slice<T: Any>(self) -> Option<&'a mut [T]> {
+        let len = self.len();
+        self.check_ref::<T>()?;
+        let ptr = self.data.as_mut_ptr() as *mut T;
+        Some(unsafe { slice::from_raw_parts_mut(ptr, len) })
+    }
+
+    /// Return an iterator over typed mutable elements of this slice.
+    /// Returns `None` if the given type `T` doesn't match the internal.
+    #[inline]
+    pub fn iter_mut<T: Any>(&mut self) -> Option<slice::IterMut<'_, T>> {
+        self.as_mut_slice::<T>().map(|x| x.iter_mut())
+    }
+
+    /// Get a mutable reference to the `i`'th element of the slice.
+    ///
+    /// Returns `None` if `T` doesn't match the internal type or if `i` is out of bounds.
+    #[inline]
+    pub fn get_mut<T: Any>(&mut self, i: usize) -> Option<&mut T> {
+        self.as_mut_slice::<T>()?.get_mut(i)
+    }
+
+    /// Get a mutable reference to a value stored in this slice at index `i`.
+    #[inline]
+    pub fn value_mut(&mut self, i: usize) -> CopyValueMut<'_> {
+        debug_assert!(i < self.len());
+        let element_size = self.element_size;
+        let bytes = &mut self.data[i * element_size..(i + 1) * element_size];
+        // Safety is guaranteed here by the value API.
+        unsafe { CopyValueMut::from_raw_parts(bytes, self.element_type_id) }
+    }
+
+    /// Return an iterator over mutable untyped value references stored in this slice.
+    #[inline]
+    pub fn iter_value_mut(&mut self) -> impl Iterator<Item = CopyValueMut<'_>> + '_ {
+        let element_type_id = self.element_type_id;
+        self.data
+            .chunks_exact_mut(self.element_size)
+            .map(move |bytes| unsafe { CopyValueMut::from_raw_parts(bytes, element_type_id) })
+    }
+
+    /// Get a mutable reference to the internal data representation.
+    ///
+    /// # Safety
+    ///
+    /// This function is marked as unsafe since the returned bytes may be modified
+    /// arbitrarily, which may potentially produce malformed values.
+    #[inline]
+    pub unsafe fn as_bytes_mut(&mut self) -> &mut [u8] {
+        &mut *self.data
+    }
+
+    /// Divide this mutable slice into two non-overlapping mutable slices at the element index
+    /// `mid`.
+    ///
+    /// The first will contain all elements from `[0, mid)` and the second will contain all
+    /// elements from `[mid, len)`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `mid > len`.
+    #[inline]
+    pub fn split_at_mut(self, mid: usize) -> (SliceCopyMut<'a>, SliceCopyMut<'a>) {
+        assert!(
+            mid <= self.len(),
+            "`mid` split index {} is out of bounds",
+            mid
+        );
+        let SliceCopyMut {
+            data,
+            element_size,
+            element_type_id,
+            element_type_name,
+            vtable,
+        } = self;
+        let (l, r) = data.split_at_mut(mid * element_size);
+        (
+            SliceCopyMut {
+                data: l,
+                element_size,
+                element_type_id,
+                element_type_name,
+                vtable,
+            },
+            SliceCopyMut {
+                data: r,
+                element_size,
+                element_type_id,
+                element_type_name,
+                vtable,
+            },
+        )
+    }
+}
+
+/// Slices are equal if they store the same type and their elements are equal.
+///
+/// Elements are compared with their own `PartialEq` implementation, which is captured at
+/// construction, or by their bytes if the element type is only known at runtime.
+impl PartialEq for SliceCopy<'_> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        if self.element_type_id != other.element_type_id {
+            return false;
+        }
+        let vtable = if self.vtable.eq.is_some() {
+            &self.vtable
+        } else {
+            &other.vtable
+        };
+        vtable.eq_bytes(self.element_size, self.data, other.data)
+    }
+}
+
+/// Slices storing different types are ordered by their `TypeId`, otherwise slices are compared
+/// lexicographically.
+///
+/// Elements are compared with their own ordering if it was captured for either slice (see
+/// `VecCopy::with_ord` and `VecCopy::with_partial_ord`). Otherwise unequal elements are ordered
+/// by their bytes, or are incomparable if their bytes are equal.
+impl PartialOrd for SliceCopy<'_> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self.element_type_id != other.element_type_id {
+            return Some(self.element_type_id.cmp(&other.element_type_id));
+        }
+        let vtable = self.vtable.ord_vtable(&other.vtable);
+        vtable.partial_cmp_bytes(self.element_size, self.data, other.data)
+    }
+}
+
+/// Slice equality is an equivalence relation as long as the equality of the elements is, which is
+/// not the case for floats.
+impl Eq for SliceCopy<'_> {}
+
+impl PartialEq for SliceCopyMut<'_> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice_copy() == other.as_slice_copy()
+    }
+}
+
+impl SliceCopy<'_> {
+    /// Format the contents of this slice as a struct with the given name.
+    pub(crate) fn debug_struct(&self, name: &str, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct(name)
+            .field("element_type_name", &self.element_type_name)
+            .field("len", &self.len())
+            .field(
+                "data",
+                &DebugElements {
+                    vtable: &self.vtable,
+                    element_size: self.element_size,
+                    bytes: self.data,
+                },
+            )
+            .finish()
+    }
+}
+
+/// Prints the element type and at most 32 elements of the slice.
+///
+/// Elements are printed with their own `Debug` implementation if it was captured (see
+/// `VecCopy::with_debug`), and as arrays of bytes otherwise.
+impl fmt::Debug for SliceCopy<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.debug_struct("SliceCopy", f)
+    }
+}
+
+impl fmt::Debug for SliceCopyMut<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.as_slice_copy().debug_struct("SliceCopyMut", f)
+    }
+}
+
+/// Slices are hashed by their element type and elements.
+///
+/// Elements are hashed with their own `Hash` implementation if it was captured (see
+/// `VecCopy::with_hash`). Otherwise floats are hashed by their values and other elements by their
+/// bytes, unless their equality differs from byte equality, in which case only the length is
+/// hashed.
+impl Hash for SliceCopy<'_> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.element_type_id.hash(state);
+        self.vtable.hash_bytes(self.element_size, self.data, state);
+    }
+}
+
+impl Hash for SliceCopyMut<'_> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_slice_copy().hash(state)
+    }
+}
+
+impl<'a> From<SliceCopyMut<'a>> for SliceCopy<'a> {
+    #[inline]
+    fn from(s: SliceCopyMut<'a>) -> SliceCopy<'a> {
+        SliceCopy {
+            data: s.data,
+            element_size: s.element_size,
+            element_type_id: s.element_type_id,
+            element_type_name: s.element_type_name,
+            vtable: s.vtable,
+        }
+    }
+}
+
+impl<'a> From<&'a VecCopy> for SliceCopy<'a> {
+    #[inline]
+    fn from(buf: &'a VecCopy) -> SliceCopy<'a> {
+        buf.as_slice_copy()
+    }
+}
+
+impl<'a> From<&'a mut VecCopy> for SliceCopyMut<'a> {
+    #[inline]
+    fn from(buf: &'a mut VecCopy) -> SliceCopyMut<'a> {
+        buf.as_slice_copy_mut()
+    }
+}
+
+impl<'a> From<SliceCopy<'a>> for VecCopy {
+    #[inline]
+    fn from(slice: SliceCopy<'a>) -> VecCopy {
+        slice.to_vec_copy()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_at_test() {
+        let buf = VecCopy::from_vec(vec![1u32, 2, 3, 4, 5]);
+        let (l, r) = buf.split_at(2);
+        assert_eq!(l.as_slice::<u32>().unwrap(), &[1, 2]);
+        assert_eq!(r.as_slice::<u32>().unwrap(), &[3, 4, 5]);
+        assert!(l.as_slice::<i32>().is_none());
+        assert_eq!(r.element_type_id(), TypeId::of::<u32>());
+
+        let (ll, lr) = l.split_at(0);
+        assert!(ll.is_empty());
+        assert_eq!(lr, l);
+        assert_eq!(VecCopy::from(r).into_vec::<u32>().unwrap(), vec![3, 4, 5]);
+    }
+
+    #[test]
+    fn split_at_mut_test() {
+        let mut buf = VecCopy::from_vec(vec![1u32, 2, 3, 4, 5]);
+        {
+            let (mut l, mut r) = buf.split_at_mut(3);
+            std::mem::swap(l.get_mut::<u32>(0).unwrap(), r.get_mut::<u32>(1).unwrap());
+            r.iter_mut::<u32>().unwrap().for_each(|x| *x *= 10);
+            assert_eq!(l.len(), 3);
+            assert!(r.get_mut::<u32>(2).is_none());
+            assert!(l.get_ref::<u32>(3).is_none());
+        }
+        assert_eq!(buf.as_slice::<u32>().unwrap(), &[5, 2, 3, 40, 10]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn split_at_out_of_bounds_test() {
+        let buf = VecCopy::from_vec(vec![1u32, 2]);
+        let _ = buf.split_at(3);
+    }
+}
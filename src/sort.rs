@@ -0,0 +1,555 @@
+//! This module implements in-place sorting and reordering of buffers.
+//!
+//! Buffers are sorted through their typed view, so sorting large buffers doesn't require
+//! converting them into a `Vec` and back. Reordering by a permutation doesn't require the element
+//! type at all, so multiple buffers can be reordered consistently by the permutation that sorts
+//! one of them.
+
+use std::cmp::Ordering;
+
+use crate::traits::CmpFn;
+use crate::{Elem, VecCopy};
+
+/// Check that `permutation` is a permutation of the indices `0..len`.
+///
+/// # Panics
+///
+/// This function panics if `permutation` has the wrong length, or contains an out of bounds or
+/// repeated index.
+pub(crate) fn assert_permutation(permutation: &[usize], len: usize) {
+    assert_eq!(
+        permutation.len(),
+        len,
+        "permutation length doesn't match the buffer length"
+    );
+    let mut seen = vec![false; len];
+    for &i in permutation {
+        assert!(i < len, "index {} is out of bounds for length {}", i, len);
+        assert!(
+            !seen[i],
+            "index {} appears more than once in the permutation",
+            i
+        );
+        seen[i] = true;
+    }
+}
+
+/// Floating point types ordered by the IEEE 754 `totalOrder` predicate.
+///
+/// Floating point numbers only implement `PartialOrd`, since `NaN` is not comparable. The total
+/// order places negative `NaN`s first and positive `NaN`s last, and orders `-0.0` before `0.0`.
+pub trait TotalOrd: Elem {
+    /// Compare two values by the IEEE 754 `totalOrder` predicate.
+    fn total_cmp(&self, other: &Self) -> Ordering;
+}
+
+impl TotalOrd for f32 {
+    #[inline]
+    fn total_cmp(&self, other: &Self) -> Ordering {
+        f32::total_cmp(self, other)
+    }
+}
+
+impl TotalOrd for f64 {
+    #[inline]
+    fn total_cmp(&self, other: &Self) -> Ordering {
+        f64::total_cmp(self, other)
+    }
+}
+
+impl VecCopy {
+    /// Sort the elements of this buffer.
+    ///
+    /// The sort is stable. If `T` doesn't match the internal type, `None` is returned and the
+    /// buffer is left unchanged.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let mut buf = VecCopy::from_vec(vec![3i32, -1, 2]);
+    /// buf.sort::<i32>().unwrap();
+    /// assert_eq!(buf.as_slice::<i32>().unwrap(), &[-1, 2, 3]);
+    /// ```
+    #[inline]
+    pub fn sort<T: Elem + Ord>(&mut self) -> Option<&mut Self> {
+        self.as_mut_slice::<T>()?.sort();
+        Some(self)
+    }
+
+    /// Sort the elements of this buffer without preserving the order of equal elements.
+    ///
+    /// This is typically faster than `sort` and doesn't allocate. If `T` doesn't match the
+    /// internal type, `None` is returned and the buffer is left unchanged.
+    #[inline]
+    pub fn sort_unstable<T: Elem + Ord>(&mut self) -> Option<&mut Self> {
+        self.as_mut_slice::<T>()?.sort_unstable();
+        Some(self)
+    }
+
+    /// Sort the elements of this buffer with the given comparator function.
+    ///
+    /// The sort is stable. If `T` doesn't match the internal type, `None` is returned and the
+    /// buffer is left unchanged.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let mut buf = VecCopy::from_vec(vec![[1.0f32, 3.0], [2.0, 1.0], [0.5, 2.0]]);
+    /// // Sort points by their second coordinate.
+    /// buf.sort_by::<[f32; 2], _>(|a, b| a[1].total_cmp(&b[1])).unwrap();
+    /// assert_eq!(buf.as_slice::<[f32; 2]>().unwrap(), &[[2.0, 1.0], [0.5, 2.0], [1.0, 3.0]]);
+    /// ```
+    #[inline]
+    pub fn sort_by<T, F>(&mut self, compare: F) -> Option<&mut Self>
+    where
+        T: Elem,
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        self.as_mut_slice::<T>()?.sort_by(compare);
+        Some(self)
+    }
+
+    /// Sort the elements of this buffer with the given key extraction function.
+    ///
+    /// The sort is stable. If `T` doesn't match the internal type, `None` is returned and the
+    /// buffer is left unchanged.
+    #[inline]
+    pub fn sort_by_key<T, K, F>(&mut self, f: F) -> Option<&mut Self>
+    where
+        T: Elem,
+        K: Ord,
+        F: FnMut(&T) -> K,
+    {
+        self.as_mut_slice::<T>()?.sort_by_key(f);
+        Some(self)
+    }
+
+    /// Sort the floating point elements of this buffer by their total order (see [`TotalOrd`]).
+    ///
+    /// Elements that compare equal in the total order have identical bits, so this sort doesn't
+    /// need to be stable. If `T` doesn't match the internal type, `None` is returned and the
+    /// buffer is left unchanged.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let mut buf = VecCopy::from_vec(vec![2.5f32, f32::NAN, -1.0, 0.0]);
+    /// buf.sort_floats::<f32>().unwrap();
+    /// let sorted = buf.as_slice::<f32>().unwrap();
+    /// assert_eq!(&sorted[..3], &[-1.0, 0.0, 2.5]);
+    /// assert!(sorted[3].is_nan());
+    /// ```
+    #[inline]
+    pub fn sort_floats<T: TotalOrd>(&mut self) -> Option<&mut Self> {
+        self.as_mut_slice::<T>()?.sort_unstable_by(T::total_cmp);
+        Some(self)
+    }
+
+    /// Compute the permutation that sorts the elements of this buffer, without moving them.
+    ///
+    /// The element at index `permutation[i]` belongs at index `i` in the sorted buffer. Equal
+    /// elements keep their relative order. This allows multiple buffers to be reordered
+    /// consistently by the elements of one of them. Returns `None` if `T` doesn't match the
+    /// internal type.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let keys = VecCopy::from_vec(vec![30u32, 10, 20]);
+    /// assert_eq!(keys.sort_indices::<u32>().unwrap(), vec![1, 2, 0]);
+    /// ```
+    #[inline]
+    pub fn sort_indices<T: Elem + Ord>(&self) -> Option<Vec<usize>> {
+        self.sort_indices_by::<T, _>(T::cmp)
+    }
+
+    /// Compute the permutation that sorts the elements of this buffer with the given comparator
+    /// function, without moving them.
+    ///
+    /// See `sort_indices` for details. Returns `None` if `T` doesn't match the internal type.
+    #[inline]
+    pub fn sort_indices_by<T, F>(&self, mut compare: F) -> Option<Vec<usize>>
+    where
+        T: Elem,
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let slice = self.as_slice::<T>()?;
+        let mut permutation: Vec<usize> = (0..slice.len()).collect();
+        permutation.sort_by(|&a, &b| compare(&slice[a], &slice[b]));
+        Some(permutation)
+    }
+
+    /// Binary search this sorted buffer for the given element.
+    ///
+    /// Returns `None` if `T` doesn't match the internal type. Otherwise, the result is the same as
+    /// that of `slice::binary_search`: `Ok` with the index of a matching element, or `Err` with
+    /// the index where the element could be inserted while maintaining the sorted order.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let buf = VecCopy::from_vec(vec![1u32, 3, 5]);
+    /// assert_eq!(buf.binary_search(&3u32), Some(Ok(1)));
+    /// assert_eq!(buf.binary_search(&4u32), Some(Err(2)));
+    /// assert_eq!(buf.binary_search(&4i32), None);
+    /// ```
+    #[inline]
+    pub fn binary_search<T: Elem + Ord>(&self, x: &T) -> Option<Result<usize, usize>> {
+        self.as_slice::<T>().map(|s| s.binary_search(x))
+    }
+
+    /// Binary search this sorted buffer with the given comparator function.
+    ///
+    /// The comparator should return the ordering of the given element relative to the target.
+    /// See `binary_search` for details. Returns `None` if `T` doesn't match the internal type.
+    #[inline]
+    pub fn binary_search_by<T, F>(&self, f: F) -> Option<Result<usize, usize>>
+    where
+        T: Elem,
+        F: FnMut(&T) -> Ordering,
+    {
+        self.as_slice::<T>().map(|s| s.binary_search_by(f))
+    }
+
+    /// Sort the elements of this buffer with the `Ord` implementation captured by `with_ord`.
+    ///
+    /// This doesn't require the element type to be known, so it can be used in generic code
+    /// operating on erased buffers. The sort is stable. Returns `None` if no ordering was
+    /// captured for this buffer.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// fn sort_any(buf: &mut VecCopy) -> bool {
+    ///     buf.sort_dyn().is_some()
+    /// }
+    ///
+    /// let mut buf = VecCopy::from_vec(vec![300u16, 2, 1]);
+    /// assert!(!sort_any(&mut buf));
+    /// let mut buf = buf.with_ord::<u16>().unwrap();
+    /// assert!(sort_any(&mut buf));
+    /// assert_eq!(buf.as_slice::<u16>().unwrap(), &[1, 2, 300]);
+    /// ```
+    pub fn sort_dyn(&mut self) -> Option<&mut Self> {
+        let CmpFn(cmp) = self.vtable.cmp?;
+        let element_size = self.element_size;
+        let data = &self.data;
+        let mut permutation: Vec<usize> = (0..self.len()).collect();
+        permutation.sort_by(|&a, &b| {
+            let a = &data[a * element_size..(a + 1) * element_size];
+            let b = &data[b * element_size..(b + 1) * element_size];
+            // This is safe since the function was captured for the stored element type.
+            unsafe { cmp(a, b) }
+        });
+        self.permute_in_place(&mut permutation);
+        Some(self)
+    }
+
+    /// Reorder the elements of this buffer such that the element at index `permutation[i]` is
+    /// moved to index `i`.
+    ///
+    /// This is the order produced by `sort_indices`. Elements are moved by their bytes, so the
+    /// element type need not be known.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `permutation` is not a permutation of the indices of this buffer.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let keys = VecCopy::from_vec(vec![30u32, 10, 20]);
+    /// let mut values = VecCopy::from_vec(vec!['c', 'a', 'b']);
+    /// values.permute(&keys.sort_indices::<u32>().unwrap());
+    /// assert_eq!(values.as_slice::<char>().unwrap(), &['a', 'b', 'c']);
+    /// ```
+    pub fn permute(&mut self, permutation: &[usize]) {
+        assert_permutation(permutation, self.len());
+        self.permute_in_place(&mut permutation.to_vec());
+    }
+
+    /// Construct a new buffer from the elements of this buffer at the given indices.
+    ///
+    /// Indices may repeat or be omitted. The new buffer has the same type, alignment and
+    /// captured functions as this one.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if any of the indices are out of bounds.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let buf = VecCopy::from_vec(vec![1u8, 2, 3]);
+    /// let gathered = buf.gathered(&[2, 0, 2]);
+    /// assert_eq!(gathered.as_slice::<u8>().unwrap(), &[3, 1, 3]);
+    /// ```
+    pub fn gathered(&self, indices: &[usize]) -> VecCopy {
+        let len = self.len();
+        let size = self.element_size;
+        let mut buf = VecCopy::with_type_from(self);
+        buf.data.reserve_exact(indices.len() * size);
+        for &i in indices {
+            assert!(i < len, "index {} is out of bounds for length {}", i, len);
+            buf.data
+                .extend_from_slice(&self.data[i * size..(i + 1) * size]);
+        }
+        buf
+    }
+
+    /// Construct a new buffer from the elements of this buffer at the given indices.
+    ///
+    /// This is an alias of `gathered`, named to pair with `scatter_from`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if any of the indices are out of bounds.
+    #[inline]
+    pub fn gather(&self, indices: &[usize]) -> VecCopy {
+        self.gathered(indices)
+    }
+
+    /// Copy the elements of `src` into this buffer at the given indices, such that the element
+    /// at index `i` of `src` is copied to index `indices[i]` of this buffer.
+    ///
+    /// This is the inverse of `gather`. If an index repeats, the last element copied to it wins.
+    /// If `src` stores a different element type than this buffer, `None` is returned and the
+    /// buffer is left unchanged.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the number of indices differs from the length of `src`, or if any
+    /// of the indices are out of bounds. All indices are checked before any element is copied,
+    /// so the buffer is left unchanged in that case.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let mut buf = VecCopy::from_vec(vec![0u8; 4]);
+    /// let src = VecCopy::from_vec(vec![1u8, 2]);
+    /// buf.scatter_from(&src, &[3, 1]).unwrap();
+    /// assert_eq!(buf.as_slice::<u8>().unwrap(), &[0, 2, 0, 1]);
+    /// ```
+    pub fn scatter_from(&mut self, src: &VecCopy, indices: &[usize]) -> Option<&mut Self> {
+        if src.element_type_id() != self.element_type_id() {
+            return None;
+        }
+        assert_eq!(
+            indices.len(),
+            src.len(),
+            "number of indices doesn't match the source length"
+        );
+        let len = self.len();
+        for &i in indices {
+            assert!(i < len, "index {} is out of bounds for length {}", i, len);
+        }
+        let size = self.element_size;
+        for (&i, bytes) in indices.iter().zip(src.byte_chunks()) {
+            self.data[i * size..(i + 1) * size].copy_from_slice(bytes);
+        }
+        Some(self)
+    }
+
+    /// Reorder the elements of this buffer such that the element at index `permutation[i]` is
+    /// moved to index `i`.
+    ///
+    /// Elements are moved by swapping their bytes along the cycles of the permutation, which is
+    /// left as the identity.
+    pub(crate) fn permute_in_place(&mut self, permutation: &mut [usize]) {
+        debug_assert_eq!(permutation.len(), self.len());
+        for start in 0..permutation.len() {
+            let mut j = start;
+            loop {
+                let k = permutation[j];
+                permutation[j] = j;
+                if k == start {
+                    break;
+                }
+                self.swap(j, k);
+                j = k;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sort_test() {
+        let mut buf = VecCopy::from_vec(vec![5u16, 1, 4, 1, 3]);
+        assert!(buf.sort::<i16>().is_none());
+        assert!(buf.sort_unstable::<i16>().is_none());
+        buf.sort::<u16>().unwrap();
+        assert_eq!(buf.as_slice::<u16>().unwrap(), &[1, 1, 3, 4, 5]);
+
+        let mut buf = VecCopy::from_vec(vec![(2u8, 'b'), (1, 'z'), (2, 'a')]);
+        buf.sort_unstable::<(u8, char)>().unwrap();
+        assert_eq!(
+            buf.as_slice::<(u8, char)>().unwrap(),
+            &[(1, 'z'), (2, 'a'), (2, 'b')]
+        );
+
+        let mut buf = VecCopy::from_vec(vec![0.0f64, -f64::NAN, 1.0, -0.0, f64::INFINITY]);
+        assert!(buf.sort_floats::<f32>().is_none());
+        buf.sort_floats::<f64>().unwrap();
+        let sorted = buf.as_slice::<f64>().unwrap();
+        assert!(sorted[0].is_nan() && sorted[0].is_sign_negative());
+        assert_eq!(&sorted[1..], &[-0.0, 0.0, 1.0, f64::INFINITY]);
+        assert!(sorted[1].is_sign_negative());
+    }
+
+    #[test]
+    fn sort_indices_test() {
+        let keys = VecCopy::from_vec(vec![3i8, -1, 3, 0]);
+        assert!(keys.sort_indices::<u8>().is_none());
+        assert_eq!(keys.sort_indices::<i8>().unwrap(), vec![1, 3, 0, 2]);
+        assert_eq!(
+            keys.sort_indices_by::<i8, _>(|a, b| b.cmp(a)).unwrap(),
+            vec![0, 2, 3, 1]
+        );
+        assert!(VecCopy::with_type::<i8>()
+            .sort_indices::<i8>()
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn permute_test() {
+        let mut buf = VecCopy::from_vec(vec![[0u8; 3], [1; 3], [2; 3], [3; 3], [4; 3]]);
+        let gathered = buf.gathered(&[4, 4, 0]);
+        assert_eq!(
+            gathered.as_slice::<[u8; 3]>().unwrap(),
+            &[[4; 3], [4; 3], [0; 3]]
+        );
+        assert!(buf.gathered(&[]).is_empty());
+
+        buf.permute(&[3, 0, 4, 1, 2]);
+        assert_eq!(
+            buf.as_slice::<[u8; 3]>().unwrap(),
+            &[[3; 3], [0; 3], [4; 3], [1; 3], [2; 3]]
+        );
+    }
+
+    #[test]
+    fn scatter_test() {
+        let mut buf = VecCopy::from_vec(vec![[0u8; 3]; 4]);
+        assert!(buf
+            .scatter_from(&VecCopy::from_vec(vec![1u8, 2, 3]), &[0])
+            .is_none());
+
+        let src = VecCopy::from_vec(vec![[1u8; 3], [2; 3], [3; 3]]);
+        buf.scatter_from(&src, &[2, 0, 2]).unwrap();
+        assert_eq!(
+            buf.as_slice::<[u8; 3]>().unwrap(),
+            &[[2; 3], [0; 3], [3; 3], [0; 3]]
+        );
+
+        // Scattering gathered elements back restores them.
+        let indices = [3, 1];
+        let gathered = buf.gather(&indices);
+        let mut restored = VecCopy::from_vec(vec![[0u8; 3]; 4]);
+        restored.scatter_from(&gathered, &indices).unwrap();
+        assert_eq!(restored.as_slice::<[u8; 3]>().unwrap()[3], [0; 3]);
+        assert_eq!(
+            restored.gather(&indices).as_slice::<[u8; 3]>(),
+            gathered.as_slice::<[u8; 3]>()
+        );
+    }
+
+    #[test]
+    fn scatter_out_of_bounds_test() {
+        let mut buf = VecCopy::from_vec(vec![1u8, 2, 3]);
+        let src = VecCopy::from_vec(vec![4u8, 5]);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            buf.scatter_from(&src, &[0, 3]);
+        }));
+        assert!(result.is_err());
+        // No elements are copied if any index is out of bounds.
+        assert_eq!(buf.as_slice::<u8>().unwrap(), &[1, 2, 3]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn permute_duplicate_test() {
+        VecCopy::from_vec(vec![1u8, 2, 3]).permute(&[0, 1, 1]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn gathered_out_of_bounds_test() {
+        VecCopy::from_vec(vec![1u8, 2, 3]).gathered(&[3]);
+    }
+
+    #[test]
+    fn binary_search_test() {
+        let buf = VecCopy::from_vec(vec![-2.0f64, 0.5, 0.5, 3.0]);
+        assert_eq!(
+            buf.binary_search_by::<f64, _>(|x| x.total_cmp(&3.0)),
+            Some(Ok(3))
+        );
+        assert_eq!(
+            buf.binary_search_by::<f64, _>(|x| x.total_cmp(&-5.0)),
+            Some(Err(0))
+        );
+        assert!(buf
+            .binary_search_by::<f32, _>(|_| Ordering::Equal)
+            .is_none());
+
+        let buf = VecCopy::from_vec(vec![(1u8, 'a'), (1, 'c'), (2, 'a')]);
+        assert_eq!(buf.binary_search(&(1u8, 'b')), Some(Err(1)));
+        assert_eq!(buf.binary_search(&(2u8, 'a')), Some(Ok(2)));
+        assert_eq!(buf.binary_search(&1u8), None);
+    }
+
+    #[test]
+    fn sort_dyn_test() {
+        #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+        struct Key {
+            major: u8,
+            minor: i32,
+        }
+        let keys = vec![
+            Key {
+                major: 2,
+                minor: -1,
+            },
+            Key { major: 1, minor: 5 },
+            Key {
+                major: 2,
+                minor: -3,
+            },
+            Key { major: 0, minor: 7 },
+            Key { major: 1, minor: 5 },
+        ];
+        let mut buf = VecCopy::from_vec(keys.clone());
+        assert!(buf.sort_dyn().is_none());
+        let mut buf = buf.with_ord::<Key>().unwrap();
+        buf.sort_dyn().unwrap();
+        let mut expected = keys;
+        expected.sort();
+        assert_eq!(buf.as_slice::<Key>().unwrap(), expected.as_slice());
+
+        let mut buf = VecCopy::with_type::<u8>().with_ord::<u8>().unwrap();
+        assert!(buf.sort_dyn().unwrap().is_empty());
+    }
+
+    #[test]
+    fn sort_by_test() {
+        let mut buf = VecCopy::from_vec(vec![(3u8, 'a'), (1, 'b'), (3, 'c'), (2, 'd')]);
+        assert!(buf.sort_by::<u8, _>(|a, b| a.cmp(b)).is_none());
+        assert!(buf.sort_by_key::<u8, _, _>(|&x| x).is_none());
+
+        buf.sort_by::<(u8, char), _>(|a, b| b.0.cmp(&a.0)).unwrap();
+        assert_eq!(
+            buf.as_slice::<(u8, char)>().unwrap(),
+            &[(3, 'a'), (3, 'c'), (2, 'd'), (1, 'b')]
+        );
+        buf.sort_by_key::<(u8, char), _, _>(|&(x, _)| x).unwrap();
+        assert_eq!(
+            buf.as_slice::<(u8, char)>().unwrap(),
+            &[(1, 'b'), (2, 'd'), (3, 'a'), (3, 'c')]
+        );
+    }
+}
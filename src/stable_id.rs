@@ -0,0 +1,315 @@
+//! This module defines type identifiers that are stable across compilations.
+//!
+//! A `TypeId` is only meaningful within a single build of a program, so it cannot be used to
+//! identify the element type of a serialized buffer. Instead, element types that are to be
+//! serialized are given a unique name via the `StableType` trait, whose hash is used as a
+//! `StableTypeId`. Since the concrete type cannot be recovered from its hash, types are
+//! registered in a global registry with `register_type`, which maps stable identifiers back to
+//! the types used in the running program. Primitive numeric types are registered by default.
+
+use std::any::TypeId;
+use std::collections::BTreeMap;
+use std::mem::{align_of, size_of};
+use std::sync::{Once, RwLock};
+
+#[cfg(feature = "typed_serde")]
+use crate::traits::{DeserializeFn, SerializeFn};
+use crate::vtable::CopyVTable;
+use crate::Elem;
+
+/// An identifier of a type that is stable across compilations and platforms.
+///
+/// This is the 64-bit FNV-1a hash of the name given to the type by its `StableType`
+/// implementation.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct StableTypeId(u64);
+
+impl StableTypeId {
+    /// Compute the stable identifier of a type with the given unique name.
+    pub const fn from_name(name: &str) -> StableTypeId {
+        const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+        const FNV_PRIME: u64 = 0x0100_0000_01b3;
+        let bytes = name.as_bytes();
+        let mut hash = FNV_OFFSET_BASIS;
+        let mut i = 0;
+        while i < bytes.len() {
+            hash ^= bytes[i] as u64;
+            hash = hash.wrapping_mul(FNV_PRIME);
+            i += 1;
+        }
+        StableTypeId(hash)
+    }
+
+    /// Get the stable identifier of the type `T`.
+    #[inline]
+    pub fn of<T: StableType>() -> StableTypeId {
+        T::STABLE_ID
+    }
+
+    /// Get the stable identifier of a registered type given its `TypeId`.
+    ///
+    /// Returns `None` if the type was not registered with `register_type`.
+    #[inline]
+    pub fn of_type_id(type_id: TypeId) -> Option<StableTypeId> {
+        let registry = registry().read().unwrap();
+        registry.ids.get(&type_id).copied()
+    }
+
+    /// Construct a stable type identifier from its integer representation.
+    #[inline]
+    pub const fn from_u64(id: u64) -> StableTypeId {
+        StableTypeId(id)
+    }
+
+    /// Get the integer representation of this identifier.
+    #[inline]
+    pub const fn as_u64(self) -> u64 {
+        self.0
+    }
+
+    /// Get the `TypeId` of the registered type with this identifier.
+    ///
+    /// Returns `None` if no type was registered with this identifier.
+    #[inline]
+    pub fn type_id(self) -> Option<TypeId> {
+        self.registered().map(|ty| ty.type_id)
+    }
+
+    /// Get the unique name of the registered type with this identifier.
+    ///
+    /// Returns `None` if no type was registered with this identifier.
+    #[inline]
+    pub fn type_name(self) -> Option<&'static str> {
+        self.registered().map(|ty| ty.name)
+    }
+
+    /// Get the registry entry of the type with this identifier.
+    #[inline]
+    pub(crate) fn registered(self) -> Option<RegisteredType> {
+        let registry = registry().read().unwrap();
+        registry.types.get(&self).copied()
+    }
+}
+
+/// A type with a name that uniquely identifies it across compilations.
+///
+/// Implementing this trait and registering the type with `register_type` makes buffers of this
+/// type serializable.
+///
+/// # Safety
+///
+/// Buffers of registered types are built from untrusted bytes when they are deserialized, so any
+/// `size_of::<Self>()` bytes must be a valid value of this type. For instance, types containing
+/// `bool`, `char`, references or enums must not implement this trait.
+///
+/// # Examples
+/// ```
+/// use data_buffer::{register_type, StableType, StableTypeId, VecCopy};
+///
+/// #[derive(Copy, Clone, PartialEq)]
+/// struct Point([f32; 3]);
+///
+/// // Any bytes make up a valid `Point`.
+/// unsafe impl StableType for Point {
+///     const STABLE_NAME: &'static str = "my_crate::Point";
+/// }
+///
+/// register_type::<Point>();
+/// let buf = VecCopy::from_vec(vec![Point([0.0; 3])]);
+/// assert_eq!(buf.stable_type_id(), Some(StableTypeId::from_name("my_crate::Point")));
+/// ```
+pub unsafe trait StableType: Elem {
+    /// A name of this type that is unique among all registered types.
+    const STABLE_NAME: &'static str;
+    /// The size of the scalars making up this type, if values of this type can be converted
+    /// between byte orders by reversing the bytes of each scalar.
+    ///
+    /// This is the size of the type itself for primitive numeric types, and for instance `4` for
+    /// `[f32; 3]`. Buffers of types without a scalar size cannot be deserialized from data with
+    /// a foreign byte order.
+    const SCALAR_SIZE: Option<usize> = None;
+    /// The stable identifier of this type.
+    const STABLE_ID: StableTypeId = StableTypeId::from_name(Self::STABLE_NAME);
+}
+
+macro_rules! impl_stable_type {
+    ($($t:ty),*) => {
+        $(
+            unsafe impl StableType for $t {
+                const STABLE_NAME: &'static str = stringify!($t);
+                const SCALAR_SIZE: Option<usize> = Some(size_of::<$t>());
+            }
+        )*
+    };
+}
+
+impl_stable_type!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+/// Information about a registered type.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct RegisteredType {
+    pub(crate) type_id: TypeId,
+    pub(crate) name: &'static str,
+    pub(crate) size: usize,
+    pub(crate) align: usize,
+    pub(crate) scalar_size: Option<usize>,
+    /// The table of functions given to buffers of this type.
+    pub(crate) vtable: CopyVTable,
+    /// Functions for serializing elements individually.
+    #[cfg(feature = "typed_serde")]
+    pub(crate) serde: Option<(SerializeFn, DeserializeFn)>,
+}
+
+/// A bidirectional map between registered types and their stable identifiers.
+struct Registry {
+    types: BTreeMap<StableTypeId, RegisteredType>,
+    ids: BTreeMap<TypeId, StableTypeId>,
+}
+
+static REGISTRY: RwLock<Registry> = RwLock::new(Registry {
+    types: BTreeMap::new(),
+    ids: BTreeMap::new(),
+});
+
+/// Get the global registry, registering primitive types on first access.
+fn registry() -> &'static RwLock<Registry> {
+    static REGISTER_PRIMITIVES: Once = Once::new();
+    REGISTER_PRIMITIVES.call_once(|| {
+        let mut registry = REGISTRY.write().unwrap();
+        macro_rules! insert_primitives {
+            ($($t:ty),*) => { $( registry.insert::<$t>().unwrap(); )* };
+        }
+        insert_primitives!(
+            u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64
+        );
+    });
+    &REGISTRY
+}
+
+impl Registry {
+    /// Insert the type `T` into this registry unless it is already registered.
+    ///
+    /// If a different type with the same identifier is registered, its name is returned as the
+    /// error.
+    fn insert<T: StableType>(&mut self) -> Result<StableTypeId, &'static str> {
+        let id = T::STABLE_ID;
+        let ty = RegisteredType {
+            type_id: TypeId::of::<T>(),
+            name: T::STABLE_NAME,
+            size: size_of::<T>(),
+            align: align_of::<T>(),
+            scalar_size: T::SCALAR_SIZE,
+            vtable: CopyVTable::of_elem::<T>(),
+            #[cfg(feature = "typed_serde")]
+            serde: None,
+        };
+        if let Some(existing) = self.types.get(&id) {
+            return if existing.type_id == ty.type_id {
+                Ok(id)
+            } else {
+                Err(existing.name)
+            };
+        }
+        self.types.insert(id, ty);
+        self.ids.insert(ty.type_id, id);
+        Ok(id)
+    }
+}
+
+/// Register the type `T` in the global type registry, and return its stable identifier.
+///
+/// Registered types can be mapped back from their stable identifiers, which is required to
+/// deserialize buffers. Registering the same type more than once has no effect.
+///
+/// # Panics
+///
+/// This function panics if `T` is a zero-sized type, or if a different type with the same stable
+/// identifier was already registered.
+pub fn register_type<T: StableType>() -> StableTypeId {
+    assert_ne!(
+        size_of::<T>(),
+        0,
+        "zero-sized type `{}` cannot be registered",
+        T::STABLE_NAME
+    );
+    // Release the lock before panicking to avoid poisoning the registry.
+    let result = registry().write().unwrap().insert::<T>();
+    result.unwrap_or_else(|existing| {
+        panic!(
+            "stable type id of `{}` collides with the registered type `{}`",
+            T::STABLE_NAME,
+            existing
+        )
+    })
+}
+
+/// Register the type `T` together with the functions used to serialize its elements
+/// individually.
+#[cfg(feature = "typed_serde")]
+pub(crate) fn register_serde_fns<T: StableType>(
+    serialize: SerializeFn,
+    deserialize: DeserializeFn,
+) -> StableTypeId {
+    let id = register_type::<T>();
+    let mut registry = registry().write().unwrap();
+    if let Some(ty) = registry.types.get_mut(&id) {
+        ty.serde = Some((serialize, deserialize));
+    }
+    id
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_test() {
+        #[derive(Copy, Clone, PartialEq)]
+        struct Foo(u8);
+        unsafe impl StableType for Foo {
+            const STABLE_NAME: &'static str = "data_buffer::tests::Foo";
+        }
+
+        // Identifiers don't depend on the build.
+        assert_eq!(StableTypeId::of::<u8>().as_u64(), 0x08c4_8207_b567_53d8);
+        assert_eq!(
+            StableTypeId::of_type_id(TypeId::of::<f64>()),
+            Some(f64::STABLE_ID)
+        );
+        assert_eq!(f64::STABLE_ID.type_name(), Some("f64"));
+
+        assert_eq!(StableTypeId::of_type_id(TypeId::of::<Foo>()), None);
+        assert_eq!(Foo::STABLE_ID.type_id(), None);
+        let id = register_type::<Foo>();
+        assert_eq!(register_type::<Foo>(), id);
+        assert_eq!(id.type_id(), Some(TypeId::of::<Foo>()));
+        assert_eq!(StableTypeId::of_type_id(TypeId::of::<Foo>()), Some(id));
+    }
+
+    #[test]
+    #[should_panic(expected = "collides")]
+    fn collision_test() {
+        #[derive(Copy, Clone, PartialEq)]
+        struct Bar(u8);
+        unsafe impl StableType for Bar {
+            const STABLE_NAME: &'static str = "u8";
+        }
+        register_type::<Bar>();
+    }
+
+    #[test]
+    #[should_panic(expected = "zero-sized")]
+    fn zero_sized_test() {
+        #[derive(Copy, Clone, PartialEq)]
+        struct Unit;
+        unsafe impl StableType for Unit {
+            const STABLE_NAME: &'static str = "data_buffer::tests::Unit";
+        }
+        register_type::<Unit>();
+    }
+}
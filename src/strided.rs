@@ -0,0 +1,301 @@
+//! This module defines views into one field of each record stored in a buffer.
+//!
+//! Buffers of records, such as interleaved vertex buffers produced by `VecCopy::interleave`, store
+//! each field at a fixed offset from the start of its record. A strided view steps through the
+//! buffer one record at a time starting at the offset of a field, so that field can be read or
+//! written without splitting the records up.
+//!
+//! The view doesn't know the type or size of the field. Each chunk of a view spans from the field
+//! offset to the end of its record, and typed access reads or writes the leading bytes of each
+//! chunk. Since the bytes are not type checked, typed access is unsafe.
+
+use std::iter::FusedIterator;
+use std::mem::size_of;
+use std::ptr;
+
+use crate::{Elem, VecCopy};
+
+/// An immutable view into one field of each record in a buffer.
+///
+/// This `struct` is created by the `strided_view` method on `VecCopy`.
+#[derive(Copy, Clone, Debug)]
+pub struct StridedView<'a> {
+    /// The bytes of all viewed records, starting at the field offset.
+    data: &'a [u8],
+    /// The number of bytes between consecutive chunks.
+    stride: usize,
+    /// The number of bytes in each chunk.
+    chunk_size: usize,
+    /// The number of chunks.
+    len: usize,
+}
+
+/// A mutable view into one field of each record in a buffer.
+///
+/// This `struct` is created by the `strided_view_mut` method on `VecCopy`.
+#[derive(Debug)]
+pub struct StridedViewMut<'a> {
+    /// The bytes of all viewed records, starting at the field offset.
+    data: &'a mut [u8],
+    /// The number of bytes between consecutive chunks.
+    stride: usize,
+    /// The number of bytes in each chunk.
+    chunk_size: usize,
+    /// The number of chunks.
+    len: usize,
+}
+
+/// Compute the byte range and chunk size of a strided view into `num_bytes` bytes.
+fn strided_layout(
+    num_bytes: usize,
+    offset_bytes: usize,
+    stride_bytes: usize,
+    count: usize,
+) -> Option<(usize, usize)> {
+    if offset_bytes >= stride_bytes || count.checked_mul(stride_bytes)? > num_bytes {
+        return None;
+    }
+    let end = if count == 0 {
+        offset_bytes
+    } else {
+        count * stride_bytes
+    };
+    Some((end, stride_bytes - offset_bytes))
+}
+
+impl VecCopy {
+    /// Borrow `count` chunks of the bytes of this buffer, starting at `offset_bytes` and spaced
+    /// `stride_bytes` apart.
+    ///
+    /// This views the field at `offset_bytes` of the first `count` records of `stride_bytes`
+    /// bytes each. Each chunk extends to the end of its record. Returns `None` if `offset_bytes`
+    /// is not less than `stride_bytes`, or if the buffer holds fewer than `count` records.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let positions = VecCopy::from_vec(vec![[0.0f32; 3], [1.0; 3]]);
+    /// let ids = VecCopy::from_vec(vec![7u16, 8]);
+    /// let vertices = VecCopy::interleave(&[&positions, &ids]).unwrap();
+    /// let view = vertices.strided_view(12, 14, 2).unwrap();
+    /// let ids: Vec<u16> = unsafe { view.iter::<u16>() }.unwrap().collect();
+    /// assert_eq!(ids, vec![7, 8]);
+    /// ```
+    pub fn strided_view(
+        &self,
+        offset_bytes: usize,
+        stride_bytes: usize,
+        count: usize,
+    ) -> Option<StridedView<'_>> {
+        let bytes = self.as_bytes();
+        let (end, chunk_size) = strided_layout(bytes.len(), offset_bytes, stride_bytes, count)?;
+        Some(StridedView {
+            data: &bytes[offset_bytes..end],
+            stride: stride_bytes,
+            chunk_size,
+            len: count,
+        })
+    }
+
+    /// Mutably borrow `count` chunks of the bytes of this buffer, starting at `offset_bytes` and
+    /// spaced `stride_bytes` apart.
+    ///
+    /// See `strided_view` for details.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let mut records = VecCopy::from_vec(vec![[0u8; 4]; 3]);
+    /// let mut view = records.strided_view_mut(2, 4, 3).unwrap();
+    /// unsafe { view.fill(0xffffu16) }.unwrap();
+    /// assert_eq!(records.as_slice::<[u8; 4]>().unwrap(), &[[0, 0, 255, 255]; 3]);
+    /// ```
+    pub fn strided_view_mut(
+        &mut self,
+        offset_bytes: usize,
+        stride_bytes: usize,
+        count: usize,
+    ) -> Option<StridedViewMut<'_>> {
+        // This is safe since views only expose their bytes through unsafe functions.
+        let bytes = unsafe { self.as_bytes_mut() };
+        let (end, chunk_size) = strided_layout(bytes.len(), offset_bytes, stride_bytes, count)?;
+        Some(StridedViewMut {
+            data: &mut bytes[offset_bytes..end],
+            stride: stride_bytes,
+            chunk_size,
+            len: count,
+        })
+    }
+}
+
+// Implement the read-only API shared by both view types.
+macro_rules! impl_strided_view_base {
+    () => {
+        /// Get the number of chunks in this view.
+        #[inline]
+        pub fn len(&self) -> usize {
+            self.len
+        }
+
+        /// Check if there are any chunks in this view.
+        #[inline]
+        pub fn is_empty(&self) -> bool {
+            self.len == 0
+        }
+
+        /// Get the number of bytes between the starts of consecutive chunks.
+        #[inline]
+        pub fn stride(&self) -> usize {
+            self.stride
+        }
+
+        /// Get the number of bytes in each chunk.
+        #[inline]
+        pub fn chunk_size(&self) -> usize {
+            self.chunk_size
+        }
+
+        /// Iterate over the chunks of bytes in this view.
+        #[inline]
+        pub fn byte_chunks(&self) -> ByteChunks<'_> {
+            ByteChunks {
+                data: &self.data,
+                stride: self.stride,
+                chunk_size: self.chunk_size,
+                len: self.len,
+            }
+        }
+
+        /// Iterate over typed values read from the leading bytes of each chunk.
+        ///
+        /// Returns `None` if `T` is larger than the chunks.
+        ///
+        /// # Safety
+        ///
+        /// The leading bytes of each chunk must be a valid representation of `T`.
+        #[inline]
+        pub unsafe fn iter<T: Elem>(&self) -> Option<impl Iterator<Item = T> + '_> {
+            if size_of::<T>() > self.chunk_size {
+                return None;
+            }
+            // The chunks are not necessarily aligned, so values are read unaligned.
+            Some(
+                self.byte_chunks()
+                    .map(|chunk| ptr::read_unaligned(chunk.as_ptr() as *const T)),
+            )
+        }
+    };
+}
+
+impl StridedView<'_> {
+    impl_strided_view_base!();
+}
+
+impl StridedViewMut<'_> {
+    impl_strided_view_base!();
+
+    /// Call `f` with each chunk of mutable bytes in this view.
+    ///
+    /// # Safety
+    ///
+    /// The modified bytes must form valid representations of the elements of the viewed buffer.
+    #[inline]
+    pub unsafe fn for_each_chunk_mut<F: FnMut(&mut [u8])>(&mut self, mut f: F) {
+        for i in 0..self.len {
+            let start = i * self.stride;
+            f(&mut self.data[start..start + self.chunk_size]);
+        }
+    }
+
+    /// Write the given value into the leading bytes of each chunk.
+    ///
+    /// Returns `None` if `T` is larger than the chunks, in which case the buffer is left
+    /// unchanged.
+    ///
+    /// # Safety
+    ///
+    /// The bytes of `value` must form valid representations of the elements of the viewed buffer
+    /// when written into each chunk.
+    #[inline]
+    pub unsafe fn fill<T: Elem>(&mut self, value: T) -> Option<&mut Self> {
+        if size_of::<T>() > self.chunk_size {
+            return None;
+        }
+        // The chunks are not necessarily aligned, so values are written unaligned.
+        self.for_each_chunk_mut(|chunk| ptr::write_unaligned(chunk.as_mut_ptr() as *mut T, value));
+        Some(self)
+    }
+}
+
+/// An iterator over the chunks of bytes of a strided view.
+///
+/// This `struct` is created by the `byte_chunks` method on `StridedView` and `StridedViewMut`.
+#[derive(Clone, Debug)]
+pub struct ByteChunks<'a> {
+    data: &'a [u8],
+    stride: usize,
+    chunk_size: usize,
+    len: usize,
+}
+
+impl<'a> Iterator for ByteChunks<'a> {
+    type Item = &'a [u8];
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a [u8]> {
+        if self.len == 0 {
+            return None;
+        }
+        let chunk = &self.data[..self.chunk_size];
+        self.len -= 1;
+        if self.len > 0 {
+            self.data = &self.data[self.stride..];
+        }
+        Some(chunk)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl ExactSizeIterator for ByteChunks<'_> {}
+impl FusedIterator for ByteChunks<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strided_view_test() {
+        let a = VecCopy::from_vec(vec![1u8, 2, 3]);
+        let b = VecCopy::from_vec(vec![10u32, 20, 30]);
+        let mut records = VecCopy::interleave(&[&a, &b]).unwrap();
+
+        assert!(records.strided_view(5, 5, 3).is_none());
+        assert!(records.strided_view(0, 5, 4).is_none());
+        assert!(records.strided_view(0, 0, 0).is_none());
+        assert!(records.strided_view(1, 5, 0).unwrap().is_empty());
+
+        let view = records.strided_view(1, 5, 3).unwrap();
+        assert_eq!((view.len(), view.stride(), view.chunk_size()), (3, 5, 4));
+        assert_eq!(view.byte_chunks().len(), 3);
+        assert!(unsafe { view.iter::<u64>() }.is_none());
+        let values: Vec<u32> = unsafe { view.iter::<u32>() }.unwrap().collect();
+        assert_eq!(values, vec![10, 20, 30]);
+        let view = records.strided_view(0, 5, 2).unwrap();
+        let firsts: Vec<u8> = view.byte_chunks().map(|chunk| chunk[0]).collect();
+        assert_eq!(firsts, vec![1, 2]);
+
+        let mut view = records.strided_view_mut(1, 5, 2).unwrap();
+        unsafe {
+            assert!(view.fill([0u8; 5]).is_none());
+            view.fill(40u32).unwrap();
+            view.for_each_chunk_mut(|chunk| chunk[0] += 1);
+        }
+        let fields = unsafe { records.deinterleave(&[&a, &b]) }.unwrap();
+        assert_eq!(fields[0], a);
+        assert_eq!(fields[1].as_slice::<u32>().unwrap(), &[41, 41, 30]);
+    }
+}
@@ -0,0 +1,304 @@
+//! This module defines a collection of named buffers with equal lengths.
+//!
+//! A `DataTable` stores each column of tabular data, such as the attributes of mesh vertices or
+//! points in a point cloud, in its own buffer. Every column has one element per row, which is
+//! enforced when columns are inserted, and rows are removed or reordered in all columns at once,
+//! so the columns can't get out of sync.
+
+use std::any::Any;
+use std::collections::BTreeMap;
+
+use crate::sort::assert_permutation;
+use crate::{LengthMismatchError, VecCopy};
+
+/// A collection of equal length buffers keyed by name.
+///
+/// Columns are ordered by name.
+///
+/// # Examples
+/// ```
+/// use data_buffer::{DataTable, VecCopy};
+/// let mut table = DataTable::new();
+/// table.insert("id", VecCopy::from_vec(vec![1u32, 2, 3])).unwrap();
+/// table.insert("mass", VecCopy::from_vec(vec![0.5f32, 1.0, 2.0])).unwrap();
+/// assert_eq!(table.num_rows(), 3);
+/// assert_eq!(table.column_as::<f32>("mass").unwrap(), &[0.5, 1.0, 2.0]);
+///
+/// // Columns must have one element per row.
+/// assert!(table.insert("flag", VecCopy::from_vec(vec![true])).is_err());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DataTable {
+    pub(crate) columns: BTreeMap<String, VecCopy>,
+}
+
+impl DataTable {
+    /// Construct an empty table.
+    #[inline]
+    pub fn new() -> Self {
+        DataTable::default()
+    }
+
+    /// The number of rows in this table, which is the length of every column.
+    ///
+    /// A table without columns has no rows.
+    #[inline]
+    pub fn num_rows(&self) -> usize {
+        self.columns.values().next().map_or(0, VecCopy::len)
+    }
+
+    /// The number of columns in this table.
+    #[inline]
+    pub fn num_columns(&self) -> usize {
+        self.columns.len()
+    }
+
+    /// Check if this table has no columns.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.columns.is_empty()
+    }
+
+    /// Add a column to this table, returning the column previously stored with the same name.
+    ///
+    /// If the length of the column differs from the number of rows, an error is returned and the
+    /// table is left unchanged. A column of any length can be inserted into a table without
+    /// other columns.
+    pub fn insert(
+        &mut self,
+        name: impl Into<String>,
+        column: VecCopy,
+    ) -> Result<Option<VecCopy>, LengthMismatchError> {
+        let name = name.into();
+        let other = self
+            .columns
+            .iter()
+            .find(|(other_name, _)| **other_name != name);
+        if let Some((_, other)) = other {
+            if other.len() != column.len() {
+                return Err(LengthMismatchError {
+                    expected: other.len(),
+                    found: column.len(),
+                });
+            }
+        }
+        Ok(self.columns.insert(name, column))
+    }
+
+    /// Remove the column with the given name from this table.
+    #[inline]
+    pub fn remove(&mut self, name: &str) -> Option<VecCopy> {
+        self.columns.remove(name)
+    }
+
+    /// Check if this table has a column with the given name.
+    #[inline]
+    pub fn contains_column(&self, name: &str) -> bool {
+        self.columns.contains_key(name)
+    }
+
+    /// Get the column with the given name.
+    #[inline]
+    pub fn column(&self, name: &str) -> Option<&VecCopy> {
+        self.columns.get(name)
+    }
+
+    /// Get the column with the given name as a typed slice.
+    ///
+    /// Returns `None` if there is no such column or if `T` doesn't match its element type.
+    #[inline]
+    pub fn column_as<T: Any>(&self, name: &str) -> Option<&[T]> {
+        self.columns.get(name)?.as_slice::<T>()
+    }
+
+    /// Get the column with the given name as a typed mutable slice.
+    ///
+    /// Returns `None` if there is no such column or if `T` doesn't match its element type.
+    #[inline]
+    pub fn column_as_mut<T: Any>(&mut self, name: &str) -> Option<&mut [T]> {
+        self.columns.get_mut(name)?.as_mut_slice::<T>()
+    }
+
+    /// Iterate over the column names in order.
+    #[inline]
+    pub fn column_names(&self) -> impl Iterator<Item = &str> + '_ {
+        self.columns.keys().map(String::as_str)
+    }
+
+    /// Iterate over the columns and their names in order.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &VecCopy)> + '_ {
+        self.columns
+            .iter()
+            .map(|(name, column)| (name.as_str(), column))
+    }
+
+    /// Remove the row at index `i` from every column, shifting all rows after it up.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `i` is out of bounds, in which case no column is modified.
+    pub fn remove_row(&mut self, i: usize) {
+        self.assert_row_in_bounds(i);
+        for column in self.columns.values_mut() {
+            column.remove_bytes(i);
+        }
+    }
+
+    /// Remove the row at index `i` from every column, replacing it with the last row.
+    ///
+    /// This does not preserve the order of the rows, but is O(1) for each column.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `i` is out of bounds, in which case no column is modified.
+    pub fn swap_remove_row(&mut self, i: usize) {
+        self.assert_row_in_bounds(i);
+        for column in self.columns.values_mut() {
+            column.swap_remove_bytes(i);
+        }
+    }
+
+    /// Reorder the rows of every column such that the row at index `permutation[i]` is moved to
+    /// index `i`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `permutation` is not a permutation of the row indices, in which
+    /// case no column is modified.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::{DataTable, VecCopy};
+    /// let mut table = DataTable::new();
+    /// table.insert("key", VecCopy::from_vec(vec![3u8, 1, 2])).unwrap();
+    /// table.insert("value", VecCopy::from_vec(vec!['c', 'a', 'b'])).unwrap();
+    /// let order = table.column("key").unwrap().sort_indices::<u8>().unwrap();
+    /// table.permute_rows(&order);
+    /// assert_eq!(table.column_as::<char>("value").unwrap(), &['a', 'b', 'c']);
+    /// ```
+    pub fn permute_rows(&mut self, permutation: &[usize]) {
+        assert_permutation(permutation, self.num_rows());
+        let mut scratch = Vec::with_capacity(permutation.len());
+        for column in self.columns.values_mut() {
+            scratch.clear();
+            scratch.extend_from_slice(permutation);
+            column.permute_in_place(&mut scratch);
+        }
+    }
+
+    /// Check that `i` is a valid row index.
+    #[inline]
+    fn assert_row_in_bounds(&self, i: usize) {
+        let num_rows = self.num_rows();
+        assert!(
+            i < num_rows,
+            "row {} is out of bounds for table with {} rows",
+            i,
+            num_rows
+        );
+    }
+
+    /// Convert this table into its columns and their names in order.
+    #[inline]
+    pub fn into_columns(self) -> impl Iterator<Item = (String, VecCopy)> {
+        self.columns.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_test() {
+        let mut table = DataTable::new();
+        assert!(table.is_empty());
+        assert_eq!(table.num_rows(), 0);
+
+        let positions = VecCopy::from_vec(vec![[0.0f32; 3]; 4]);
+        assert_eq!(table.insert("position", positions.clone()), Ok(None));
+        // Replacing the only column may change the number of rows.
+        assert_eq!(
+            table.insert("position", VecCopy::from_vec(vec![[1.0f32; 3]; 2])),
+            Ok(Some(positions.clone()))
+        );
+        assert_eq!(
+            table.insert("position", positions),
+            Ok(Some(VecCopy::from_vec(vec![[1.0f32; 3]; 2])))
+        );
+        table
+            .insert(
+                String::from("index"),
+                VecCopy::from_vec(vec![0usize, 1, 2, 3]),
+            )
+            .unwrap();
+        assert_eq!(
+            table.insert("index", VecCopy::from_vec(vec![0usize])),
+            Err(LengthMismatchError {
+                expected: 4,
+                found: 1
+            })
+        );
+        assert_eq!(table.num_rows(), 4);
+        assert_eq!(table.num_columns(), 2);
+        assert_eq!(
+            table.column_names().collect::<Vec<_>>(),
+            vec!["index", "position"]
+        );
+
+        assert!(table.contains_column("index"));
+        assert!(table.column_as::<u32>("index").is_none());
+        assert!(table.column_as::<usize>("normal").is_none());
+        table.column_as_mut::<usize>("index").unwrap().reverse();
+        assert_eq!(table.column("index").unwrap().get::<usize>(0), Some(3));
+
+        let index = table.remove("index").unwrap();
+        assert_eq!(index.len(), 4);
+        assert!(!table.contains_column("index"));
+        let columns: Vec<_> = table.into_columns().collect();
+        assert_eq!(columns.len(), 1);
+        assert_eq!(columns[0].0, "position");
+    }
+
+    #[test]
+    fn row_test() {
+        let mut table = DataTable::new();
+        table
+            .insert("a", VecCopy::from_vec(vec![0u8, 1, 2, 3, 4]))
+            .unwrap();
+        table
+            .insert(
+                "b",
+                VecCopy::from_vec(vec![[0u16; 2], [1; 2], [2; 2], [3; 2], [4; 2]]),
+            )
+            .unwrap();
+
+        table.remove_row(1);
+        assert_eq!(table.num_rows(), 4);
+        table.swap_remove_row(0);
+        assert_eq!(table.column_as::<u8>("a").unwrap(), &[4, 2, 3]);
+        table.permute_rows(&[2, 0, 1]);
+        assert_eq!(table.column_as::<u8>("a").unwrap(), &[3, 4, 2]);
+        assert_eq!(
+            table.column_as::<[u16; 2]>("b").unwrap(),
+            &[[3; 2], [4; 2], [2; 2]]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn remove_row_out_of_bounds_test() {
+        let mut table = DataTable::new();
+        table.insert("a", VecCopy::from_vec(vec![1u8])).unwrap();
+        table.remove_row(1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn permute_rows_invalid_test() {
+        let mut table = DataTable::new();
+        table.insert("a", VecCopy::from_vec(vec![1u8, 2])).unwrap();
+        table.permute_rows(&[0, 0]);
+    }
+}
@@ -0,0 +1,156 @@
+//! This module defines wrappers around `VecCopy` that certify the thread safety of its elements.
+//!
+//! Since the element type of a `VecCopy` is erased, the compiler cannot tell whether it is safe to
+//! move or share a buffer across threads. Instead, whether the element type is `Send` or `Sync`
+//! is recorded in the buffer itself (see `VecCopy::with_send` and `VecCopy::with_sync`), and
+//! checked at runtime when converting a buffer into one of the wrappers below. Generic code that
+//! sends buffers to other threads can then require these wrappers in its signatures.
+
+use std::ops::Deref;
+
+use crate::VecCopy;
+
+/// A `VecCopy` whose elements are known to be `Send`.
+///
+/// This wrapper dereferences to the underlying buffer, but doesn't provide mutable access to it,
+/// since that would allow it to be reinitialized with a different element type.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SendVecCopy(VecCopy);
+
+/// A `VecCopy` whose elements are known to be `Send` and `Sync`.
+///
+/// This wrapper dereferences to the underlying buffer, but doesn't provide mutable access to it,
+/// since that would allow it to be reinitialized with a different element type.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SyncVecCopy(VecCopy);
+
+impl VecCopy {
+    /// Returns `true` if the element type of this buffer is known to be `Send`.
+    #[inline]
+    pub fn is_send(&self) -> bool {
+        self.vtable.send
+    }
+
+    /// Returns `true` if the element type of this buffer is known to be `Sync`.
+    #[inline]
+    pub fn is_sync(&self) -> bool {
+        self.vtable.sync
+    }
+
+    /// Convert this buffer into a `SendVecCopy`, which can be safely moved to another thread.
+    ///
+    /// Returns `None` if the element type is not known to be `Send`.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let buf = VecCopy::from_vec(vec![1u32, 2, 3]).into_send().unwrap();
+    /// let handle = std::thread::spawn(move || buf.len());
+    /// assert_eq!(handle.join().unwrap(), 3);
+    ///
+    /// // Raw pointers are not `Send`.
+    /// let buf = VecCopy::from_vec(vec![std::ptr::null::<u8>()]);
+    /// assert!(buf.into_send().is_none());
+    /// ```
+    #[inline]
+    pub fn into_send(self) -> Option<SendVecCopy> {
+        if self.is_send() {
+            Some(SendVecCopy(self))
+        } else {
+            None
+        }
+    }
+
+    /// Convert this buffer into a `SyncVecCopy`, which can be safely moved to or shared with
+    /// other threads.
+    ///
+    /// Returns `None` if the element type is not known to be both `Send` and `Sync`.
+    #[inline]
+    pub fn into_sync(self) -> Option<SyncVecCopy> {
+        if self.is_send() && self.is_sync() {
+            Some(SyncVecCopy(self))
+        } else {
+            None
+        }
+    }
+}
+
+impl SendVecCopy {
+    /// Unwrap the underlying buffer.
+    #[inline]
+    pub fn into_inner(self) -> VecCopy {
+        self.0
+    }
+}
+
+impl SyncVecCopy {
+    /// Unwrap the underlying buffer.
+    #[inline]
+    pub fn into_inner(self) -> VecCopy {
+        self.0
+    }
+}
+
+impl Deref for SendVecCopy {
+    type Target = VecCopy;
+    #[inline]
+    fn deref(&self) -> &VecCopy {
+        &self.0
+    }
+}
+
+impl Deref for SyncVecCopy {
+    type Target = VecCopy;
+    #[inline]
+    fn deref(&self) -> &VecCopy {
+        &self.0
+    }
+}
+
+impl From<SyncVecCopy> for SendVecCopy {
+    #[inline]
+    fn from(buf: SyncVecCopy) -> Self {
+        SendVecCopy(buf.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn thread_safety_markers() {
+        #[derive(Copy, Clone, Debug, PartialEq)]
+        struct Point {
+            x: f32,
+            y: f32,
+        }
+
+        // Primitives are recorded at construction.
+        let buf = VecCopy::from_vec(vec![1.0f64, 2.0]);
+        assert!(buf.is_send() && buf.is_sync());
+
+        // Other types must be recorded explicitly.
+        let points = VecCopy::from_vec(vec![Point { x: 1.0, y: 2.0 }]);
+        assert!(!points.is_send() && !points.is_sync());
+        assert!(points.clone().into_send().is_none());
+        let points = points.with_send::<Point>().unwrap();
+        assert!(points.clone().into_sync().is_none());
+        assert!(points.clone().into_send().is_some());
+        assert!(points.clone().with_sync::<f32>().is_none());
+        let points = points.with_sync::<Point>().unwrap().into_sync().unwrap();
+
+        let shared = Arc::new(points);
+        let handle = {
+            let shared = Arc::clone(&shared);
+            std::thread::spawn(move || shared.get::<Point>(0))
+        };
+        assert_eq!(handle.join().unwrap(), Some(Point { x: 1.0, y: 2.0 }));
+
+        // Resetting the type through the unwrapped buffer resets the markers.
+        let mut buf = Arc::try_unwrap(shared).unwrap().into_inner();
+        buf.copy_from_slice(&[std::ptr::null::<u8>()]);
+        assert!(!buf.is_send());
+    }
+}
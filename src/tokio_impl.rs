@@ -0,0 +1,78 @@
+//! This module implements asynchronous streaming of buffers with `tokio`.
+//!
+//! Buffers are streamed in the raw binary format, so the header describing the element type
+//! precedes the data. The data is transferred in chunks, which lets the writer apply
+//! backpressure to large buffers instead of requiring the whole payload to be accepted at once.
+
+use std::io;
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::raw::{self, Header, HEADER_SIZE};
+use crate::{DecodeError, VecCopy};
+
+/// The maximum number of bytes written at a time.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+impl VecCopy {
+    /// Asynchronously write this buffer to the given writer in the raw binary format.
+    ///
+    /// This is the asynchronous counterpart of [`encode_raw`](VecCopy::encode_raw), and the
+    /// element type must be registered with [`register_type`] in the same way.
+    ///
+    /// [`register_type`]: crate::register_type
+    pub async fn write_to<W: AsyncWrite + Unpin>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&self.raw_header()?).await?;
+        for chunk in self.data.chunks(CHUNK_SIZE) {
+            writer.write_all(chunk).await?;
+        }
+        writer.flush().await
+    }
+
+    /// Asynchronously read a buffer in the raw binary format from the given reader.
+    ///
+    /// This is the asynchronous counterpart of [`decode_raw`](VecCopy::decode_raw), and reports
+    /// errors in the same way.
+    pub async fn read_from<R: AsyncRead + Unpin>(reader: &mut R) -> io::Result<VecCopy> {
+        let invalid = |err: DecodeError| io::Error::new(io::ErrorKind::InvalidData, err);
+        let mut header = [0u8; HEADER_SIZE];
+        reader.read_exact(&mut header).await?;
+        let header = Header::decode_uncompressed(&header).map_err(invalid)?;
+        // Validate the type before reading any data.
+        raw::registered_type(header.element_type, header.element_size).map_err(invalid)?;
+        let payload_size = header.payload_size().map_err(invalid)?;
+        // Don't trust the header with the size of the allocation.
+        let mut payload = Vec::new();
+        reader.take(payload_size).read_to_end(&mut payload).await?;
+        VecCopy::decode_raw_payload(&header, payload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn async_round_trip_test() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            // Use a small pipe to exercise backpressure.
+            let (mut writer, mut reader) = tokio::io::duplex(1024);
+            let buf = VecCopy::from_vec((0..100_000).map(|i| i as f32).collect::<Vec<_>>());
+            let (written, read) = tokio::join!(buf.write_to(&mut writer), async {
+                VecCopy::read_from(&mut reader).await
+            });
+            written.unwrap();
+            assert_eq!(read.unwrap(), buf);
+
+            let mut bytes = Vec::new();
+            buf.write_to(&mut bytes).await.unwrap();
+            let err = VecCopy::read_from(&mut &bytes[..bytes.len() - 1])
+                .await
+                .unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+        });
+    }
+}
@@ -0,0 +1,105 @@
+//! This module defines macros instrumenting expensive operations with `tracing`.
+//!
+//! With the `tracing` feature, reallocations, large copies, casts and (de)serialization emit
+//! spans and events with the target `data_buffer` at the `DEBUG` level, including the number of
+//! bytes involved. Without the feature, the macros expand to nothing.
+
+/// Copies of at least this many bytes are reported by `trace_copy`.
+#[cfg(feature = "tracing")]
+pub(crate) const LARGE_COPY_BYTES: usize = 1 << 20;
+
+/// Enter a span with the given name and fields until the end of the enclosing scope.
+macro_rules! trace_span {
+    ($name:literal $(, $($fields:tt)*)?) => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!(target: "data_buffer", $name $(, $($fields)*)?).entered();
+    };
+}
+
+/// Emit an event with the given fields and message.
+macro_rules! trace_event {
+    ($($args:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(target: "data_buffer", $($args)*);
+    };
+}
+
+/// Emit an event for a copy of the given number of bytes if it is large.
+macro_rules! trace_copy {
+    ($num_bytes:expr) => {
+        #[cfg(feature = "tracing")]
+        {
+            let num_bytes: usize = $num_bytes;
+            if num_bytes >= crate::trace::LARGE_COPY_BYTES {
+                tracing::debug!(target: "data_buffer", num_bytes, "copying bytes");
+            }
+        }
+    };
+}
+
+pub(crate) use {trace_copy, trace_event, trace_span};
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    use crate::VecCopy;
+
+    /// A subscriber recording the names of all spans and the messages of all events.
+    #[derive(Default)]
+    struct Recorder {
+        next_id: AtomicU64,
+        names: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Subscriber for Recorder {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            self.names
+                .lock()
+                .unwrap()
+                .push(span.metadata().name().to_string());
+            Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+        }
+        fn record(&self, _: &Id, _: &Record<'_>) {}
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+        fn event(&self, event: &Event<'_>) {
+            struct Message(String);
+            impl tracing::field::Visit for Message {
+                fn record_debug(&mut self, field: &tracing::field::Field, v: &dyn std::fmt::Debug) {
+                    if field.name() == "message" {
+                        self.0 = format!("{:?}", v);
+                    }
+                }
+            }
+            let mut message = Message(String::new());
+            event.record(&mut message);
+            self.names.lock().unwrap().push(message.0);
+        }
+        fn enter(&self, _: &Id) {}
+        fn exit(&self, _: &Id) {}
+    }
+
+    #[test]
+    fn tracing_test() {
+        let recorder = Recorder::default();
+        let names = Arc::clone(&recorder.names);
+        tracing::subscriber::with_default(recorder, || {
+            let mut buf = VecCopy::with_type::<u8>();
+            buf.extend_from_slice(&vec![0u8; super::LARGE_COPY_BYTES]);
+            let _ = buf.clone();
+        });
+        let names = names.lock().unwrap();
+        assert!(names.iter().any(|name| name == "reallocating buffer"));
+        assert_eq!(
+            names.iter().filter(|name| *name == "copying bytes").count(),
+            2
+        );
+    }
+}
@@ -6,6 +6,7 @@
 
 use crate::bytes::*;
 use dyn_derive::dyn_trait_method;
+use std::cmp::Ordering;
 use std::fmt;
 use std::hash::{Hash, Hasher};
 use std::mem::ManuallyDrop;
@@ -34,12 +35,34 @@ pub trait PartialEqBytes: PartialEq {
 
 pub trait EqBytes: PartialEqBytes + Eq {}
 
+pub trait PartialOrdBytes: PartialOrd {
+    #[dyn_trait_method]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering>;
+    //unsafe fn partial_cmp_bytes(a: &[u8], b: &[u8]) -> Option<Ordering>;
+}
+
+pub trait OrdBytes: Ord {
+    #[dyn_trait_method]
+    fn cmp(&self, other: &Self) -> Ordering;
+    //unsafe fn cmp_bytes(a: &[u8], b: &[u8]) -> Ordering;
+}
+
 pub trait HashBytes: Hash {
     #[dyn_trait_method]
     fn hash<H: Hasher>(&self, state: &mut H);
     //unsafe fn hash_bytes(bytes: &[u8], state: &mut dyn Hasher);
 }
 
+pub trait DefaultBytes: Default {
+    /// Write the default value into the given bytes without dropping them.
+    unsafe fn default_into_raw_bytes(dst: &mut [u8]);
+}
+
+pub trait DisplayBytes: fmt::Display {
+    /// Format the value stored in the given bytes with its `Display` implementation.
+    unsafe fn display_bytes(bytes: &[u8], f: &mut fmt::Formatter) -> Result<(), fmt::Error>;
+}
+
 pub trait DebugBytes: fmt::Debug {
     #[dyn_trait_method]
     fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error>;
@@ -86,6 +109,22 @@ impl<T: PartialEq + 'static> PartialEqBytes for T {
 
 impl<T: PartialEqBytes + Eq> EqBytes for T {}
 
+impl<T: PartialOrd + 'static> PartialOrdBytes for T {
+    #[inline]
+    unsafe fn partial_cmp_bytes(a: &[u8], b: &[u8]) -> Option<Ordering> {
+        let (a, b): (&T, &T) = (Bytes::from_bytes(a), Bytes::from_bytes(b));
+        a.partial_cmp(b)
+    }
+}
+
+impl<T: Ord + 'static> OrdBytes for T {
+    #[inline]
+    unsafe fn cmp_bytes(a: &[u8], b: &[u8]) -> Ordering {
+        let (a, b): (&T, &T) = (Bytes::from_bytes(a), Bytes::from_bytes(b));
+        a.cmp(b)
+    }
+}
+
 impl<T: Hash + 'static> HashBytes for T {
     #[inline]
     unsafe fn hash_bytes(bytes: &[u8], mut state: &mut dyn Hasher) {
@@ -94,6 +133,22 @@ impl<T: Hash + 'static> HashBytes for T {
     }
 }
 
+impl<T: Default + 'static> DefaultBytes for T {
+    #[inline]
+    unsafe fn default_into_raw_bytes(dst: &mut [u8]) {
+        assert_eq!(dst.len(), std::mem::size_of::<T>());
+        std::ptr::write_unaligned(dst.as_mut_ptr() as *mut T, T::default());
+    }
+}
+
+impl<T: fmt::Display + 'static> DisplayBytes for T {
+    #[inline]
+    unsafe fn display_bytes(bytes: &[u8], f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let typed_data: &T = Bytes::from_bytes(bytes);
+        fmt::Display::fmt(typed_data, f)
+    }
+}
+
 impl<T: fmt::Debug + 'static> DebugBytes for T {
     #[inline]
     unsafe fn fmt_bytes(bytes: &[u8], f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
@@ -106,9 +161,17 @@ pub(crate) type CloneFnType = unsafe fn(&[u8]) -> Box<[u8]>;
 pub(crate) type CloneFromFnType = unsafe fn(&mut [u8], &[u8]);
 pub(crate) type CloneIntoRawFnType = unsafe fn(&[u8], &mut [u8]);
 pub(crate) type EqFnType = unsafe fn(&[u8], &[u8]) -> bool;
+pub(crate) type PartialCmpFnType = unsafe fn(&[u8], &[u8]) -> Option<Ordering>;
+pub(crate) type CmpFnType = unsafe fn(&[u8], &[u8]) -> Ordering;
 pub(crate) type HashFnType = unsafe fn(&[u8], &mut dyn Hasher);
 pub(crate) type FmtFnType = unsafe fn(&[u8], &mut fmt::Formatter) -> Result<(), fmt::Error>;
 pub(crate) type DropFnType = unsafe fn(&mut [u8]);
+pub(crate) type DefaultFnType = unsafe fn(&mut [u8]);
+#[cfg(feature = "typed_serde")]
+pub(crate) type SerializeFnType = unsafe fn(&[u8]) -> &dyn erased_serde::Serialize;
+#[cfg(feature = "typed_serde")]
+pub(crate) type DeserializeFnType =
+    fn(&mut dyn erased_serde::Deserializer) -> Result<crate::VecCopy, erased_serde::Error>;
 
 macro_rules! impl_fn_wrapper {
     (derive() struct $fn:ident ( $fn_type:ident )) => {
@@ -153,6 +216,13 @@ macro_rules! impl_fn_wrapper {
 impl_fn_wrapper!(derive(Copy, Clone) struct CloneFn(CloneFnType));
 impl_fn_wrapper!(derive(Copy, Clone) struct CloneFromFn(CloneFromFnType));
 impl_fn_wrapper!(derive(Copy, Clone) struct EqFn(EqFnType));
+impl_fn_wrapper!(derive(Copy, Clone) struct PartialCmpFn(PartialCmpFnType));
+impl_fn_wrapper!(derive(Copy, Clone) struct CmpFn(CmpFnType));
 impl_fn_wrapper!(derive(Copy, Clone) struct HashFn(HashFnType));
 impl_fn_wrapper!(derive(Copy, Clone) struct FmtFn(FmtFnType));
 impl_fn_wrapper!(derive(Copy, Clone) struct DropFn(DropFnType));
+impl_fn_wrapper!(derive(Copy, Clone) struct DefaultFn(DefaultFnType));
+#[cfg(feature = "typed_serde")]
+impl_fn_wrapper!(derive(Copy, Clone) struct SerializeFn(SerializeFnType));
+#[cfg(feature = "typed_serde")]
+impl_fn_wrapper!(derive(Copy, Clone) struct DeserializeFn(DeserializeFnType));
@@ -0,0 +1,243 @@
+//! Element-wise serialization of buffers.
+//!
+//! By default buffers are serialized as raw bytes, which is compact but neither human-readable
+//! nor independent of the memory layout of the element type. This module serializes buffers of
+//! types registered with [`register_serde_type`] as a typed sequence of elements instead, using
+//! the `Serialize` and `Deserialize` implementations of the element type. For instance a buffer
+//! of structs is serialized into JSON as an array of objects.
+//!
+//! The functions in this module are intended to be used with the `#[serde(with = "...")]`
+//! attribute:
+//!
+//! ```
+//! use data_buffer::{typed_serde, StableType, VecCopy};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+//! struct Point {
+//!     x: f32,
+//!     y: f32,
+//! }
+//!
+//! unsafe impl StableType for Point {
+//!     const STABLE_NAME: &'static str = "my_crate::Point";
+//! }
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Mesh {
+//!     #[serde(with = "typed_serde")]
+//!     vertices: VecCopy,
+//! }
+//!
+//! typed_serde::register_serde_type::<Point>();
+//! let mesh = Mesh { vertices: VecCopy::from_vec(vec![Point { x: 1.0, y: 2.0 }]) };
+//! let json = serde_json::to_string(&mesh).unwrap();
+//! assert_eq!(
+//!     json,
+//!     r#"{"vertices":{"element_type":"my_crate::Point","data":[{"x":1.0,"y":2.0}]}}"#
+//! );
+//! let mesh: Mesh = serde_json::from_str(&json).unwrap();
+//! assert_eq!(mesh.vertices.as_slice::<Point>().unwrap(), &[Point { x: 1.0, y: 2.0 }]);
+//! ```
+
+use std::fmt;
+
+use serde::de::{self, DeserializeOwned, DeserializeSeed, Deserializer};
+use serde::ser::{self, SerializeSeq, SerializeStruct, Serializer};
+use serde::Serialize;
+
+use crate::bytes::Bytes;
+use crate::stable_id;
+use crate::traits::{DeserializeFn, SerializeFn};
+use crate::{StableType, StableTypeId, VecCopy};
+
+const FIELDS: &[&str] = &["element_type", "data"];
+
+/// Register the type `T` in the global type registry (see [`register_type`]) together with its
+/// `Serialize` and `Deserialize` implementations, which makes buffers of this type serializable
+/// with the functions in this module.
+///
+/// [`register_type`]: crate::register_type
+pub fn register_serde_type<T>() -> StableTypeId
+where
+    T: StableType + Serialize + DeserializeOwned,
+{
+    stable_id::register_serde_fns::<T>(
+        SerializeFn(serialize_element::<T>),
+        DeserializeFn(deserialize_elements::<T>),
+    )
+}
+
+/// Interpret the given bytes as a serializable element.
+unsafe fn serialize_element<T: Serialize + 'static>(bytes: &[u8]) -> &dyn erased_serde::Serialize {
+    let element: &T = Bytes::from_bytes(bytes);
+    element
+}
+
+/// Deserialize a sequence of elements into a new buffer.
+fn deserialize_elements<T: StableType + DeserializeOwned>(
+    deserializer: &mut dyn erased_serde::Deserializer,
+) -> Result<VecCopy, erased_serde::Error> {
+    let elements: Vec<T> = erased_serde::deserialize(deserializer)?;
+    Ok(VecCopy::from_vec(elements))
+}
+
+/// Serialize the given buffer as a sequence of elements.
+///
+/// The element type must be registered with [`register_serde_type`].
+pub fn serialize<S: Serializer>(buf: &VecCopy, serializer: S) -> Result<S::Ok, S::Error> {
+    let ty = buf
+        .stable_type_id()
+        .and_then(StableTypeId::registered)
+        .ok_or_else(|| {
+            ser::Error::custom(format_args!(
+                "element type `{}` is not registered",
+                buf.element_type_name()
+            ))
+        })?;
+    let (SerializeFn(serialize_element), _) = ty.serde.ok_or_else(|| {
+        ser::Error::custom(format_args!(
+            "element type `{}` is not registered for element-wise serialization",
+            ty.name
+        ))
+    })?;
+
+    /// The elements of a buffer.
+    struct Elements<'a> {
+        buf: &'a VecCopy,
+        serialize_element: unsafe fn(&[u8]) -> &dyn erased_serde::Serialize,
+    }
+
+    impl Serialize for Elements<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut seq = serializer.serialize_seq(Some(self.buf.len()))?;
+            for bytes in self.buf.byte_chunks() {
+                // This is safe since the function was registered for the stored element type.
+                seq.serialize_element(unsafe { (self.serialize_element)(bytes) })?;
+            }
+            seq.end()
+        }
+    }
+
+    let mut state = serializer.serialize_struct("VecCopy", FIELDS.len())?;
+    state.serialize_field("element_type", ty.name)?;
+    state.serialize_field(
+        "data",
+        &Elements {
+            buf,
+            serialize_element,
+        },
+    )?;
+    state.end()
+}
+
+/// Deserialize a buffer from a sequence of elements.
+///
+/// The element type must be registered with [`register_serde_type`], and must precede the
+/// elements in the serialized data.
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<VecCopy, D::Error> {
+    /// Deserializes the elements given the function registered for their type.
+    struct Elements(DeserializeFn);
+
+    impl<'de> DeserializeSeed<'de> for Elements {
+        type Value = VecCopy;
+        fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<VecCopy, D::Error> {
+            let mut erased = <dyn erased_serde::Deserializer>::erase(deserializer);
+            (self.0).0(&mut erased).map_err(de::Error::custom)
+        }
+    }
+
+    /// Get the element deserialization function registered for the type with the given name.
+    fn elements<E: de::Error>(name: &str) -> Result<Elements, E> {
+        StableTypeId::from_name(name)
+            .registered()
+            .and_then(|ty| ty.serde)
+            .map(|(_, deserialize)| Elements(deserialize))
+            .ok_or_else(|| {
+                E::custom(format_args!(
+                    "element type `{}` is not registered for element-wise deserialization",
+                    name
+                ))
+            })
+    }
+
+    struct VecCopyVisitor;
+
+    impl<'de> de::Visitor<'de> for VecCopyVisitor {
+        type Value = VecCopy;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("struct VecCopy")
+        }
+
+        fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<VecCopy, A::Error> {
+            let name: String = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+            seq.next_element_seed(elements(&name)?)?
+                .ok_or_else(|| de::Error::invalid_length(1, &self))
+        }
+
+        fn visit_map<A: de::MapAccess<'de>>(self, mut map: A) -> Result<VecCopy, A::Error> {
+            match map.next_key::<String>()?.as_deref() {
+                Some("element_type") => {}
+                Some(_) => return Err(de::Error::custom("expected `element_type` first")),
+                None => return Err(de::Error::missing_field("element_type")),
+            }
+            let name: String = map.next_value()?;
+            match map.next_key::<String>()?.as_deref() {
+                Some("data") => {}
+                Some(key) => return Err(de::Error::unknown_field(key, FIELDS)),
+                None => return Err(de::Error::missing_field("data")),
+            }
+            map.next_value_seed(elements(&name)?)
+        }
+    }
+
+    deserializer.deserialize_struct("VecCopy", FIELDS, VecCopyVisitor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+    struct Particle {
+        id: u32,
+        mass: f32,
+    }
+
+    unsafe impl StableType for Particle {
+        const STABLE_NAME: &'static str = "data_buffer::typed_serde::tests::Particle";
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct System {
+        #[serde(with = "super")]
+        particles: VecCopy,
+    }
+
+    #[test]
+    fn typed_serde_test() {
+        let particles = vec![Particle { id: 1, mass: 2.5 }, Particle { id: 2, mass: 0.5 }];
+        let system = System {
+            particles: VecCopy::from_vec(particles.clone()),
+        };
+        assert!(serde_json::to_string(&system).is_err());
+
+        register_serde_type::<Particle>();
+        let json = serde_json::to_string(&system).unwrap();
+        assert!(json.contains(r#"[{"id":1,"mass":2.5},{"id":2,"mass":0.5}]"#));
+        let new_system: System = serde_json::from_str(&json).unwrap();
+        assert_eq!(new_system, system);
+
+        let bytes = bincode::serialize(&system).unwrap();
+        let new_system: System = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(new_system, system);
+
+        // The element type must be known before the elements.
+        let reordered = r#"{"particles":{"data":[],"element_type":"u8"}}"#;
+        assert!(serde_json::from_str::<System>(reordered).is_err());
+    }
+}
@@ -24,13 +24,11 @@ pub struct VecClone {
 
 impl Clone for VecClone {
     fn clone(&self) -> Self {
-        let data_clone = |bytes: &[u8]| {
-            let mut new_data = bytes.to_vec();
+        let data_clone = |new_data: &mut [u8]| {
             self.data
                 .byte_chunks()
                 .zip(new_data.chunks_exact_mut(self.data.element_size()))
                 .for_each(|(src, dst)| unsafe { self.clone_from_fn.0(dst, src) });
-            new_data
         };
         VecClone {
             data: ManuallyDrop::new(self.data.clone_with(data_clone)),
@@ -144,7 +142,8 @@ impl VecClone {
             let diff = new_len - self.len();
             self.data.reserve_bytes(diff * size_t);
             for _ in 0..diff {
-                self.data.push(value.clone());
+                // This is safe since this container takes care of dropping its elements.
+                unsafe { self.data.push_non_copy(value.clone()) };
             }
         } else {
             // Drop trailing elements manually.
@@ -191,7 +190,8 @@ impl VecClone {
     /// returned.
     #[inline]
     pub fn push<T: Elem>(&mut self, element: T) -> Option<&mut Self> {
-        if let Some(_) = self.data.push(element) {
+        // This is safe since this container takes care of dropping its elements.
+        if let Some(_) = unsafe { self.data.push_non_copy(element) } {
             Some(self)
         } else {
             None
@@ -343,6 +343,17 @@ impl VecClone {
         self.data.get_mut::<T>(i)
     }
 
+    /// Overwrite the `i`'th element of the buffer with the given value, dropping the previous
+    /// element.
+    ///
+    /// Returns `None` if `T` doesn't match the internal type or if `i` is out of bounds, in which
+    /// case `value` is dropped and the buffer is left unchanged.
+    #[inline]
+    pub fn set<T: Elem>(&mut self, i: usize, value: T) -> Option<&mut Self> {
+        *self.get_mut::<T>(i)? = value;
+        Some(self)
+    }
+
     /// Move bytes to this buffer.
     ///
     /// The given buffer must have the same underlying type as `self`.
@@ -431,6 +442,7 @@ impl VecClone {
             data,
             element_size,
             element_type_id,
+            ..
         } = &**data;
         data.chunks_exact(*element_size)
             .map(move |bytes| unsafe { CloneValueRef::from_raw_parts(bytes, *element_type_id) })
@@ -471,6 +483,7 @@ impl VecClone {
             data,
             element_size,
             element_type_id,
+            ..
         } = &mut **data;
         data.chunks_exact_mut(*element_size)
             .map(move |bytes| unsafe {
@@ -522,6 +535,21 @@ mod tests {
     use std::mem::size_of;
     use std::rc::Rc;
 
+    #[test]
+    fn set_test() {
+        let old = Rc::new(1u8);
+        let new = Rc::new(2u8);
+        let mut buf = VecClone::from(vec![Rc::clone(&old)]);
+        assert!(buf.set(0, Rc::new(3u16)).is_none());
+        assert!(buf.set(1, Rc::clone(&new)).is_none());
+        assert_eq!(Rc::strong_count(&new), 1);
+
+        buf.set(0, Rc::clone(&new)).unwrap();
+        assert_eq!(Rc::strong_count(&old), 1);
+        assert_eq!(Rc::strong_count(&new), 2);
+        assert_eq!(buf.get_ref::<Rc<u8>>(0).unwrap(), &new);
+    }
+
     #[test]
     fn clone_from_test() {
         //use std::collections::HashSet;
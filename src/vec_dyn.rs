@@ -33,13 +33,11 @@ impl<V> Drop for VecDyn<V> {
 
 impl<V: HasClone> Clone for VecDyn<V> {
     fn clone(&self) -> Self {
-        let data_clone = |bytes: &[u8]| {
-            let mut new_data = bytes.to_vec();
+        let data_clone = |new_data: &mut [u8]| {
             self.data
                 .byte_chunks()
                 .zip(new_data.chunks_exact_mut(self.data.element_size()))
                 .for_each(|(src, dst)| unsafe { self.vtable.1.clone_from_fn()(dst, src) });
-            new_data
         };
         VecDyn {
             data: ManuallyDrop::new(self.data.clone_with(data_clone)),
@@ -138,7 +136,8 @@ impl<V> VecDyn<V> {
     /// returned.
     #[inline]
     pub fn push<T: Elem>(&mut self, element: T) -> Option<&mut Self> {
-        if let Some(_) = self.data.push(element) {
+        // This is safe since this container takes care of dropping its elements.
+        if let Some(_) = unsafe { self.data.push_non_copy(element) } {
             Some(self)
         } else {
             None
@@ -266,6 +265,17 @@ impl<V> VecDyn<V> {
         self.data.get_mut::<T>(i)
     }
 
+    /// Overwrite the `i`'th element of the buffer with the given value, dropping the previous
+    /// element.
+    ///
+    /// Returns `None` if `T` doesn't match the internal type or if `i` is out of bounds, in which
+    /// case `value` is dropped and the buffer is left unchanged.
+    #[inline]
+    pub fn set_as<T: Elem>(&mut self, i: usize, value: T) -> Option<&mut Self> {
+        *self.get_mut_as::<T>(i)? = value;
+        Some(self)
+    }
+
     /// Move bytes to this buffer.
     ///
     /// The given buffer must have the same underlying type as `self`.
@@ -372,6 +382,7 @@ impl<V> VecDyn<V> {
             data,
             element_size,
             element_type_id,
+            ..
         } = &**data;
         data.chunks_exact(*element_size)
             .map(move |bytes| unsafe { ValueRef::from_raw_parts(bytes, *element_type_id, vtable) })
@@ -402,6 +413,7 @@ impl<V> VecDyn<V> {
             data,
             element_size,
             element_type_id,
+            ..
         } = &mut **data;
         data.chunks_exact_mut(*element_size)
             .map(move |bytes| unsafe { ValueMut::from_raw_parts(bytes, *element_type_id, vtable) })
@@ -455,7 +467,8 @@ impl<V: HasClone> VecDyn<V> {
             let diff = new_len - self.len();
             self.data.reserve_bytes(diff * size_t);
             for _ in 0..diff {
-                self.data.push(value.clone());
+                // This is safe since this container takes care of dropping its elements.
+                unsafe { self.data.push_non_copy(value.clone()) };
             }
         } else {
             // Drop trailing elements manually.
@@ -656,6 +669,21 @@ mod tests {
         assert_eq!(a.element_type_id(), TypeId::of::<Rc<u8>>());
     }
 
+    #[test]
+    fn set_as_test() {
+        let old = Rc::new(1u8);
+        let new = Rc::new(2u8);
+        let mut buf = VecDynAll::from(vec![Rc::clone(&old), Rc::clone(&old)]);
+        assert!(buf.set_as(0, 2u8).is_none());
+        assert!(buf.set_as(2, Rc::clone(&new)).is_none());
+        assert_eq!(Rc::strong_count(&old), 3);
+
+        buf.set_as(1, Rc::clone(&new)).unwrap();
+        assert_eq!(Rc::strong_count(&old), 2);
+        assert_eq!(Rc::strong_count(&new), 2);
+        assert_eq!(buf.get_ref_as::<Rc<u8>>(1).unwrap(), &new);
+    }
+
     /// Test resizing a buffer.
     #[test]
     fn resize() {
@@ -864,6 +892,19 @@ mod tests {
         }
     }
 
+    /// Test that pushed heap-owning values are moved into the buffer and dropped exactly once.
+    #[test]
+    fn push_drop_test() {
+        let rc = Rc::new(String::from("owned"));
+        let mut buf = VecDynAll::with_type::<Rc<String>>();
+        buf.push(Rc::clone(&rc)).unwrap();
+        buf.resize(3, Rc::clone(&rc)).unwrap();
+        assert_eq!(Rc::strong_count(&rc), 4);
+        assert_eq!(buf.len(), 3);
+        drop(buf);
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+
     /// Test appending to a buffer from another buffer.
     #[test]
     fn append_test() {
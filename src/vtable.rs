@@ -0,0 +1,342 @@
+//! This module defines the table of optional trait functions that can be attached to a `VecCopy`.
+//!
+//! Since `VecCopy` only requires its elements to be `Copy` and `PartialEq`, it cannot know at
+//! construction whether the element type implements other standard traits. Equality is captured
+//! at construction, while other functions are captured on demand via the typed `with_*` methods
+//! on `VecCopy`, after which the corresponding trait implementations on the buffer dispatch to
+//! the element's own implementation.
+
+use std::any::{Any, TypeId};
+use std::cmp::Ordering;
+use std::convert::TryInto;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::mem::size_of;
+
+use crate::traits::*;
+use crate::{Elem, StableTypeId};
+
+/// A table of optional functions for the element type of a `VecCopy`.
+#[derive(Copy, Clone, Debug, Default)]
+pub(crate) struct CopyVTable {
+    /// Element-wise equality.
+    ///
+    /// This is `None` if elements are to be compared by their bytes, which is the case for types
+    /// whose equality is known to coincide with byte equality, and for buffers whose element type
+    /// is only known at runtime.
+    pub(crate) eq: Option<EqFn>,
+    /// Element-wise hashing.
+    ///
+    /// This is `None` if elements are to be hashed by their bytes, which is consistent with
+    /// equality for types whose equality coincides with byte equality.
+    pub(crate) hash: Option<HashFn>,
+    /// Element-wise `Debug` formatting.
+    ///
+    /// If this is `None`, elements are formatted as arrays of bytes.
+    pub(crate) fmt: Option<FmtFn>,
+    /// Element-wise `Display` formatting.
+    ///
+    /// If this is `None`, elements of non-numeric types are displayed with `fmt` if it was
+    /// captured, and as hexadecimal bytes otherwise.
+    pub(crate) display: Option<FmtFn>,
+    /// Element-wise partial ordering.
+    pub(crate) partial_cmp: Option<PartialCmpFn>,
+    /// Element-wise total ordering.
+    pub(crate) cmp: Option<CmpFn>,
+    /// Writes the default element into a byte slice.
+    ///
+    /// If this is `None`, the buffer cannot be grown without an explicit value.
+    pub(crate) default: Option<DefaultFn>,
+    /// Whether the element type is known to be `Send`.
+    pub(crate) send: bool,
+    /// Whether the element type is known to be `Sync`.
+    pub(crate) sync: bool,
+}
+
+/// The maximum number of elements printed by the `Debug` implementations of buffers.
+pub(crate) const DEBUG_MAX_ELEMENTS: usize = 32;
+
+/// Returns `true` if `T` is `P`, or an array of up to 32 values of type `P`.
+fn is_array_of<T: Any, P: Any>() -> bool {
+    let id = TypeId::of::<T>();
+    if id == TypeId::of::<P>() {
+        return true;
+    }
+    if size_of::<P>() == 0 || !size_of::<T>().is_multiple_of(size_of::<P>()) {
+        return false;
+    }
+    macro_rules! lengths {
+        ($($n:literal),*) => {
+            match size_of::<T>() / size_of::<P>() {
+                $($n => id == TypeId::of::<[P; $n]>(),)*
+                _ => false,
+            }
+        };
+    }
+    lengths!(
+        1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25,
+        26, 27, 28, 29, 30, 31, 32
+    )
+}
+
+/// Returns `true` if the equality of values of type `T` is equivalent to the equality of their
+/// bytes.
+///
+/// This is the case for primitive types without padding whose values each have a unique
+/// representation, and for arrays of them.
+pub(crate) fn is_bytewise_eq<T: Any>() -> bool {
+    macro_rules! arrays_of {
+        ($($t:ty),*) => { false $(|| is_array_of::<T, $t>())* };
+    }
+    arrays_of!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, bool, char)
+}
+
+/// Returns `true` if `T` is a primitive numeric, boolean or character type, or an array of them.
+pub(crate) fn is_primitive<T: Any>() -> bool {
+    is_bytewise_eq::<T>() || is_array_of::<T, f32>() || is_array_of::<T, f64>()
+}
+
+macro_rules! impl_hash_floats {
+    ($name:ident, $t:ty) => {
+        /// Hash elements made up of floats consistently with their equality.
+        ///
+        /// Since `0.0 == -0.0`, zeros are hashed as positive zero. NaNs are not equal to
+        /// anything, so they are all hashed alike.
+        fn $name(bytes: &[u8], mut state: &mut dyn Hasher) {
+            for chunk in bytes.chunks_exact(size_of::<$t>()) {
+                let x = <$t>::from_ne_bytes(chunk.try_into().unwrap());
+                let canonical = if x.is_nan() {
+                    <$t>::NAN
+                } else if x == 0.0 {
+                    0.0
+                } else {
+                    x
+                };
+                canonical.to_bits().hash(&mut state);
+            }
+        }
+    };
+}
+
+impl_hash_floats!(hash_f32s, f32);
+impl_hash_floats!(hash_f64s, f64);
+
+impl CopyVTable {
+    /// Construct the default table for buffers of type `T`.
+    ///
+    /// No functions are captured, but primitive types are known to be `Send` and `Sync`.
+    #[inline]
+    pub(crate) fn of<T: Any>() -> Self {
+        let is_primitive = is_primitive::<T>();
+        CopyVTable {
+            send: is_primitive,
+            sync: is_primitive,
+            ..CopyVTable::default()
+        }
+    }
+
+    /// Construct the table for buffers of the `Elem` type `T`.
+    ///
+    /// This captures the `PartialEq` implementation of `T`, unless its equality is known to
+    /// coincide with byte equality. Floats and arrays of floats are hashed consistently with
+    /// their equality.
+    #[inline]
+    pub(crate) fn of_elem<T: Elem>() -> Self {
+        let hash = if is_array_of::<T, f32>() {
+            Some(HashFn(hash_f32s))
+        } else if is_array_of::<T, f64>() {
+            Some(HashFn(hash_f64s))
+        } else {
+            None
+        };
+        CopyVTable {
+            eq: if is_bytewise_eq::<T>() {
+                None
+            } else {
+                Some(EqFn(T::eq_bytes))
+            },
+            hash,
+            ..CopyVTable::of::<T>()
+        }
+    }
+
+    /// Construct the table for buffers of the type with the given `TypeId`.
+    ///
+    /// This is the table captured when the type was registered with `register_type`, or the
+    /// default table if the type is not registered.
+    #[inline]
+    pub(crate) fn of_type_id(type_id: TypeId) -> Self {
+        StableTypeId::of_type_id(type_id)
+            .and_then(StableTypeId::registered)
+            .map_or_else(CopyVTable::default, |ty| ty.vtable)
+    }
+
+    /// Compare two byte slices containing elements of the same type.
+    #[inline]
+    pub(crate) fn eq_bytes(&self, element_size: usize, a: &[u8], b: &[u8]) -> bool {
+        if a.len() != b.len() {
+            return false;
+        }
+        match self.eq {
+            Some(EqFn(eq)) => a
+                .chunks_exact(element_size)
+                .zip(b.chunks_exact(element_size))
+                // This is safe since the function was captured for the stored element type.
+                .all(|(a, b)| unsafe { eq(a, b) }),
+            None => a == b,
+        }
+    }
+
+    /// Choose between this and the given table, preferring the one with the strongest ordering,
+    /// and then the one with a captured equality.
+    #[inline]
+    pub(crate) fn ord_vtable<'a>(&'a self, other: &'a CopyVTable) -> &'a CopyVTable {
+        let strength = |vtable: &CopyVTable| {
+            (
+                vtable.cmp.is_some(),
+                vtable.partial_cmp.is_some(),
+                vtable.eq.is_some(),
+            )
+        };
+        if strength(self) >= strength(other) {
+            self
+        } else {
+            other
+        }
+    }
+
+    /// Lexicographically compare two byte slices containing elements of the same type.
+    ///
+    /// Elements are compared with the captured total ordering if any, then with the captured
+    /// partial ordering. Otherwise elements equal according to `eq_bytes` are equal, and other
+    /// elements are ordered by their bytes, or incomparable if their bytes are equal (like NaNs).
+    /// This keeps the ordering consistent with `eq_bytes`.
+    #[inline]
+    pub(crate) fn partial_cmp_bytes(
+        &self,
+        element_size: usize,
+        a: &[u8],
+        b: &[u8],
+    ) -> Option<Ordering> {
+        if self.cmp.is_some() {
+            return Some(self.cmp_bytes(element_size, a, b));
+        }
+        match self.partial_cmp {
+            Some(PartialCmpFn(partial_cmp)) => {
+                for (a, b) in a
+                    .chunks_exact(element_size)
+                    .zip(b.chunks_exact(element_size))
+                {
+                    // This is safe since the function was captured for the stored element type.
+                    match unsafe { partial_cmp(a, b) } {
+                        Some(Ordering::Equal) => {}
+                        non_eq => return non_eq,
+                    }
+                }
+                a.len().partial_cmp(&b.len())
+            }
+            None => {
+                for (a, b) in a
+                    .chunks_exact(element_size)
+                    .zip(b.chunks_exact(element_size))
+                {
+                    if self.eq_bytes(element_size, a, b) {
+                        continue;
+                    }
+                    match a.cmp(b) {
+                        Ordering::Equal => return None,
+                        non_eq => return Some(non_eq),
+                    }
+                }
+                a.len().partial_cmp(&b.len())
+            }
+        }
+    }
+
+    /// Lexicographically compare two byte slices containing elements of the same type.
+    ///
+    /// Elements are compared with the captured total ordering if any, and by their bytes
+    /// otherwise.
+    #[inline]
+    pub(crate) fn cmp_bytes(&self, element_size: usize, a: &[u8], b: &[u8]) -> Ordering {
+        match self.cmp {
+            Some(CmpFn(cmp)) => {
+                for (a, b) in a
+                    .chunks_exact(element_size)
+                    .zip(b.chunks_exact(element_size))
+                {
+                    // This is safe since the function was captured for the stored element type.
+                    match unsafe { cmp(a, b) } {
+                        Ordering::Equal => {}
+                        non_eq => return non_eq,
+                    }
+                }
+                a.len().cmp(&b.len())
+            }
+            None => a.cmp(b),
+        }
+    }
+
+    /// Hash a byte slice containing elements of the stored type.
+    ///
+    /// Elements are hashed with the captured hash function if any, which is captured at
+    /// construction for floats. Otherwise, if elements are compared with a captured `PartialEq`
+    /// function, equal elements may have different bytes (e.g. padding), so only the length is
+    /// hashed.
+    #[inline]
+    pub(crate) fn hash_bytes<H: Hasher>(&self, element_size: usize, bytes: &[u8], state: &mut H) {
+        match self.hash {
+            Some(HashFn(hash)) => {
+                bytes.len().hash(state);
+                let state: &mut dyn Hasher = state;
+                for elem_bytes in bytes.chunks_exact(element_size) {
+                    // This is safe since the function was captured for the stored element type.
+                    unsafe { hash(elem_bytes, state) };
+                }
+            }
+            None if self.eq.is_some() => bytes.len().hash(state),
+            None => bytes.hash(state),
+        }
+    }
+}
+
+/// A helper for formatting a single element with a captured `Debug` implementation.
+struct DebugElement<'a> {
+    fmt: FmtFnType,
+    bytes: &'a [u8],
+}
+
+impl fmt::Debug for DebugElement<'_> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // This is safe since the function was captured for the stored element type.
+        unsafe { (self.fmt)(self.bytes, f) }
+    }
+}
+
+/// A helper for formatting a bounded list of elements stored in a byte slice.
+///
+/// At most `DEBUG_MAX_ELEMENTS` elements are printed followed by an ellipsis.
+pub(crate) struct DebugElements<'a> {
+    pub(crate) vtable: &'a CopyVTable,
+    pub(crate) element_size: usize,
+    pub(crate) bytes: &'a [u8],
+}
+
+impl fmt::Debug for DebugElements<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut list = f.debug_list();
+        let chunks = self.bytes.chunks_exact(self.element_size);
+        let len = chunks.len();
+        for bytes in chunks.take(DEBUG_MAX_ELEMENTS) {
+            match self.vtable.fmt {
+                Some(FmtFn(fmt)) => list.entry(&DebugElement { fmt, bytes }),
+                None => list.entry(&bytes),
+            };
+        }
+        if len > DEBUG_MAX_ELEMENTS {
+            list.entry(&format_args!("..."));
+        }
+        list.finish()
+    }
+}
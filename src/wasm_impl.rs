@@ -0,0 +1,151 @@
+//! This module implements conversions between buffers and JavaScript typed arrays.
+//!
+//! Buffers of fixed size integer and floating point types correspond to the typed array of the
+//! same element type, for instance `Vec<f32>` to `Float32Array` and `Vec<i64>` to
+//! `BigInt64Array`. Buffers of other types have no typed array equivalent.
+
+use std::any::TypeId;
+
+use js_sys::wasm_bindgen::{JsCast, JsValue};
+use js_sys::{
+    BigInt64Array, BigUint64Array, Float32Array, Float64Array, Int16Array, Int32Array, Int8Array,
+    Object, Uint16Array, Uint32Array, Uint8Array, Uint8ClampedArray,
+};
+
+use crate::VecCopy;
+
+/// Call `$f` with the buffer slice and the typed array type matching the element type of `$buf`,
+/// or evaluate to `None` if there is no such typed array.
+macro_rules! dispatch_typed_array {
+    ($buf:expr, $f:ident) => {{
+        let buf = $buf;
+        let type_id = buf.element_type_id();
+        dispatch_typed_array!(@types buf, type_id, $f;
+            i8 => Int8Array, i16 => Int16Array, i32 => Int32Array, i64 => BigInt64Array,
+            u8 => Uint8Array, u16 => Uint16Array, u32 => Uint32Array, u64 => BigUint64Array,
+            f32 => Float32Array, f64 => Float64Array
+        )
+    }};
+    (@types $buf:ident, $type_id:ident, $f:ident; $($t:ty => $array:ident),*) => {
+        $(
+            if $type_id == TypeId::of::<$t>() {
+                Some(Object::from($f!($array, $buf.as_slice::<$t>().unwrap())))
+            } else
+        )* {
+            None
+        }
+    };
+}
+
+impl VecCopy {
+    /// Copy the elements of this buffer into a new JavaScript typed array.
+    ///
+    /// Returns `None` if the element type has no corresponding typed array.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use data_buffer::VecCopy;
+    /// use js_sys::Float32Array;
+    /// use js_sys::wasm_bindgen::JsCast;
+    /// let buf = VecCopy::from_vec(vec![1.0f32, 2.0, 3.0]);
+    /// let array: Float32Array = buf.to_typed_array().unwrap().unchecked_into();
+    /// assert_eq!(array.length(), 3);
+    /// assert_eq!(VecCopy::from_typed_array(&array).unwrap(), buf);
+    /// ```
+    pub fn to_typed_array(&self) -> Option<Object> {
+        macro_rules! copy {
+            ($array:ident, $slice:expr) => {
+                $array::from($slice)
+            };
+        }
+        dispatch_typed_array!(self, copy)
+    }
+
+    /// Construct a JavaScript typed array viewing the elements of this buffer without copying.
+    ///
+    /// Returns `None` if the element type has no corresponding typed array.
+    ///
+    /// # Safety
+    ///
+    /// The view points directly into WebAssembly memory, so it must not be used after the buffer
+    /// is modified or dropped. Any allocation may grow the WebAssembly memory, which also
+    /// invalidates the view, so it should be consumed before allocating again.
+    pub unsafe fn as_typed_array_view(&self) -> Option<Object> {
+        macro_rules! view {
+            ($array:ident, $slice:expr) => {
+                $array::view($slice)
+            };
+        }
+        dispatch_typed_array!(self, view)
+    }
+
+    /// Construct a buffer by copying the elements of the given JavaScript typed array.
+    ///
+    /// A `Uint8ClampedArray` produces a buffer of `u8` elements. Returns `None` if `value` is not
+    /// a typed array.
+    pub fn from_typed_array(value: &JsValue) -> Option<VecCopy> {
+        macro_rules! from_typed_arrays {
+            ($($array:ident),*) => {
+                $(
+                    if let Some(array) = value.dyn_ref::<$array>() {
+                        return Some(VecCopy::from_vec(array.to_vec()));
+                    }
+                )*
+            };
+        }
+        from_typed_arrays!(
+            Int8Array,
+            Int16Array,
+            Int32Array,
+            BigInt64Array,
+            Uint8Array,
+            Uint8ClampedArray,
+            Uint16Array,
+            Uint32Array,
+            BigUint64Array,
+            Float32Array,
+            Float64Array
+        );
+        None
+    }
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[wasm_bindgen_test]
+    fn typed_array_test() {
+        let buf = VecCopy::from_vec(vec![1.0f32, -2.5, 3.0]);
+        let array: Float32Array = buf.to_typed_array().unwrap().dyn_into().unwrap();
+        assert_eq!(array.to_vec(), vec![1.0, -2.5, 3.0]);
+        assert_eq!(VecCopy::from_typed_array(&array).unwrap(), buf);
+
+        let buf = VecCopy::from_vec(vec![i64::MIN, 0, i64::MAX]);
+        let array: BigInt64Array = buf.to_typed_array().unwrap().dyn_into().unwrap();
+        assert_eq!(array.to_vec(), vec![i64::MIN, 0, i64::MAX]);
+        assert_eq!(VecCopy::from_typed_array(&array).unwrap(), buf);
+
+        // Clamped arrays are read as bytes.
+        let array = Uint8ClampedArray::from(&[1u8, 255][..]);
+        let buf = VecCopy::from_typed_array(&array).unwrap();
+        assert_eq!(buf.as_slice::<u8>().unwrap(), &[1, 255]);
+
+        assert!(VecCopy::from_vec(vec![[0u8; 2]]).to_typed_array().is_none());
+        assert!(VecCopy::from_vec(vec![true]).to_typed_array().is_none());
+        assert!(VecCopy::from_typed_array(&JsValue::from(1.0)).is_none());
+        assert!(VecCopy::from_typed_array(&Object::new()).is_none());
+    }
+
+    #[wasm_bindgen_test]
+    fn typed_array_view_test() {
+        let buf = VecCopy::from_vec(vec![1u16, 2, 3]);
+        let array: Uint16Array = unsafe { buf.as_typed_array_view() }
+            .unwrap()
+            .dyn_into()
+            .unwrap();
+        assert_eq!(array.to_vec(), vec![1, 2, 3]);
+        assert!(unsafe { VecCopy::from_vec(vec!['a']).as_typed_array_view() }.is_none());
+    }
+}
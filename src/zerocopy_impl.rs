@@ -0,0 +1,108 @@
+//! This module implements safe conversions between buffers and bytes with `zerocopy`.
+//!
+//! Types implementing both `AsBytes` and `FromBytes` have no padding and no invalid bit
+//! patterns, so buffers and slices of such types can be constructed from and viewed as untrusted
+//! bytes (e.g. network or IPC payloads) without `unsafe`. Borrowed views check the alignment of
+//! the given bytes at run time.
+
+use std::mem::size_of;
+
+use zerocopy::{AsBytes, FromBytes};
+
+use crate::{Elem, SliceCopy, SliceCopyMut, VecCopy};
+
+impl VecCopy {
+    /// Construct a buffer with elements of type `T` by copying the given bytes.
+    ///
+    /// The bytes need not be aligned for `T`. Returns `None` if the number of bytes is not a
+    /// multiple of the size of `T`.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let payload = [1u8, 0, 2, 0, 3];
+    /// let buf = VecCopy::from_bytes_checked::<u16>(&payload[..4]).unwrap();
+    /// assert_eq!(buf.len(), 2);
+    /// assert!(VecCopy::from_bytes_checked::<u16>(&payload).is_none());
+    /// ```
+    pub fn from_bytes_checked<T: Elem + AsBytes + FromBytes>(bytes: &[u8]) -> Option<Self> {
+        if size_of::<T>() == 0 || bytes.len() % size_of::<T>() != 0 {
+            return None;
+        }
+        let mut buf = VecCopy::with_capacity::<T>(bytes.len() / size_of::<T>());
+        buf.data.extend_from_slice(bytes);
+        Some(buf)
+    }
+
+    /// Mutably borrow the buffer data as bytes.
+    ///
+    /// This is a safe alternative to `as_bytes_mut`, since any bytes written are valid elements of
+    /// type `T`. Returns `None` if `T` doesn't match the element type of the buffer.
+    #[inline]
+    pub fn as_bytes_mut_checked<T: Elem + AsBytes + FromBytes>(&mut self) -> Option<&mut [u8]> {
+        self.as_mut_slice::<T>().map(AsBytes::as_bytes_mut)
+    }
+}
+
+impl<'a> SliceCopy<'a> {
+    /// Borrow the given bytes as a slice with elements of type `T` without copying.
+    ///
+    /// Returns `None` if the number of bytes is not a multiple of the size of `T`, or if the
+    /// bytes are not aligned for `T`.
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::SliceCopy;
+    /// let values = [1u32, 2];
+    /// let bytes = zerocopy::AsBytes::as_bytes(&values[..]);
+    /// let slice = SliceCopy::from_bytes_checked::<u32>(bytes).unwrap();
+    /// assert_eq!(slice.as_slice::<u32>(), Some(&values[..]));
+    /// assert!(SliceCopy::from_bytes_checked::<u32>(&bytes[1..5]).is_none());
+    /// ```
+    #[inline]
+    pub fn from_bytes_checked<T: Elem + AsBytes + FromBytes>(bytes: &'a [u8]) -> Option<Self> {
+        T::slice_from(bytes).map(SliceCopy::from_slice)
+    }
+}
+
+impl<'a> SliceCopyMut<'a> {
+    /// Mutably borrow the given bytes as a slice with elements of type `T` without copying.
+    ///
+    /// Returns `None` if the number of bytes is not a multiple of the size of `T`, or if the
+    /// bytes are not aligned for `T`.
+    #[inline]
+    pub fn from_bytes_checked<T: Elem + AsBytes + FromBytes>(bytes: &'a mut [u8]) -> Option<Self> {
+        T::mut_slice_from(bytes).map(SliceCopyMut::from_slice_mut)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zerocopy_bytes_test() {
+        let mut values = [0x0102_0304u32, 0x0506_0708, 0];
+        let bytes = values.as_bytes().to_vec();
+
+        let mut buf = VecCopy::from_bytes_checked::<u32>(&bytes).unwrap();
+        assert_eq!(buf.as_slice::<u32>(), Some(&values[..]));
+        assert!(VecCopy::from_bytes_checked::<u32>(&bytes[..5]).is_none());
+        // Unaligned bytes are copied into an aligned buffer.
+        let buf_unaligned = VecCopy::from_bytes_checked::<[u8; 4]>(&bytes[1..9]).unwrap();
+        assert_eq!(buf_unaligned.len(), 2);
+
+        assert!(buf.as_bytes_mut_checked::<i32>().is_none());
+        buf.as_bytes_mut_checked::<u32>().unwrap()[8..].copy_from_slice(&[1, 1, 1, 1]);
+        assert_eq!(buf.get::<u32>(2), Some(0x0101_0101));
+
+        let value_bytes = values.as_bytes_mut();
+        assert!(SliceCopy::from_bytes_checked::<u32>(&value_bytes[1..5]).is_none());
+        assert!(SliceCopy::from_bytes_checked::<u32>(&value_bytes[..6]).is_none());
+        let slice = SliceCopy::from_bytes_checked::<u32>(&value_bytes[4..]).unwrap();
+        assert_eq!(slice.len(), 2);
+        let mut slice_mut = SliceCopyMut::from_bytes_checked::<u32>(value_bytes).unwrap();
+        slice_mut.as_mut_slice::<u32>().unwrap()[2] = 7;
+        assert_eq!(values[2], 7);
+    }
+}
@@ -0,0 +1,124 @@
+//! This module implements compression of buffers in the raw binary format with `zstd`.
+//!
+//! Numeric data typically compresses very well, which makes it worthwhile to compress large
+//! buffers before storing or sending them. Only the data is compressed, while the header stays
+//! uncompressed so that the element type and number of elements can be inspected cheaply.
+
+use std::io::{self, Read, Write};
+
+use crate::checksum;
+use crate::raw::{Header, CHECKSUM_FLAG, COMPRESSION_NONE, COMPRESSION_ZSTD, HEADER_SIZE};
+use crate::trace::trace_span;
+use crate::{DecodeError, VecCopy};
+
+impl VecCopy {
+    /// Write this buffer to the given writer in the raw binary format with the data compressed
+    /// at the given zstd compression `level`.
+    ///
+    /// A `level` of `0` selects the default compression level. A checksum of the data is always
+    /// included. The element type must be registered with [`register_type`], otherwise an error
+    /// of kind `InvalidInput` is returned.
+    ///
+    /// [`register_type`]: crate::register_type
+    ///
+    /// # Examples
+    /// ```
+    /// use data_buffer::VecCopy;
+    /// let buf = VecCopy::from_vec(vec![0.0f64; 1000]);
+    /// let mut bytes = Vec::new();
+    /// buf.encode_compressed(&mut bytes, 0).unwrap();
+    /// assert!(bytes.len() < 100);
+    /// let decoded = VecCopy::decode_compressed(&mut bytes.as_slice()).unwrap();
+    /// assert_eq!(decoded, buf);
+    /// ```
+    pub fn encode_compressed<W: Write>(&self, writer: &mut W, level: i32) -> io::Result<()> {
+        trace_span!("encode_compressed", num_bytes = self.data.len(), level);
+        let mut header = self.raw_header()?;
+        header[7] = COMPRESSION_ZSTD | CHECKSUM_FLAG;
+        let mut encoder = zstd::stream::write::Encoder::new(Vec::new(), level)?;
+        encoder.write_all(&self.data)?;
+        encoder.write_all(&checksum::crc32(&self.data).to_le_bytes())?;
+        let compressed = encoder.finish()?;
+        writer.write_all(&header)?;
+        writer.write_all(&(compressed.len() as u64).to_le_bytes())?;
+        writer.write_all(&compressed)
+    }
+
+    /// Read a buffer in the raw binary format from the given reader, decompressing the data if
+    /// it was compressed.
+    ///
+    /// This reads both data written with [`encode_compressed`] and uncompressed data written with
+    /// [`encode_raw`], and reports errors in the same way as [`decode_raw`].
+    ///
+    /// [`encode_compressed`]: VecCopy::encode_compressed
+    /// [`encode_raw`]: VecCopy::encode_raw
+    /// [`decode_raw`]: VecCopy::decode_raw
+    pub fn decode_compressed<R: Read>(reader: &mut R) -> io::Result<VecCopy> {
+        trace_span!("decode_compressed");
+        let invalid = |err: DecodeError| io::Error::new(io::ErrorKind::InvalidData, err);
+        let mut header = [0u8; HEADER_SIZE];
+        reader.read_exact(&mut header)?;
+        let header = Header::decode(&header).map_err(invalid)?;
+        match header.compression {
+            COMPRESSION_NONE => VecCopy::decode_raw_data(&header, reader),
+            COMPRESSION_ZSTD => {
+                let mut compressed_size = [0u8; 8];
+                reader.read_exact(&mut compressed_size)?;
+                let compressed = reader.take(u64::from_le_bytes(compressed_size));
+                let mut decoder = zstd::stream::read::Decoder::new(compressed)?;
+                let buf = VecCopy::decode_raw_data(&header, &mut decoder)?;
+                // Reject data that decompresses to more bytes than stated in the header.
+                if decoder.read(&mut [0u8])? != 0 {
+                    return Err(invalid(DecodeError::InvalidLength {
+                        len: header.len,
+                        element_size: header.element_size,
+                    }));
+                }
+                Ok(buf)
+            }
+            tag => Err(invalid(DecodeError::UnsupportedCompression(tag))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compression_test() {
+        let buf = VecCopy::from_vec((0..10_000u32).map(|i| i / 100).collect::<Vec<_>>());
+        let mut compressed = Vec::new();
+        buf.encode_compressed(&mut compressed, 3).unwrap();
+        assert!(compressed.len() < buf.as_bytes().len() / 10);
+        assert_eq!(compressed[7], COMPRESSION_ZSTD | CHECKSUM_FLAG);
+        assert_eq!(
+            VecCopy::decode_compressed(&mut compressed.as_slice()).unwrap(),
+            buf
+        );
+
+        // Uncompressed data is also accepted.
+        let mut raw = Vec::new();
+        buf.encode_raw(&mut raw).unwrap();
+        assert_eq!(
+            VecCopy::decode_compressed(&mut raw.as_slice()).unwrap(),
+            buf
+        );
+
+        // Compressed data is rejected by the uncompressed decoder.
+        let err = VecCopy::decode_raw(&mut compressed.as_slice()).unwrap_err();
+        assert_eq!(
+            *err.into_inner().unwrap().downcast::<DecodeError>().unwrap(),
+            DecodeError::UnsupportedCompression(COMPRESSION_ZSTD)
+        );
+
+        // The header must match the decompressed data.
+        let mut corrupt = compressed.clone();
+        corrupt[24] -= 1;
+        let err = VecCopy::decode_compressed(&mut corrupt.as_slice()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        // Truncated data.
+        let err = VecCopy::decode_compressed(&mut &compressed[..compressed.len() - 4]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+}